@@ -5,16 +5,15 @@ use tmag5273::{
     types::{Axis, DeviceVersion, TMag5273Error},
     InterruptConfig, TMag5273,
 };
-use utils::setup_i2c_and_gpio;
+use utils::{setup_i2c_and_gpio, Delay};
 
 /// Simple Main Function to run the example
-/// This will set up the Hall effect Sensor to send out a high on the interrupt pin when the sensor has finished its scan
-/// The loop will then get the X, Y and Z axis readings
-/// The loop constantly polls the Interrupt pin
+/// This will set up the Hall effect Sensor to pull the interrupt pin low when the sensor has finished its scan
+/// The loop will then wait on the interrupt pin and get the X, Y and Z axis readings
 fn main() -> Result<(), Box<dyn Error>> {
     println!("Running Example 2 Interrupts!");
 
-    let (i2c, mut pin) = setup_i2c_and_gpio()?; // Set up your I2C and gpio pin
+    let (i2c, pin) = setup_i2c_and_gpio()?; // Set up your I2C and gpio pin
 
     let mut mag_sensor = TMag5273::new(i2c, DeviceVersion::TMAG5273B1)?.init_default()?;
     print_device_stats(&mut mag_sensor)?;
@@ -24,33 +23,14 @@ fn main() -> Result<(), Box<dyn Error>> {
         panic!("Failed to set up device: {err:?}");
     }
 
+    // The acquisition owns the interrupt pin and waits for it rather than polling the sensor
+    let mut acquisition = mag_sensor.interrupt_acquisition(pin)?;
+    let mut delay = Delay::new();
     loop {
-        if check_pin(&mut pin) {
-            println!("Sensor has Finished its scan!!");
-            //Get the X Threshold and print it out
-            match mag_sensor.get_mag_threshold(Axis::X) {
-                Ok(x_threshold) => println!("X Threshold: {x_threshold:?}"),
-                Err(err) => println!("Failed to get X Threshold: {err:?}"),
-            }
-            println!("Sensor has now finished its scan!!");
-            // Get the data
-            match mag_sensor.get_all_data() {
-                Ok(data) => println!("Data: {data:?}"),
-                Err(err) => println!("Failed to get data: {err:?}"),
-            }
-        }
-    }
-}
-
-fn check_pin<Pin>(pin: &mut Pin) -> bool
-where
-    Pin: embedded_hal::digital::InputPin,
-{
-    match pin.is_low() {
-        Ok(is_low) => is_low,
-        Err(err) => {
-            eprintln!("Failed to read pin: {err:?}");
-            false
+        // Give up on a scan after a second, for example if the sensor has been reset
+        match acquisition.wait_for_data(&mut delay, 1_000_000) {
+            Ok(data) => println!("Sensor has finished its scan!! Data: {data:?}"),
+            Err(err) => println!("Failed to get data: {err:?}"),
         }
     }
 }
@@ -61,8 +41,10 @@ where
 {
     mag_sensor.set_interrupts(InterruptConfig {
         interrupt_mode: InterruptMode::INTInterrupt,
-        int_pin_mode: INTPinMode::Pulsed,
+        // A latched interrupt is held until the data is read, so it can not be missed
+        int_pin_mode: INTPinMode::Latched,
         threshold_interrupt_enabled: true,
+        conversion_complete_interrupt_enabled: true,
         ..Default::default()
     })?;
 
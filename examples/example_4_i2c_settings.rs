@@ -26,21 +26,11 @@ fn main() -> Result<(), Box<dyn Error>> {
     let i2c_address = register.i2c_address().value();
     println!("Original I2C Address: {i2c_address:x}");
     println!("Setting I2C Address to: {new_i2c_address:x}");
-    let register_update = register
-        .with_i2c_address(arbitrary_int::u7::new(new_i2c_address))
-        .with_i2c_address_update_enabled(true);
 
-    mag_sensor.set_config_register(register_update)?;
+    // The driver moves to the new address, and checks the device answers there
+    mag_sensor.change_address(new_i2c_address)?;
 
-    // Drop the TMag5273 instance
-    drop(mag_sensor);
-
-    // Set everything up again with the new address of the device
-    let i2c = setup_i2c()?;
-    println!("Getting Address from Sensor on new Address {new_i2c_address:x}");
-    let mut tmag5273 = TMag5273::new_with_address(i2c, new_i2c_address, DeviceVersion::TMAG5273A1)?;
-
-    let register: I2cAddressRegister = tmag5273.get_config_register()?;
+    let register: I2cAddressRegister = mag_sensor.get_config_register()?;
     println!("New I2C Address: {:x}", register.i2c_address().value());
     Ok(())
 }
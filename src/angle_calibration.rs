@@ -0,0 +1,233 @@
+//! # Angle Calibration
+//!
+//! The CORDIC angle calculation assumes the two channels selected by `ANGLE_EN` see a sine and cosine of equal
+//! amplitude centred on zero. A real magnet is rarely that ideal, so the device can scale one channel with the gain
+//! in MAG_GAIN_CONFIG and shift each channel with the offsets in MAG_OFFSET_CONFIG_1/2. The [AngleCalibrator] works
+//! these out from the raw channel data recorded while the magnet turns through at least one full rotation.
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+
+use crate::{
+    conversions,
+    registers::{Angle, MagGainChannel, SensorConfig2Register},
+    types::{TMag5273Error, TMag5273RawChannelData},
+    TMag5273,
+};
+
+/// Each offset LSB is the range / 2048, while each result LSB is the range / 32768.
+const RESULT_CODES_PER_OFFSET_LSB: f32 = 16.0;
+
+/// Number of 45 degree sectors the samples must cover to count as a full rotation.
+const SECTORS: usize = 8;
+
+/// Gain, gain channel and offsets for the angle calculation, alongside the angle error they leave.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct AngleCalibration {
+    /// The channels of the angle calculation the calibration was made for
+    pub angle: Angle,
+    /// The channel scaled by the gain, the one with the larger amplitude
+    pub gain_channel: MagGainChannel,
+    /// The gain as written to MAG_GAIN_CONFIG, mapped 0 - 255 = 0 - 100%, where 0 is no scaling
+    pub gain: u8,
+    /// Offset of the first channel as written to MAG_OFFSET_CONFIG_1, in steps of the range / 2048
+    pub first_offset: i8,
+    /// Offset of the second channel as written to MAG_OFFSET_CONFIG_2, in steps of the range / 2048
+    pub second_offset: i8,
+    /// The largest angle error of the samples without any correction, in degrees
+    pub error_before: f32,
+    /// The largest angle error of the samples with the gain and offsets applied, in degrees
+    pub error_after: f32,
+}
+
+/// Records up to `N` samples of the two channels of an angle calculation and fits an [AngleCalibration] to them.
+///
+/// ## Example
+///
+/// ```Rust
+/// let mut calibrator = AngleCalibrator::<128>::new(Angle::XY);
+/// while !calibrator.is_full() {
+///     // Turn the magnet through at least one full rotation
+///     calibrator.add_sample(mag5273.get_raw_all_data()?)?;
+///     delay.delay_ms(10);
+/// }
+/// let calibration = calibrator.fit()?;
+/// mag5273.set_angle_calibration(&calibration)?;
+/// println!("{} -> {} degrees", calibration.error_before, calibration.error_after);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AngleCalibrator<const N: usize> {
+    angle: Angle,
+    samples: [[i16; 2]; N],
+    len: usize,
+}
+
+impl<const N: usize> AngleCalibrator<N> {
+    /// Creates an empty calibrator for the channels of the angle calculation.
+    pub fn new(angle: Angle) -> Self {
+        Self {
+            angle,
+            samples: [[0; 2]; N],
+            len: 0,
+        }
+    }
+
+    /// Adds the two channels of the angle calculation from a sample. Throws an OutOfRange error once the buffer
+    /// is full or if a code does not fit the result registers, or a WrongMode error if the calibrator was created
+    /// with the angle calculation disabled.
+    pub fn add_sample(&mut self, data: TMag5273RawChannelData) -> Result<(), TMag5273Error> {
+        // The device works on the result registers, where X is not inverted
+        let register = |code: i32| i16::try_from(code).map_err(|_| TMag5273Error::OutOfRange);
+        let channels = match self.angle {
+            Angle::Disabled => return Err(TMag5273Error::WrongMode),
+            Angle::XY => [register(-data.x.code)?, register(data.y.code)?],
+            Angle::YZ => [register(data.y.code)?, register(data.z.code)?],
+            Angle::XZ => [register(-data.x.code)?, register(data.z.code)?],
+        };
+        let sample = self
+            .samples
+            .get_mut(self.len)
+            .ok_or(TMag5273Error::OutOfRange)?;
+        *sample = channels;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Number of samples recorded.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if no sample has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Checks if the buffer is full.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Discards the samples recorded so far.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Works out the offsets which centre both channels and the gain which matches their amplitudes, from the
+    /// extremes of each channel. Throws a CalibrationFailed error if the samples do not cover a full rotation, or
+    /// an OutOfRange error if an offset is too large for its register.
+    pub fn fit(&self) -> Result<AngleCalibration, TMag5273Error> {
+        let samples = &self.samples[..self.len];
+        let [(first_centre, first_amplitude), (second_centre, second_amplitude)] =
+            [0, 1].map(|channel| {
+                let (min, max) = samples
+                    .iter()
+                    .fold((i16::MAX, i16::MIN), |(min, max), sample| {
+                        (min.min(sample[channel]), max.max(sample[channel]))
+                    });
+                let (min, max) = (f32::from(min), f32::from(max));
+                ((max + min) / 2.0, (max - min) / 2.0)
+            });
+        if first_amplitude <= 0.0 || second_amplitude <= 0.0 {
+            return Err(TMag5273Error::CalibrationFailed);
+        }
+
+        // Where each sample lies on the ideal circle, which must be visited all the way round
+        let ideal = |sample: &[i16; 2]| {
+            libm::atan2f(
+                (f32::from(sample[1]) - second_centre) / second_amplitude,
+                (f32::from(sample[0]) - first_centre) / first_amplitude,
+            )
+        };
+        let mut visited = [false; SECTORS];
+        for sample in samples {
+            let turn = ideal(sample) / (2.0 * core::f32::consts::PI) + 0.5;
+            visited[((turn * SECTORS as f32) as usize).min(SECTORS - 1)] = true;
+        }
+        if visited.contains(&false) {
+            return Err(TMag5273Error::CalibrationFailed);
+        }
+
+        let to_offset = |centre: f32| {
+            let offset = libm::roundf(-centre / RESULT_CODES_PER_OFFSET_LSB);
+            match (f32::from(i8::MIN)..=f32::from(i8::MAX)).contains(&offset) {
+                true => Ok(offset as i8),
+                false => Err(TMag5273Error::OutOfRange),
+            }
+        };
+        let first_offset = to_offset(first_centre)?;
+        let second_offset = to_offset(second_centre)?;
+
+        // The gain can only scale down, so it goes on the channel with the larger amplitude
+        let (gain_channel, ratio) = match first_amplitude > second_amplitude {
+            true => (MagGainChannel::First, second_amplitude / first_amplitude),
+            false => (MagGainChannel::Second, first_amplitude / second_amplitude),
+        };
+        // A gain of 0 is interpreted by the device as 1
+        let gain = match libm::roundf(ratio * 256.0) {
+            gain if gain >= 256.0 => 0,
+            gain => gain.max(1.0) as u8,
+        };
+
+        let corrected = |sample: &[i16; 2]| {
+            let gain = match gain {
+                0 => 1.0,
+                gain => f32::from(gain) / 256.0,
+            };
+            let mut first =
+                f32::from(sample[0]) + f32::from(first_offset) * RESULT_CODES_PER_OFFSET_LSB;
+            let mut second =
+                f32::from(sample[1]) + f32::from(second_offset) * RESULT_CODES_PER_OFFSET_LSB;
+            match gain_channel {
+                MagGainChannel::First => first *= gain,
+                MagGainChannel::Second => second *= gain,
+            }
+            libm::atan2f(second, first)
+        };
+        let uncorrected =
+            |sample: &[i16; 2]| libm::atan2f(f32::from(sample[1]), f32::from(sample[0]));
+        let largest_error = |angle: &dyn Fn(&[i16; 2]) -> f32| {
+            samples
+                .iter()
+                .map(|sample| angle_difference(angle(sample), ideal(sample)).abs())
+                .fold(0.0f32, f32::max)
+                .to_degrees()
+        };
+
+        Ok(AngleCalibration {
+            angle: self.angle,
+            gain_channel,
+            gain,
+            first_offset,
+            second_offset,
+            error_before: largest_error(&uncorrected),
+            error_after: largest_error(&corrected),
+        })
+    }
+}
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Writes the gain channel, gain and offsets of an angle calibration to the device. Throws a WrongMode error
+    /// if the device is not set to the angle calculation the calibration was made for.
+    pub fn set_angle_calibration(
+        &mut self,
+        calibration: &AngleCalibration,
+    ) -> Result<(), TMag5273Error> {
+        let config: SensorConfig2Register = self.config_register()?;
+        for (register, value) in conversions::angle_calibration_writes(config, calibration)? {
+            self.write_register(register, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Difference between two angles from atan2 in radians, wrapped to ±PI.
+fn angle_difference(a: f32, b: f32) -> f32 {
+    use core::f32::consts::PI;
+    match a - b {
+        difference if difference > PI => difference - 2.0 * PI,
+        difference if difference < -PI => difference + 2.0 * PI,
+        difference => difference,
+    }
+}
@@ -0,0 +1,762 @@
+//! # Async Driver
+//!
+//! An async variant of the [TMag5273](crate::TMag5273) driver built on the
+//! [embedded-hal-async](https://docs.rs/embedded-hal-async) traits, for use with executors such as Embassy.
+//! It is enabled with the `async` feature.
+//!
+//! The API mirrors the blocking driver, the only difference being that every method which talks to the device
+//! is an `async fn`. The configuration structs outlined in config.rs and the raw registers outlined in the
+//! registers module are shared between both drivers, as is all the conversion and validation logic.
+mod config;
+mod encoder;
+mod health;
+mod interrupt;
+mod recovery;
+mod registers;
+#[cfg(feature = "uom")]
+mod units;
+
+pub use interrupt::InterruptAcquisition;
+
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::i2c::{I2c, SevenBitAddress};
+
+use crate::conversions;
+use crate::recovery::AppliedConfig;
+use crate::registers::*;
+use crate::shadow::ShadowRegisters;
+use crate::types::{
+    Axis, CacheMode, DeviceVersion, RawMagneticSample, RawTemperature, TMag5273Error,
+    TMag5273RawChannelData, TMag5273RawEnabledData, TMag5273RawFastReadData, TMag5273RawFrameData,
+    TMag5273Sample,
+};
+#[cfg(feature = "float")]
+use crate::types::{
+    MagneticChannelOffset, TMag5273ChannelData, TMag5273EnabledData, TMag5273FastReadData,
+    TMag5273FrameData,
+};
+
+pub struct TMag5273<I2C> {
+    device_version: DeviceVersion,
+    /// The concrete I²C device implementation.
+    i2c: I2C,
+    /// The I²C device address.
+    address: SevenBitAddress,
+    /// Copy of the configuration registers, kept in step with every write made through the driver.
+    shadow: ShadowRegisters,
+    /// Where the configuration used to validate and convert reads comes from.
+    cache_mode: CacheMode,
+    /// Whether the health of the device is checked after every data read.
+    health_check: bool,
+    /// The configuration last written through the driver, restored after a power-on-reset.
+    applied: AppliedConfig,
+    /// Whether a power-on-reset is detected and recovered from while reading data.
+    auto_recovery: bool,
+    /// Set count of the last sample read through get_sampled_data or get_raw_sampled_data.
+    last_set_count: Option<u8>,
+}
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Create an instance of the TMag5273xx device.
+    ///
+    /// The device will be checked for connectivity by reading the manufacturer ID.
+    ///
+    /// ## Example
+    ///
+    /// ```Rust
+    /// async fn run(i2c_bus: I2c) -> Result<(), TMag5273Error> {
+    ///     let mut mag5273 = TMag5273::new(i2c_bus, DeviceVersion::TMAG5273B1)
+    ///         .await?
+    ///         .init_default()
+    ///         .await?;
+    ///     let data = mag5273.get_all_data().await?;
+    /// }
+    /// ```
+    pub async fn new(i2c: I2C, version: DeviceVersion) -> Result<TMag5273<I2C>, TMag5273Error> {
+        TMag5273::new_with_address(i2c, version.get_default_address(), version).await
+    }
+
+    /// Create an instance of a TMag5273xx device with a specific address.
+    pub async fn new_with_address(
+        i2c: I2C,
+        address: SevenBitAddress,
+        version: DeviceVersion,
+    ) -> Result<TMag5273<I2C>, TMag5273Error> {
+        let mut device = TMag5273 {
+            i2c,
+            address,
+            device_version: version,
+            shadow: ShadowRegisters::default(),
+            cache_mode: CacheMode::default(),
+            health_check: false,
+            applied: AppliedConfig::default(),
+            auto_recovery: false,
+            last_set_count: None,
+        };
+        device.get_manufacturer_id().await?;
+        device.sync_shadow().await?;
+        device.sync_applied().await?;
+        device.is_correct_device_version().await?;
+        Ok(device)
+    }
+
+    /// Check if the device is connected.
+    pub async fn is_connected(&mut self) -> bool {
+        self.get_manufacturer_id().await.is_ok()
+    }
+
+    /// Get the manufacturer ID.
+    pub async fn get_manufacturer_id(&mut self) -> Result<u16, TMag5273Error> {
+        let mut data: [u8; 2] = [0x00, 0x00];
+        self.read_registers(TMAG5273Register::ManufacturerIdLsb, &mut data)
+            .await?;
+        conversions::check_manufacturer_id(data)
+    }
+
+    /// Returns the Device ID of the device, otherwise an Error if the device is not connected or the register is malformed.
+    pub async fn get_device_id(&mut self) -> Result<DeviceId, TMag5273Error> {
+        let device_id_register = self.get_config_register::<DeviceIdRegister>().await?;
+        conversions::convert_device_id(device_id_register)
+    }
+
+    /// Changes the I2C address of the device while keeping the driver valid. The new address is written with the
+    /// update enabled, then the driver moves to it and confirms the device answers there by reading the manufacturer ID.
+    /// If the device does not answer, the old address is written back at the new address, in case the device moved
+    /// but the confirmation was lost, then the driver goes back to the old address and the error is returned. If that
+    /// write fails as well, the device may be left at either address.
+    /// The address must be a 7-bit address outside the reserved ranges, otherwise an OutOfRange error is thrown.
+    /// If another device already answers at the address nothing is written and an AddressInUse error is thrown.
+    ///
+    /// NOTE: the device returns to its factory address after a power cycle.
+    ///
+    /// ### Arguments
+    ///
+    /// * `address` - The new 7-bit I2C address
+    pub async fn change_address(&mut self, address: SevenBitAddress) -> Result<(), TMag5273Error> {
+        let new_address = conversions::check_i2c_address(address)?;
+        if address != self.address && self.i2c.read(address, &mut [0x00]).await.is_ok() {
+            return Err(TMag5273Error::AddressInUse);
+        }
+        let register = self
+            .get_config_register::<I2cAddressRegister>()
+            .await?
+            .with_i2c_address(new_address)
+            .with_i2c_address_update_enabled(true);
+        self.set_config_register(register).await?;
+
+        let previous_address = self.address;
+        self.address = address;
+        if let Err(err) = self.get_manufacturer_id().await {
+            // The original error is returned whether or not the device could be moved back
+            if let Ok(command) = conversions::address_move(previous_address) {
+                let _ = self.i2c.write(address, &command).await;
+            }
+            self.address = previous_address;
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Sets where the driver gets the configuration it needs to validate and convert a measurement.
+    ///
+    /// * `ReadThrough` - the default, the configuration registers are read back before every measurement
+    /// * `Cached` - the driver's copy of the configuration is used. The copy is updated by every register written
+    ///   through the driver, and the POR flag is read alongside the data so that a device which has reset is
+    ///   noticed and the copy refreshed
+    /// * `Unchecked` - the driver's copy of the configuration is used without any check, for the lowest bus load
+    ///
+    /// NOTE: the cached modes assume nothing but this driver changes the configuration of the device.
+    /// Call refresh_cache after the configuration has been changed any other way.
+    ///
+    /// ## Example
+    ///
+    /// ```Rust
+    /// mag5273.set_cache_mode(CacheMode::Cached);
+    /// loop {
+    ///     let data = mag5273.get_all_data().await?;
+    /// }
+    /// ```
+    pub fn set_cache_mode(&mut self, mode: CacheMode) {
+        self.cache_mode = mode;
+    }
+
+    /// Returns the current cache mode.
+    pub fn get_cache_mode(&self) -> CacheMode {
+        self.cache_mode
+    }
+
+    /// Reads the configuration registers back from the device into the driver's copy.
+    pub async fn refresh_cache(&mut self) -> Result<(), TMag5273Error> {
+        self.sync_shadow().await
+    }
+
+    /// Set the threshold for the device. The threshold is a floating point value, which is converted to a
+    /// raw value and set on the device.
+    /// The threshold is set for the X, Y and Z axis.
+    /// The range is calculated based on the range of the device.
+    ///
+    /// ### Arguments
+    ///
+    /// * `threshold` - The threshold value in mT
+    /// * `axis` - The axis to set the threshold for
+    #[cfg(feature = "float")]
+    pub async fn set_mag_threshold(
+        &mut self,
+        threshold: f32,
+        axis: Axis,
+    ) -> Result<(), TMag5273Error> {
+        let config = self.config_register::<SensorConfig2Register>().await?;
+        let (register, value) =
+            conversions::mag_threshold_write(threshold, axis, config, self.device_version)?;
+        self.write_register(register, value).await
+    }
+
+    /// Get the threshold for the device. The threshold is a floating point value, which is converted from a
+    /// raw value sent from the device.
+    /// The range is calculated based on the range that the device is currently configured for.
+    ///
+    /// Output in mT.
+    ///
+    #[cfg(feature = "float")]
+    pub async fn get_mag_threshold(&mut self, axis: Axis) -> Result<f32, TMag5273Error> {
+        let config = self.config_register::<SensorConfig2Register>().await?;
+        let (register, range) =
+            conversions::mag_threshold_register(axis, config, self.device_version);
+        let mut buf: [u8; 1] = [0x00];
+        self.read_registers(register, &mut buf).await?;
+        Ok(conversions::mag_threshold_from_raw(buf[0], range))
+    }
+
+    /// Set the temperature threshold for the device in degrees Celsius. When the temperature channel is enabled,
+    /// crossing the threshold can be used to assert an interrupt.
+    /// The threshold has a resolution of 8 degC and is rounded to the nearest step.
+    ///
+    /// ### Arguments
+    ///
+    /// * `threshold` - The threshold in degC, from -41 to 167. `None` disables the threshold comparison.
+    #[cfg(feature = "float")]
+    pub async fn set_temperature_threshold(
+        &mut self,
+        threshold: Option<f32>,
+    ) -> Result<(), TMag5273Error> {
+        let t_config = self.config_register::<TConfigRegister>().await?;
+        self.set_config_register(conversions::temp_threshold_config(threshold, t_config)?)
+            .await
+    }
+
+    /// Get the temperature threshold for the device in degrees Celsius.
+    /// Returns `None` if the threshold comparison is disabled.
+    ///
+    /// Output in degC.
+    #[cfg(feature = "float")]
+    pub async fn get_temperature_threshold(&mut self) -> Result<Option<f32>, TMag5273Error> {
+        let t_config: TConfigRegister = self.config_register().await?;
+        conversions::temp_threshold_from_code(t_config.t_thr_config())
+    }
+
+    /// Set the magnetic gain for the device.
+    /// Gain value mapped 0 - 255 = 0 - 100%
+    pub async fn set_magnetic_gain(&mut self, gain: u8) -> Result<(), TMag5273Error> {
+        self.write_register(TMAG5273Register::MagGainConfig, gain)
+            .await
+    }
+
+    /// Get the magnetic gain for the device.
+    /// Gain value mapped 0 - 255 = 0 - 100%
+    pub async fn get_magnetic_gain(&mut self) -> Result<u8, TMag5273Error> {
+        let mut data: [u8; 1] = [0x00];
+        self.read_registers(TMAG5273Register::MagGainConfig, &mut data)
+            .await?;
+        Ok(data[0])
+    }
+
+    /// Writes the gain channel, gain and offsets of an angle calibration to the device. Throws a WrongMode error
+    /// if the device is not set to the angle calculation the calibration was made for.
+    #[cfg(feature = "float")]
+    pub async fn set_angle_calibration(
+        &mut self,
+        calibration: &crate::AngleCalibration,
+    ) -> Result<(), TMag5273Error> {
+        let config: SensorConfig2Register = self.config_register().await?;
+        for (register, value) in conversions::angle_calibration_writes(config, calibration)? {
+            self.write_register(register, value).await?;
+        }
+        Ok(())
+    }
+
+    /// Set the magnetic offset correction for the device in mT. The offset is added to the axis selected by
+    /// the angle calculation before the angle is calculated, so an axis reading +2 mT too high needs an offset of -2 mT.
+    /// The offset resolution is the range of the axis / 2048, e.g. 0.0195 mT for a 40 mT range, giving a
+    /// correction of -128 to +127 times the resolution. The offset is rounded to the nearest step, so reading it back
+    /// is accurate to within half of the resolution.
+    ///
+    /// NOTE: the angle must be set first, as it selects which axis and range the offset applies to.
+    ///
+    /// ### Arguments
+    ///
+    /// * `offset` - The offset in mT
+    /// * `mag_offset` - The axis of the angle calculation to set the offset for
+    #[cfg(feature = "float")]
+    pub async fn set_magnetic_offset(
+        &mut self,
+        offset: f32,
+        mag_offset: MagneticChannelOffset,
+    ) -> Result<(), TMag5273Error> {
+        let config: SensorConfig2Register = self.config_register().await?;
+        let (register, value) =
+            conversions::mag_offset_write(offset, mag_offset, config, self.device_version)?;
+        self.write_register(register, value).await
+    }
+
+    /// Get the magnetic offset correction for the device. The resolution is calculated from the
+    /// angle and range that the device is currently configured for.
+    ///
+    /// Output in mT.
+    #[cfg(feature = "float")]
+    pub async fn get_magnetic_offset(
+        &mut self,
+        mag_offset: MagneticChannelOffset,
+    ) -> Result<f32, TMag5273Error> {
+        let config: SensorConfig2Register = self.config_register().await?;
+        let (register, resolution) =
+            conversions::mag_offset_register(mag_offset, config, self.device_version)?;
+        let mut buf: [u8; 1] = [0x00];
+        self.read_registers(register, &mut buf).await?;
+        Ok(conversions::mag_offset_from_raw(buf[0], resolution))
+    }
+
+    /// Get the temperature of the device in degrees Celsius. This will
+    /// throw an error if the temperature channel is not enabled.
+    #[cfg(feature = "float")]
+    pub async fn get_temperature(&mut self) -> Result<f32, TMag5273Error> {
+        let data = self.read_temperature().await?;
+        Ok(conversions::convert_temp(data))
+    }
+
+    /// Get the temperature code of the device, without any floating point conversion. This will
+    /// throw an error if the temperature channel is not enabled.
+    pub async fn get_raw_temperature(&mut self) -> Result<RawTemperature, TMag5273Error> {
+        let data = self.read_temperature().await?;
+        Ok(RawTemperature {
+            code: u16::from_be_bytes(data),
+        })
+    }
+
+    /// Gets the specific axis channel data. This will throw an error if the channel is not enabled.
+    #[cfg(feature = "float")]
+    pub async fn get_mag_data(&mut self, axis: Axis) -> Result<f32, TMag5273Error> {
+        let (data, range) = self.read_mag_data(axis).await?;
+        Ok(conversions::convert_magnetism(axis, data, range.into()))
+    }
+
+    /// Gets the specific axis channel data as the code from the result registers, alongside the range it
+    /// is scaled by. This will throw an error if the channel is not enabled.
+    pub async fn get_raw_mag_data(
+        &mut self,
+        axis: Axis,
+    ) -> Result<RawMagneticSample, TMag5273Error> {
+        let (data, range) = self.read_mag_data(axis).await?;
+        Ok(conversions::raw_magnetic_sample(axis, data, range))
+    }
+
+    /// Get all the axis data from the device, alongside the temperature of the device
+    /// The data is returned as a TMag5273Data struct. If the correct Channels are not enabled,
+    /// an error is thrown. For any other set of channels use get_enabled_data.
+    #[cfg(feature = "float")]
+    pub async fn get_all_data(&mut self) -> Result<TMag5273ChannelData, TMag5273Error> {
+        let (data, xy_range, z_range) = self.read_all_data().await?;
+        Ok(conversions::convert_all_data(data, xy_range, z_range))
+    }
+
+    /// Gets all the axis data like get_all_data, with the calibration applied to the X, Y and Z axes.
+    #[cfg(feature = "float")]
+    pub async fn get_calibrated_data(
+        &mut self,
+        calibration: &crate::Calibration,
+    ) -> Result<TMag5273ChannelData, TMag5273Error> {
+        Ok(calibration.apply(self.get_all_data().await?))
+    }
+
+    /// Gets all the axis data like get_all_data, normalised to the reference temperature of the compensation.
+    /// This will throw a CompensationConflict error if the device compensates the magnet itself through
+    /// `mag_tempo`, so the correction is never applied twice.
+    #[cfg(feature = "float")]
+    pub async fn get_compensated_data(
+        &mut self,
+        compensation: &crate::TemperatureCompensation<'_>,
+    ) -> Result<TMag5273ChannelData, TMag5273Error> {
+        let config: DeviceConfig1Register = self.config_register().await?;
+        conversions::check_compensation_conflict(config)?;
+        Ok(compensation.compensate(self.get_all_data().await?))
+    }
+
+    /// Get all the axis data from the device, alongside the temperature of the device, without any floating
+    /// point conversion. If the correct Channels are not enabled, an error is thrown.
+    pub async fn get_raw_all_data(&mut self) -> Result<TMag5273RawChannelData, TMag5273Error> {
+        let (data, xy_range, z_range) = self.read_all_data().await?;
+        Ok(conversions::convert_raw_all_data(data, xy_range, z_range))
+    }
+
+    /// Get the data of the channels enabled on the device, in any magnetic channel mode and with or without the
+    /// temperature. Channels which are not enabled are `None`. Only the result registers from the first to the last
+    /// enabled channel are read, so e.g. in the XY mode the temperature and Z result registers are skipped.
+    /// If no channel is enabled, a ChannelDisabled error is thrown.
+    ///
+    /// ## Example
+    ///
+    /// ```Rust
+    /// let data = mag5273.get_enabled_data().await?;
+    /// if let (Some(x), Some(y)) = (data.x, data.y) {
+    ///     println!("X: {x} mT, Y: {y} mT");
+    /// }
+    /// ```
+    #[cfg(feature = "float")]
+    pub async fn get_enabled_data(&mut self) -> Result<TMag5273EnabledData, TMag5273Error> {
+        let data = self.get_raw_enabled_data().await?;
+        Ok(conversions::convert_enabled_data(data))
+    }
+
+    /// Get the data of the channels enabled on the device, without any floating point conversion.
+    /// See get_enabled_data.
+    pub async fn get_raw_enabled_data(&mut self) -> Result<TMag5273RawEnabledData, TMag5273Error> {
+        let channels = self.enabled_channels().await?;
+        self.read_enabled_data(channels).await
+    }
+
+    /// Get all the axis data from the device, alongside the temperature of the device, together with the set count
+    /// of the conversion it comes from. The set count is compared with the one of the previous sample read through
+    /// this method or get_raw_sampled_data, flagging whether the data is from the next conversion, the same conversion
+    /// again or whether conversions were missed in between. If the correct Channels are not enabled, an error is thrown.
+    #[cfg(feature = "float")]
+    pub async fn get_sampled_data(
+        &mut self,
+    ) -> Result<TMag5273Sample<TMag5273ChannelData>, TMag5273Error> {
+        let (data, xy_range, z_range, set_count) = self.read_sample().await?;
+        Ok(self.track_sample(
+            conversions::convert_all_data(data, xy_range, z_range),
+            set_count,
+        ))
+    }
+
+    /// Get all the axis data from the device, alongside the temperature of the device, together with the set count
+    /// of the conversion it comes from, without any floating point conversion. See get_sampled_data.
+    pub async fn get_raw_sampled_data(
+        &mut self,
+    ) -> Result<TMag5273Sample<TMag5273RawChannelData>, TMag5273Error> {
+        let (data, xy_range, z_range, set_count) = self.read_sample().await?;
+        Ok(self.track_sample(
+            conversions::convert_raw_all_data(data, xy_range, z_range),
+            set_count,
+        ))
+    }
+
+    /// Reads every result and status register, from the temperature up to the Device Status Register, in a single
+    /// transfer. Only the channels enabled on the device, and the angle and magnitude when the angle calculation is
+    /// enabled, are decoded, any other field is `None`. Unlike get_all_data no channel has to be enabled.
+    ///
+    /// ## Example
+    ///
+    /// ```Rust
+    /// let frame = mag5273.get_frame_data().await?;
+    /// if let (Some(angle), Some(magnitude)) = (frame.angle, frame.magnitude) {
+    ///     println!("{angle} deg at a magnitude of {magnitude}");
+    /// }
+    /// ```
+    #[cfg(feature = "float")]
+    pub async fn get_frame_data(&mut self) -> Result<TMag5273FrameData, TMag5273Error> {
+        let frame = self.get_raw_frame_data().await?;
+        Ok(conversions::convert_frame(frame))
+    }
+
+    /// Reads every result and status register in a single transfer, without any floating point conversion.
+    /// See get_frame_data.
+    pub async fn get_raw_frame_data(&mut self) -> Result<TMag5273RawFrameData, TMag5273Error> {
+        let mut configs: SensorConfigRegisters = self.sensor_config_registers().await?;
+        let mut t_config: TConfigRegister = self.config_register().await?;
+        let mut data = [0x00; conversions::FRAME_LEN];
+        if self
+            .read_results(TMAG5273Register::TMsbResult, &mut data)
+            .await?
+        {
+            configs = self.shadow.sensor_config();
+            t_config = self.shadow.t_config();
+        }
+        conversions::convert_raw_frame(data, configs, t_config, self.device_version)
+    }
+
+    /// Reads the enabled channels using one of the 1-byte I2C read modes, set through `i2c_read_mode` in the
+    /// DeviceConfig. No register address is written, the device sends the enabled channels in the T, X, Y, Z order
+    /// followed by the Conversion Status Register, which makes this the fastest way of polling the sensor.
+    /// The layout is taken from the driver's copy of the configuration whatever the cache mode, so nothing but the
+    /// frame is read. The configuration must therefore only be changed through the driver.
+    /// This will throw a WrongMode error if the device is in the standard 3-byte read mode.
+    #[cfg(feature = "float")]
+    pub async fn get_fast_read_data(&mut self) -> Result<TMag5273FastReadData, TMag5273Error> {
+        let data = self.get_fast_read_data_raw().await?;
+        Ok(conversions::convert_fast_read_data(data))
+    }
+
+    /// Reads the enabled channels using one of the 1-byte I2C read modes like get_fast_read_data, returning the data
+    /// without any floating point conversion.
+    pub async fn get_fast_read_data_raw(
+        &mut self,
+    ) -> Result<TMag5273RawFastReadData, TMag5273Error> {
+        let frame = conversions::FastReadFrame::new(
+            self.shadow.device_config_1(),
+            self.shadow.sensor_config(),
+            self.shadow.t_config(),
+            self.device_version,
+        )?;
+        let mut data = [0x00; conversions::MAX_FAST_READ_LEN];
+        let data = &mut data[..frame.len()];
+        self.i2c.read(self.address, data).await?;
+        let data = frame.decode(self.address, data)?;
+        // The frame already carries the diagnostic flag, so the full report is only read once it is set
+        if self.health_check && data.conversion_status.diagnostic_error() {
+            self.health().await?.check()?;
+        }
+        Ok(data)
+    }
+
+    /// Gets the measured/calculated angle in degrees of the enabled channel
+    /// This will throw an error if the channels are not enabled.
+    #[cfg(feature = "float")]
+    pub async fn get_angle(&mut self) -> Result<f32, TMag5273Error> {
+        let data = self.read_angle().await?;
+        Ok(conversions::convert_angle(data))
+    }
+
+    /// Gets the measured/calculated angle of the enabled channel in steps of 1/16 degree, below 5760.
+    /// This will throw an error if the channels are not enabled.
+    pub async fn get_raw_angle(&mut self) -> Result<u16, TMag5273Error> {
+        let data = self.read_angle().await?;
+        Ok(conversions::angle_sixteenths(data))
+    }
+
+    /// Returns the resultant vector magnitude (during the angle measurement) result. This value should be consistent during 360 degrees measurements.
+    pub async fn get_magnitude(&mut self) -> Result<u8, TMag5273Error> {
+        let mut data: [u8; 1] = [0x00];
+        self.read_registers(TMAG5273Register::MagnitudeResult, &mut data)
+            .await?;
+        Ok(data[0])
+    }
+
+    /// Starts a single conversion of the enabled channels through the I2C trigger. The device must be in stand-by
+    /// mode with the trigger mode set to the I2C command, otherwise a WrongMode error is thrown.
+    pub async fn trigger_conversion(&mut self) -> Result<(), TMag5273Error> {
+        let device_config_2: DeviceConfig2Register = self.config_register().await?;
+        conversions::check_i2c_trigger(device_config_2)?;
+        // The trigger is the MSB of the register address, no register is written
+        let command = conversions::TRIGGER_BIT | u8::from(TMAG5273Register::ConvStatus);
+        self.i2c.write(self.address, &[command]).await?;
+        Ok(())
+    }
+
+    /// Makes a single measurement of the enabled channels while the device is in stand-by mode, so the device only
+    /// draws its active current for the length of one conversion. The conversion is started through the I2C
+    /// trigger, then the Conversion Status Register is polled until the new data is ready. If the data is not ready
+    /// within `timeout_us` a Timeout error is thrown.
+    /// Like get_enabled_data, channels which are not enabled are `None`, and if no channel is enabled a
+    /// ChannelDisabled error is thrown before the conversion is started.
+    ///
+    /// ### Arguments
+    ///
+    /// * `delay` - Delay used between polls of the Conversion Status Register
+    /// * `timeout_us` - Time to wait for the conversion in us
+    #[cfg(feature = "float")]
+    pub async fn measure_once<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<TMag5273EnabledData, TMag5273Error> {
+        let data = self.measure_once_raw(delay, timeout_us).await?;
+        Ok(conversions::convert_enabled_data(data))
+    }
+
+    /// Makes a single measurement like measure_once, returning the data without any floating point conversion.
+    pub async fn measure_once_raw<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<TMag5273RawEnabledData, TMag5273Error> {
+        let channels = self.enabled_channels().await?;
+        self.wait_for_conversion(delay, timeout_us).await?;
+        self.read_enabled_data(channels).await
+    }
+
+    /// Initialise the device with the default settings.
+    pub async fn init_default(mut self) -> Result<Self, TMag5273Error> {
+        for register in conversions::DEFAULT_CONFIG_REGISTERS {
+            let mut value = [0x00];
+            self.read_registers(register, &mut value).await?;
+            self.write_register(register, conversions::default_config(register, value[0]))
+                .await?;
+        }
+        Ok(self)
+    }
+}
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Checks the temp channel is enabled
+    async fn check_temp_channel(&mut self) -> Result<(), TMag5273Error> {
+        let t_config: TConfigRegister = self.config_register().await?;
+        conversions::check_temp_channel(t_config)
+    }
+
+    /// Reads the temperature result registers, checking the channel is enabled
+    async fn read_temperature(&mut self) -> Result<[u8; 2], TMag5273Error> {
+        // Check if channel is enabled, throw error otherwise
+        self.check_temp_channel().await?;
+        let mut data: [u8; 2] = [0x00; 2];
+        if self
+            .read_results(TMAG5273Register::TMsbResult, &mut data)
+            .await?
+        {
+            self.check_temp_channel().await?;
+        }
+        Ok(data)
+    }
+
+    /// Reads the result registers of the axis, alongside the range in mT they are scaled by
+    async fn read_mag_data(&mut self, axis: Axis) -> Result<([u8; 2], u16), TMag5273Error> {
+        let configs: SensorConfigRegisters = self.sensor_config_registers().await?;
+        let (register, mut range) =
+            conversions::mag_data_register(axis, configs, self.device_version)?;
+
+        let mut data: [u8; 2] = [0x00; 2];
+        if self.read_results(register, &mut data).await? {
+            let configs = self.shadow.sensor_config();
+            (_, range) = conversions::mag_data_register(axis, configs, self.device_version)?;
+        }
+        Ok((data, range))
+    }
+
+    /// Reads the temperature and axis result registers in one burst, alongside the XY and Z ranges in mT
+    async fn read_all_data(&mut self) -> Result<([u8; 8], u16, u16), TMag5273Error> {
+        let configs: SensorConfigRegisters = self.sensor_config_registers().await?;
+        let t_config: TConfigRegister = self.config_register().await?;
+        let (mut xy_range, mut z_range) =
+            conversions::all_data_ranges(configs, t_config, self.device_version)?;
+
+        // Full Data Read
+        let mut data: [u8; 8] = [0x00; 8];
+        if self
+            .read_results(TMAG5273Register::TMsbResult, &mut data)
+            .await?
+        {
+            (xy_range, z_range) = conversions::all_data_ranges(
+                self.shadow.sensor_config(),
+                self.shadow.t_config(),
+                self.device_version,
+            )?;
+        }
+        Ok((data, xy_range, z_range))
+    }
+
+    /// Reads the temperature and axis result registers and the Conversion Status Register in one burst, alongside
+    /// the XY and Z ranges in mT and the set count of the conversion
+    async fn read_sample(&mut self) -> Result<([u8; 8], u16, u16, u8), TMag5273Error> {
+        let configs: SensorConfigRegisters = self.sensor_config_registers().await?;
+        let t_config: TConfigRegister = self.config_register().await?;
+        let (mut xy_range, mut z_range) =
+            conversions::all_data_ranges(configs, t_config, self.device_version)?;
+
+        let mut data = [0x00; conversions::SAMPLE_BURST_LEN];
+        if self
+            .read_results(TMAG5273Register::TMsbResult, &mut data)
+            .await?
+        {
+            // The set count starts again after a power-on-reset
+            self.last_set_count = None;
+            (xy_range, z_range) = conversions::all_data_ranges(
+                self.shadow.sensor_config(),
+                self.shadow.t_config(),
+                self.device_version,
+            )?;
+        }
+        let (data, set_count) = conversions::split_sample(data);
+        Ok((data, xy_range, z_range, set_count))
+    }
+
+    /// Wraps data with its set count, flagging it against the previous sample
+    fn track_sample<DATA>(&mut self, data: DATA, set_count: u8) -> TMag5273Sample<DATA> {
+        let freshness = conversions::sample_freshness(self.last_set_count, set_count);
+        self.last_set_count = Some(set_count);
+        TMag5273Sample {
+            data,
+            set_count,
+            freshness,
+        }
+    }
+
+    /// Reads the angle result registers, checking the angle calculation is enabled
+    async fn read_angle(&mut self) -> Result<[u8; 2], TMag5273Error> {
+        // Check if the channel is enabled
+        let config: SensorConfig2Register = self.config_register().await?;
+        conversions::check_angle_enabled(config)?;
+        let mut data = [0x00; 2];
+        if self
+            .read_results(TMAG5273Register::AngleResultMSB, &mut data)
+            .await?
+        {
+            conversions::check_angle_enabled(self.shadow.sensor_config().sensor_config2())?;
+        }
+        Ok(data)
+    }
+
+    /// Gets the channels enabled on the device, alongside the ranges in mT the axes are scaled by
+    async fn enabled_channels(&mut self) -> Result<conversions::EnabledChannels, TMag5273Error> {
+        let configs: SensorConfigRegisters = self.sensor_config_registers().await?;
+        let t_config: TConfigRegister = self.config_register().await?;
+        conversions::EnabledChannels::new(configs, t_config, self.device_version)
+    }
+
+    /// Reads the result registers covering the enabled channels and decodes them
+    async fn read_enabled_data(
+        &mut self,
+        mut channels: conversions::EnabledChannels,
+    ) -> Result<TMag5273RawEnabledData, TMag5273Error> {
+        let (start, end) = channels.span();
+        let mut data = [0x00; 8];
+        if self
+            .read_results(channels.start_register()?, &mut data[start..end])
+            .await?
+        {
+            channels = channels.refreshed(
+                self.shadow.sensor_config(),
+                self.shadow.t_config(),
+                self.device_version,
+            )?;
+        }
+        Ok(channels.decode_raw(data))
+    }
+
+    /// Triggers a single conversion and waits for it to complete
+    async fn wait_for_conversion<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<(), TMag5273Error> {
+        let previous: ConversionStatusRegister = self.get_config_register().await?;
+        self.trigger_conversion().await?;
+
+        let mut timeout =
+            conversions::PollTimeout::new(conversions::CONVERSION_POLL_INTERVAL_US, timeout_us);
+        while !conversions::conversion_complete(previous, self.get_config_register().await?) {
+            delay.delay_us(timeout.next_delay()?).await;
+        }
+        Ok(())
+    }
+
+    /// Checks if the connect device has the correct hardware version for what the user has chosen
+    async fn is_correct_device_version(&mut self) -> Result<(), TMag5273Error> {
+        let device_id = self.get_device_id().await?;
+        conversions::check_device_version(device_id, self.device_version)
+    }
+}
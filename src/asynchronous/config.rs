@@ -0,0 +1,51 @@
+use embedded_hal_async::i2c::{I2c, SevenBitAddress};
+
+use super::TMag5273;
+use crate::{registers::*, DeviceConfig, InterruptConfig, SensorConfig, TMag5273Error};
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Set the interrupt configuration.
+    pub async fn set_interrupts(&mut self, config: InterruptConfig) -> Result<(), TMag5273Error> {
+        self.set_config_register(config.into_register()).await?;
+        Ok(())
+    }
+    /// Set the device configuration.
+    pub async fn set_device_config(&mut self, config: DeviceConfig) -> Result<(), TMag5273Error> {
+        let (config1, config2) = config.into_registers();
+        self.set_config_register(config1).await?;
+        self.set_config_register(config2).await?;
+        Ok(())
+    }
+    /// Set the sensor configuration.
+    pub async fn set_sensor_config(&mut self, config: SensorConfig) -> Result<(), TMag5273Error> {
+        let (config1, config2) = config.into_registers();
+        self.set_config_register(config1).await?;
+        self.set_config_register(config2).await?;
+        Ok(())
+    }
+    /// Get the sensor configuration.
+    pub async fn get_sensor_config(&mut self) -> Result<SensorConfig, TMag5273Error> {
+        let config = self
+            .get_dual_config_register::<SensorConfigRegisters>()
+            .await?;
+        let t_config = self.get_config_register::<TConfigRegister>().await?;
+        SensorConfig::from_registers(config, t_config)
+    }
+    /// Get the device configuration.
+    pub async fn get_device_config(&mut self) -> Result<DeviceConfig, TMag5273Error> {
+        let config = self
+            .get_dual_config_register::<DeviceConfigRegisters>()
+            .await?;
+        DeviceConfig::from_registers(config)
+    }
+    /// Get the interrupt configuration.
+    pub async fn get_interrupt_config(&mut self) -> Result<InterruptConfig, TMag5273Error> {
+        let config = self
+            .get_config_register::<InterruptConfigRegister>()
+            .await?;
+        InterruptConfig::from_register(config)
+    }
+}
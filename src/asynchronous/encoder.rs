@@ -0,0 +1,19 @@
+use embedded_hal_async::i2c::{I2c, SevenBitAddress};
+
+use super::TMag5273;
+use crate::{
+    encoder::{EncoderState, RotaryEncoder},
+    types::TMag5273Error,
+};
+
+impl<I2C> RotaryEncoder<TMag5273<I2C>>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Reads the angle from the device and adds it, taken at the timestamp in microseconds.
+    /// This will throw an error if the angle calculation is not enabled.
+    pub async fn update(&mut self, timestamp_us: u64) -> Result<EncoderState, TMag5273Error> {
+        let angle = self.sensor().get_raw_angle().await?;
+        Ok(self.update_with_angle(angle, timestamp_us))
+    }
+}
@@ -0,0 +1,50 @@
+use embedded_hal_async::i2c::{I2c, SevenBitAddress};
+
+use super::TMag5273;
+use crate::{
+    conversions,
+    registers::TMAG5273Register,
+    types::{HealthReport, TMag5273Error},
+};
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Reads the fault flags of the device, then clears the latched error flags so that the next report only
+    /// shows faults which have happened since. A fault which is still present is flagged again by the device.
+    /// The power-on-reset flag is reported but not cleared, as the driver relies on it to notice a reset.
+    /// Faults latched while the supply ramped up may be reported by the first call after power up.
+    pub async fn health(&mut self) -> Result<HealthReport, TMag5273Error> {
+        let mut data = [0x00; conversions::HEALTH_BURST_LEN];
+        self.read_registers(TMAG5273Register::ConvStatus, &mut data)
+            .await?;
+        if let Some((register, errors)) = conversions::clear_errors_write(&data) {
+            self.write_register(register, errors).await?;
+        }
+        Ok(conversions::health_report(data))
+    }
+
+    /// Sets whether the health of the device is checked after every data read, turning the first fault found
+    /// into an error such as VccUnderVoltage instead of returning the data. This costs an extra read of the status
+    /// registers per measurement, except for get_fast_read_data which only reads them once the diagnostic flag sent
+    /// with the data is set. Disabled by default.
+    pub fn set_health_check(&mut self, enabled: bool) {
+        self.health_check = enabled;
+    }
+
+    /// Returns whether the health of the device is checked after every data read.
+    pub fn get_health_check(&self) -> bool {
+        self.health_check
+    }
+
+    /// ### Raw Registers
+    ///
+    /// Checks the health of the device after data has been read, if enabled through set_health_check.
+    pub(crate) async fn check_health_after_read(&mut self) -> Result<(), TMag5273Error> {
+        if !self.health_check {
+            return Ok(());
+        }
+        self.health().await?.check()
+    }
+}
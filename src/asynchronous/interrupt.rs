@@ -0,0 +1,148 @@
+use core::future::{poll_fn, Future};
+use core::pin::pin;
+use core::task::Poll;
+
+use embedded_hal::digital::Error;
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::digital::Wait;
+use embedded_hal_async::i2c::{I2c, SevenBitAddress};
+
+#[cfg(feature = "float")]
+use crate::types::TMag5273ChannelData;
+use crate::{
+    conversions,
+    registers::*,
+    types::{TMag5273Error, TMag5273RawChannelData},
+};
+
+use super::TMag5273;
+
+/// Handle which owns the INT pin of a device, created by [TMag5273::interrupt_acquisition].
+pub struct InterruptAcquisition<'a, I2C, PIN> {
+    sensor: &'a mut TMag5273<I2C>,
+    pin: PIN,
+    /// Whether the interrupt is latched rather than pulsed
+    latched: bool,
+}
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Takes the INT pin of the device to wait for the conversion complete interrupt before reading the data.
+    /// The interrupt must already be configured through the InterruptConfig, with the conversion complete interrupt
+    /// enabled and sent through the INT pin, otherwise a WrongMode error is thrown.
+    ///
+    /// ## Example
+    ///
+    /// ```Rust
+    /// let mut acquisition = mag5273.interrupt_acquisition(pin).await?;
+    /// loop {
+    ///     let data = acquisition.wait_for_data(&mut delay, 100_000).await?;
+    /// }
+    /// ```
+    pub async fn interrupt_acquisition<PIN>(
+        &mut self,
+        pin: PIN,
+    ) -> Result<InterruptAcquisition<'_, I2C, PIN>, TMag5273Error>
+    where
+        PIN: Wait,
+    {
+        let config: InterruptConfigRegister = self.get_config_register().await?;
+        let latched = conversions::check_conversion_interrupt(config)?;
+        Ok(InterruptAcquisition {
+            sensor: self,
+            pin,
+            latched,
+        })
+    }
+}
+
+impl<I2C, PIN> InterruptAcquisition<'_, I2C, PIN>
+where
+    I2C: I2c<SevenBitAddress>,
+    PIN: Wait,
+{
+    /// Waits for the conversion complete interrupt, then reads all the axis data alongside the temperature of the
+    /// device in one burst. If the interrupt is not taken within `timeout_us` a Timeout error is thrown.
+    /// A latched interrupt which was asserted before this call returns straight away with the data it flagged, while
+    /// a pulsed interrupt is only taken on its falling edge so the same pulse is never read twice.
+    /// Like get_all_data, the X, Y, Z and temperature channels must be enabled, otherwise an error is thrown. The
+    /// configuration is only read once the interrupt is taken, as addressing the device clears a latched interrupt.
+    ///
+    /// ### Arguments
+    ///
+    /// * `delay` - Delay which bounds the wait for the interrupt
+    /// * `timeout_us` - Time to wait for the interrupt in us
+    #[cfg(feature = "float")]
+    pub async fn wait_for_data<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<TMag5273ChannelData, TMag5273Error> {
+        let (data, xy_range, z_range) = self.wait_for_results(delay, timeout_us).await?;
+        Ok(conversions::convert_all_data(data, xy_range, z_range))
+    }
+
+    /// Waits for the conversion complete interrupt like wait_for_data, returning the data without any floating
+    /// point conversion.
+    pub async fn wait_for_raw_data<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<TMag5273RawChannelData, TMag5273Error> {
+        let (data, xy_range, z_range) = self.wait_for_results(delay, timeout_us).await?;
+        Ok(conversions::convert_raw_all_data(data, xy_range, z_range))
+    }
+
+    /// Gives access to the sensor, for example to change the configuration between acquisitions.
+    pub fn sensor(&mut self) -> &mut TMag5273<I2C> {
+        self.sensor
+    }
+
+    /// Hands back the INT pin.
+    pub fn release(self) -> PIN {
+        self.pin
+    }
+
+    /// Waits for the interrupt, then reads the result registers alongside the XY and Z ranges in mT
+    async fn wait_for_results<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<([u8; 8], u16, u16), TMag5273Error> {
+        // INT is active low
+        let asserted = match self.latched {
+            true => with_timeout(self.pin.wait_for_low(), delay, timeout_us).await?,
+            false => with_timeout(self.pin.wait_for_falling_edge(), delay, timeout_us).await?,
+        };
+        asserted.map_err(|err| TMag5273Error::Pin(err.kind()))?;
+
+        // Reading the results addresses the device, which also clears a latched interrupt
+        self.sensor.read_all_data().await
+    }
+}
+
+/// Runs the future until it completes, or throws a Timeout error once `timeout_us` has passed.
+async fn with_timeout<F, D>(
+    future: F,
+    delay: &mut D,
+    timeout_us: u32,
+) -> Result<F::Output, TMag5273Error>
+where
+    F: Future,
+    D: DelayNs,
+{
+    let mut future = pin!(future);
+    let mut timeout = pin!(delay.delay_us(timeout_us));
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        timeout
+            .as_mut()
+            .poll(cx)
+            .map(|()| Err(TMag5273Error::Timeout))
+    })
+    .await
+}
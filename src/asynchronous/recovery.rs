@@ -0,0 +1,113 @@
+use embedded_hal_async::i2c::{I2c, SevenBitAddress};
+
+use super::TMag5273;
+use crate::{
+    conversions,
+    recovery::RESTORED_LEN,
+    registers::*,
+    types::{CacheMode, TMag5273Error},
+};
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Checks if the device has been through a power-on-reset, and if so restores the configuration last written
+    /// through the driver and clears the POR flag. Returns true if the device was restored.
+    /// A device which has been moved to another address is looked for at its factory address when it no longer
+    /// answers, and moved back before it is restored.
+    ///
+    /// NOTE: with several sensors on one bus, a supply dip resets them all to the same factory address. They must
+    /// be brought up again one at a time instead.
+    ///
+    /// ## Example
+    ///
+    /// ```Rust
+    /// if mag5273.recover().await? {
+    ///     println!("The sensor was reset and has been restored");
+    /// }
+    /// ```
+    pub async fn recover(&mut self) -> Result<bool, TMag5273Error> {
+        let factory_address = self.device_version.get_default_address();
+        let (status, answering) = match self.read_conversion_status(self.address).await {
+            Ok(status) => (status, self.address),
+            Err(err) if self.address != factory_address => {
+                match self.read_conversion_status(factory_address).await {
+                    Ok(status) if status.power_on_reset() => (status, factory_address),
+                    _ => return Err(err),
+                }
+            }
+            Err(err) => return Err(err),
+        };
+        if !status.power_on_reset() {
+            return Ok(false);
+        }
+
+        if answering != self.address {
+            let command = conversions::address_move(self.address)?;
+            self.i2c.write(answering, &command).await?;
+        }
+        // The CRC and read mode are back to their defaults until DeviceConfig1 is restored
+        self.shadow = Default::default();
+        self.last_set_count = None;
+        let applied = self.applied;
+        for (register, value) in applied.restore_writes() {
+            self.write_register(register, value).await?;
+        }
+        Ok(true)
+    }
+
+    /// Sets whether a power-on-reset is detected and recovered from while reading data, see
+    /// [recover](TMag5273::recover). The read which finds the reset returns a DeviceReset error once the device is
+    /// restored, as its data comes from the reset device, and the next read returns data again. Disabled by default.
+    ///
+    /// A reset is looked for when a configuration read back before a measurement differs from the one last written,
+    /// when the device stops answering, and through the POR flag read in the same burst as the data, so a healthy
+    /// device costs no extra transfers.
+    pub fn set_auto_recovery(&mut self, enabled: bool) {
+        self.auto_recovery = enabled;
+    }
+
+    /// Returns whether a power-on-reset is detected and recovered from while reading data.
+    pub fn get_auto_recovery(&self) -> bool {
+        self.auto_recovery
+    }
+
+    /// ### Raw Registers
+    ///
+    /// In auto recovery, checks whether a read which failed or returned an unexpected configuration was caused by a
+    /// power-on-reset, and recovers from it. Returns true if the device was restored.
+    pub(crate) async fn recovered(&mut self) -> bool {
+        self.auto_recovery && matches!(self.recover().await, Ok(true))
+    }
+
+    /// ### Raw Registers
+    ///
+    /// Reads the configuration back from the device into the applied configuration.
+    pub(crate) async fn sync_applied(&mut self) -> Result<(), TMag5273Error> {
+        let mut data = [0x00; RESTORED_LEN];
+        self.read_registers(TMAG5273Register::DeviceConfig1, &mut data)
+            .await?;
+        self.applied
+            .update_from_read(TMAG5273Register::DeviceConfig1, &data);
+        Ok(())
+    }
+
+    /// Whether the POR flag has to be read alongside the data.
+    pub(crate) fn reads_conversion_status(&self) -> bool {
+        self.cache_mode == CacheMode::Cached || self.auto_recovery
+    }
+
+    /// Reads the Conversion Status Register at an address. A single register read never reaches the CRC byte, so
+    /// this works whatever the CRC setting of the device is.
+    async fn read_conversion_status(
+        &mut self,
+        address: SevenBitAddress,
+    ) -> Result<ConversionStatusRegister, TMag5273Error> {
+        let mut data = [0x00];
+        self.i2c
+            .write_read(address, &[TMAG5273Register::ConvStatus.into()], &mut data)
+            .await?;
+        Ok(ConversionStatusRegister::new_with_raw_value(data[0]))
+    }
+}
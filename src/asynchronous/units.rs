@@ -0,0 +1,95 @@
+use embedded_hal_async::i2c::{I2c, SevenBitAddress};
+
+use super::TMag5273;
+use crate::{
+    types::{Axis, MagneticChannelOffset, TMag5273Error},
+    units::{
+        angle, flux_density, temperature, to_degree_celsius, to_millitesla, Angle,
+        MagneticFluxDensity, TMag5273QuantityData, ThermodynamicTemperature,
+    },
+};
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Set the threshold for an axis as a magnetic flux density, see [set_mag_threshold](TMag5273::set_mag_threshold).
+    pub async fn set_mag_threshold_quantity(
+        &mut self,
+        threshold: MagneticFluxDensity,
+        axis: Axis,
+    ) -> Result<(), TMag5273Error> {
+        self.set_mag_threshold(to_millitesla(threshold), axis).await
+    }
+
+    /// Get the threshold for an axis as a magnetic flux density.
+    pub async fn get_mag_threshold_quantity(
+        &mut self,
+        axis: Axis,
+    ) -> Result<MagneticFluxDensity, TMag5273Error> {
+        self.get_mag_threshold(axis).await.map(flux_density)
+    }
+
+    /// Set the temperature threshold, see [set_temperature_threshold](TMag5273::set_temperature_threshold).
+    /// `None` disables the threshold comparison.
+    pub async fn set_temperature_threshold_quantity(
+        &mut self,
+        threshold: Option<ThermodynamicTemperature>,
+    ) -> Result<(), TMag5273Error> {
+        self.set_temperature_threshold(threshold.map(to_degree_celsius))
+            .await
+    }
+
+    /// Get the temperature threshold, or `None` if the threshold comparison is disabled.
+    pub async fn get_temperature_threshold_quantity(
+        &mut self,
+    ) -> Result<Option<ThermodynamicTemperature>, TMag5273Error> {
+        Ok(self.get_temperature_threshold().await?.map(temperature))
+    }
+
+    /// Set the magnetic offset correction as a magnetic flux density, see
+    /// [set_magnetic_offset](TMag5273::set_magnetic_offset).
+    pub async fn set_magnetic_offset_quantity(
+        &mut self,
+        offset: MagneticFluxDensity,
+        mag_offset: MagneticChannelOffset,
+    ) -> Result<(), TMag5273Error> {
+        self.set_magnetic_offset(to_millitesla(offset), mag_offset)
+            .await
+    }
+
+    /// Get the magnetic offset correction as a magnetic flux density.
+    pub async fn get_magnetic_offset_quantity(
+        &mut self,
+        mag_offset: MagneticChannelOffset,
+    ) -> Result<MagneticFluxDensity, TMag5273Error> {
+        self.get_magnetic_offset(mag_offset).await.map(flux_density)
+    }
+
+    /// Get the temperature of the device. This will throw an error if the temperature channel is not enabled.
+    pub async fn get_temperature_quantity(
+        &mut self,
+    ) -> Result<ThermodynamicTemperature, TMag5273Error> {
+        self.get_temperature().await.map(temperature)
+    }
+
+    /// Gets the specific axis channel data. This will throw an error if the channel is not enabled.
+    pub async fn get_mag_data_quantity(
+        &mut self,
+        axis: Axis,
+    ) -> Result<MagneticFluxDensity, TMag5273Error> {
+        self.get_mag_data(axis).await.map(flux_density)
+    }
+
+    /// Get all the axis data from the device, alongside the temperature of the device.
+    /// If the correct Channels are not enabled, an error is thrown.
+    pub async fn get_all_data_quantity(&mut self) -> Result<TMag5273QuantityData, TMag5273Error> {
+        self.get_all_data().await.map(TMag5273QuantityData::from)
+    }
+
+    /// Gets the measured/calculated angle of the enabled channel.
+    /// This will throw an error if the channels are not enabled.
+    pub async fn get_angle_quantity(&mut self) -> Result<Angle, TMag5273Error> {
+        self.get_angle().await.map(angle)
+    }
+}
@@ -0,0 +1,361 @@
+//! # Hard and Soft Iron Calibration
+//!
+//! Steel parts near the sensor and currents in the PCB add an offset to the field it measures (hard iron) and
+//! distort it differently along each axis (soft iron), so a magnet swept around the sensor traces an offset
+//! ellipsoid rather than a sphere centred on the origin. The [Calibrator] collects samples during such a sweep and
+//! fits an ellipsoid to them, giving a [Calibration] which maps later samples back onto a sphere.
+//!
+//! Everything works without an allocator. The samples are held in a buffer sized by the caller and the fit solves a
+//! fixed 9x9 system, so a few dozen samples spread over the sweep are enough. The samples are centred and scaled
+//! before the fit, which keeps it accurate in single precision, so targets with a single precision FPU do not fall
+//! back to soft-float.
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+
+use crate::{
+    conversions,
+    types::{TMag5273ChannelData, TMag5273Error},
+    TMag5273,
+};
+
+/// Number of terms in the general ellipsoid fitted to the samples.
+const ELLIPSOID_TERMS: usize = 9;
+
+/// Length of a serialised [Calibration]: the offset and matrix as little endian f32s followed by a CRC.
+pub const CALIBRATION_LEN: usize = 12 * 4 + 1;
+
+/// Hard iron offset and soft iron matrix, applied as `matrix * (sample - offset)`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Calibration {
+    /// Hard iron offset of the X, Y and Z axes in mT
+    pub offset: [f32; 3],
+    /// Soft iron correction matrix, in rows
+    pub matrix: [[f32; 3]; 3],
+}
+
+impl Default for Calibration {
+    /// A calibration which leaves the samples unchanged.
+    fn default() -> Self {
+        Self {
+            offset: [0.0; 3],
+            matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+}
+
+impl Calibration {
+    /// Corrects the X, Y and Z axis data of a sample. The temperature is left unchanged.
+    pub fn apply(&self, data: TMag5273ChannelData) -> TMag5273ChannelData {
+        let centred = [
+            data.x - self.offset[0],
+            data.y - self.offset[1],
+            data.z - self.offset[2],
+        ];
+        let [x, y, z] = self.matrix.map(|row| {
+            row.iter()
+                .zip(centred)
+                .map(|(factor, value)| factor * value)
+                .sum()
+        });
+        TMag5273ChannelData {
+            x,
+            y,
+            z,
+            temp: data.temp,
+        }
+    }
+
+    /// Serialises the calibration, e.g. to store it in flash. A CRC is added so corrupted data is caught when
+    /// the calibration is read back.
+    pub fn to_bytes(&self) -> [u8; CALIBRATION_LEN] {
+        let mut bytes = [0x00; CALIBRATION_LEN];
+        let values = self.offset.iter().chain(self.matrix.iter().flatten());
+        for (chunk, value) in bytes.chunks_exact_mut(4).zip(values) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        bytes[CALIBRATION_LEN - 1] = conversions::crc8(&bytes[..CALIBRATION_LEN - 1]);
+        bytes
+    }
+
+    /// Reads back a calibration serialised with to_bytes. Throws a CrcMismatch error if the data is corrupted.
+    pub fn from_bytes(bytes: &[u8; CALIBRATION_LEN]) -> Result<Self, TMag5273Error> {
+        let (data, crc) = bytes.split_at(CALIBRATION_LEN - 1);
+        if conversions::crc8(data) != crc[0] {
+            return Err(TMag5273Error::CrcMismatch);
+        }
+        let mut values = data
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+        let mut next = || values.next().unwrap_or_default();
+        Ok(Self {
+            offset: [next(), next(), next()],
+            matrix: [
+                [next(), next(), next()],
+                [next(), next(), next()],
+                [next(), next(), next()],
+            ],
+        })
+    }
+}
+
+/// Collects up to `N` samples during a calibration sweep and fits a [Calibration] to them.
+///
+/// ## Example
+///
+/// ```Rust
+/// let mut calibrator = Calibrator::<64>::new();
+/// while !calibrator.is_full() {
+///     // Sweep the magnet around the sensor, covering as many directions as possible
+///     calibrator.add_sample(mag5273.get_all_data()?)?;
+///     delay.delay_ms(50);
+/// }
+/// let calibration = calibrator.fit()?;
+/// flash.write(CALIBRATION_ADDRESS, &calibration.to_bytes());
+/// let data = mag5273.get_calibrated_data(&calibration)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Calibrator<const N: usize> {
+    samples: [[f32; 3]; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for Calibrator<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Calibrator<N> {
+    /// Creates an empty calibrator.
+    pub fn new() -> Self {
+        Self {
+            samples: [[0.0; 3]; N],
+            len: 0,
+        }
+    }
+
+    /// Adds the X, Y and Z axis data of a sample. Throws an OutOfRange error once the buffer is full.
+    pub fn add_sample(&mut self, data: TMag5273ChannelData) -> Result<(), TMag5273Error> {
+        let sample = self
+            .samples
+            .get_mut(self.len)
+            .ok_or(TMag5273Error::OutOfRange)?;
+        *sample = [data.x, data.y, data.z];
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Number of samples collected.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if no sample has been collected.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Checks if the buffer is full.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Discards the samples collected so far.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Fits an ellipsoid to the samples. The offset is its centre, and the matrix maps it onto a sphere with the
+    /// same volume, so the corrected field keeps its magnitude on average.
+    /// Throws a CalibrationFailed error if there are fewer than 9 samples, or if the samples do not describe an
+    /// ellipsoid, which happens when the sweep only covers a plane or a few directions.
+    pub fn fit(&self) -> Result<Calibration, TMag5273Error> {
+        let samples = &self.samples[..self.len];
+        if samples.len() < ELLIPSOID_TERMS {
+            return Err(TMag5273Error::CalibrationFailed);
+        }
+
+        // Centre and scale the samples around 1 to keep the normal equations well conditioned in single precision
+        let mut mean = [0.0f32; 3];
+        for sample in samples {
+            for (mean, value) in mean.iter_mut().zip(sample) {
+                *mean += *value / samples.len() as f32;
+            }
+        }
+        let scale = samples
+            .iter()
+            .flat_map(|sample| sample.iter().zip(mean).map(|(value, mean)| *value - mean))
+            .fold(0.0f32, |scale, value| scale.max(value.abs()));
+        if scale == 0.0 {
+            return Err(TMag5273Error::CalibrationFailed);
+        }
+
+        // Least squares fit of ax² + by² + cz² + 2dxy + 2exz + 2fyz + 2gx + 2hy + 2iz = 1
+        let mut normal = [[0.0f32; ELLIPSOID_TERMS]; ELLIPSOID_TERMS];
+        let mut rhs = [0.0f32; ELLIPSOID_TERMS];
+        for sample in samples {
+            let [x, y, z] = [0, 1, 2].map(|axis| (sample[axis] - mean[axis]) / scale);
+            let terms = [
+                x * x,
+                y * y,
+                z * z,
+                2.0 * x * y,
+                2.0 * x * z,
+                2.0 * y * z,
+                2.0 * x,
+                2.0 * y,
+                2.0 * z,
+            ];
+            for (row, first) in normal.iter_mut().zip(terms) {
+                for (value, second) in row.iter_mut().zip(terms) {
+                    *value += first * second;
+                }
+            }
+            for (value, term) in rhs.iter_mut().zip(terms) {
+                *value += term;
+            }
+        }
+        let [a, b, c, d, e, f, g, h, i] =
+            solve(normal, rhs).ok_or(TMag5273Error::CalibrationFailed)?;
+
+        // The centre of the ellipsoid (x - c)ᵀ Q (x - c) = k
+        let quadric = [[a, d, e], [d, b, f], [e, f, c]];
+        let inverse = invert_3x3(quadric).ok_or(TMag5273Error::CalibrationFailed)?;
+        let centre = inverse.map(|row| -(row[0] * g + row[1] * h + row[2] * i));
+        let mut k = 1.0;
+        for (row, centre_row) in quadric.iter().zip(centre) {
+            for (value, centre_col) in row.iter().zip(centre) {
+                k += centre_row * value * centre_col;
+            }
+        }
+        if k <= 0.0 {
+            return Err(TMag5273Error::CalibrationFailed);
+        }
+
+        // sqrt(Q / k) maps the ellipsoid onto the unit sphere, scaled back up to the mean radius of the ellipsoid
+        let (eigenvalues, eigenvectors) =
+            symmetric_eigen(quadric.map(|row| row.map(|value| value / k)));
+        if eigenvalues.iter().any(|value| *value <= 0.0) {
+            return Err(TMag5273Error::CalibrationFailed);
+        }
+        let radius = libm::cbrtf(
+            eigenvalues
+                .iter()
+                .map(|value| 1.0 / libm::sqrtf(*value))
+                .product::<f32>(),
+        );
+        let roots = eigenvalues.map(|value| libm::sqrtf(value) * radius);
+        let matrix = [0, 1, 2].map(|row| {
+            [0, 1, 2].map(|col| {
+                (0..3)
+                    .map(|index| eigenvectors[row][index] * roots[index] * eigenvectors[col][index])
+                    .sum::<f32>()
+            })
+        });
+        let offset = [0, 1, 2].map(|axis| mean[axis] + centre[axis] * scale);
+        Ok(Calibration { offset, matrix })
+    }
+}
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Gets all the axis data like get_all_data, with the calibration applied to the X, Y and Z axes.
+    pub fn get_calibrated_data(
+        &mut self,
+        calibration: &Calibration,
+    ) -> Result<TMag5273ChannelData, TMag5273Error> {
+        Ok(calibration.apply(self.get_all_data()?))
+    }
+}
+
+/// Solves the linear system by Gaussian elimination with partial pivoting. Returns `None` if it is singular.
+fn solve<const M: usize>(mut matrix: [[f32; M]; M], mut rhs: [f32; M]) -> Option<[f32; M]> {
+    const SINGULAR: f32 = 1e-6;
+    for col in 0..M {
+        let pivot =
+            (col..M).max_by(|a, b| matrix[*a][col].abs().total_cmp(&matrix[*b][col].abs()))?;
+        if matrix[pivot][col].abs() < SINGULAR {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+        let (pivot_rows, rows) = matrix.split_at_mut(col + 1);
+        let pivot_row = &pivot_rows[col];
+        let (pivot_rhs, rhs_rows) = rhs.split_at_mut(col + 1);
+        for (row, rhs_row) in rows.iter_mut().zip(rhs_rows) {
+            let factor = row[col] / pivot_row[col];
+            for (value, pivot_value) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot_value;
+            }
+            *rhs_row -= factor * pivot_rhs[col];
+        }
+    }
+    let mut solution = [0.0; M];
+    for row in (0..M).rev() {
+        let known: f32 = matrix[row][row + 1..]
+            .iter()
+            .zip(&solution[row + 1..])
+            .map(|(value, solved)| value * solved)
+            .sum();
+        solution[row] = (rhs[row] - known) / matrix[row][row];
+    }
+    Some(solution)
+}
+
+/// Inverts a 3x3 matrix through its adjugate. Returns `None` if it is singular.
+fn invert_3x3(m: [[f32; 3]; 3]) -> Option<[[f32; 3]; 3]> {
+    let cofactor = |row: usize, col: usize| {
+        let (r0, r1) = ((row + 1) % 3, (row + 2) % 3);
+        let (c0, c1) = ((col + 1) % 3, (col + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let determinant: f32 = (0..3).map(|col| m[0][col] * cofactor(0, col)).sum();
+    if determinant.abs() < 1e-9 {
+        return None;
+    }
+    // The inverse is the transposed cofactor matrix over the determinant
+    Some([0, 1, 2].map(|row| [0, 1, 2].map(|col| cofactor(col, row) / determinant)))
+}
+
+/// Eigenvalues and eigenvectors (as columns) of a symmetric 3x3 matrix, by the cyclic Jacobi method.
+fn symmetric_eigen(mut m: [[f32; 3]; 3]) -> ([f32; 3], [[f32; 3]; 3]) {
+    const SWEEPS: usize = 32;
+    let mut vectors = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    for _ in 0..SWEEPS {
+        let off_diagonal = m[0][1].abs() + m[0][2].abs() + m[1][2].abs();
+        if off_diagonal < 1e-9 {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if m[p][q] == 0.0 {
+                continue;
+            }
+            // Rotation which zeroes m[p][q]
+            let theta = (m[q][q] - m[p][p]) / (2.0 * m[p][q]);
+            let t = theta.signum() / (theta.abs() + libm::sqrtf(theta * theta + 1.0));
+            let cos = 1.0 / libm::sqrtf(t * t + 1.0);
+            let sin = t * cos;
+            for row in m.iter_mut() {
+                let (kp, kq) = (row[p], row[q]);
+                row[p] = cos * kp - sin * kq;
+                row[q] = sin * kp + cos * kq;
+            }
+            // p is always below q, so the two rows are split apart to rotate them together
+            let (upper, lower) = m.split_at_mut(q);
+            for (pk, qk) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                let (vp, vq) = (*pk, *qk);
+                *pk = cos * vp - sin * vq;
+                *qk = sin * vp + cos * vq;
+            }
+            for row in vectors.iter_mut() {
+                let (vp, vq) = (row[p], row[q]);
+                row[p] = cos * vp - sin * vq;
+                row[q] = sin * vp + cos * vq;
+            }
+        }
+    }
+    ([m[0][0], m[1][1], m[2][2]], vectors)
+}
@@ -24,7 +24,8 @@ pub struct DeviceConfig {
     pub conv_avg: ConversionAverage,
     /// Temperature coefficient of the magnet
     pub mag_tempo: MagnetTemperatureCoefficient,
-    /// Enables I2C CRC byte to be sent
+    /// Enables I2C CRC byte to be sent. Once set, the driver verifies the CRC on every read
+    /// and returns a CrcMismatch error if the check fails.
     pub i2c_crc_enabled: bool,
     /// Selects Operating Mode and updates value based on operating
     /// mode if device transitions from Wake-up and sleep mode to Standby
@@ -66,65 +67,98 @@ pub struct SensorConfig {
     pub threshold_crossing_count: ThresholdCrossingCount,
 }
 
-impl<I2C> TMag5273<I2C>
-where
-    I2C: I2c<SevenBitAddress>,
-{
-    /// Set the interrupt configuration.
-    pub fn set_interrupts(&mut self, config: InterruptConfig) -> Result<(), TMag5273Error> {
-        let register = InterruptConfigRegister::builder()
-            .with_int_pin_disabled(config.int_pin_disabled)
-            .with_interrupt_mode(config.interrupt_mode)
-            .with_int_pin_mode(config.int_pin_mode)
-            .with_threshold_interrupt_enabled(config.threshold_interrupt_enabled)
-            .with_conversion_complete_interrupt_enabled(
-                config.conversion_complete_interrupt_enabled,
-            )
-            .build();
-        self.set_config_register(register)?;
-        Ok(())
+impl InterruptConfig {
+    /// Builds the interrupt configuration register.
+    pub(crate) fn into_register(self) -> InterruptConfigRegister {
+        InterruptConfigRegister::builder()
+            .with_int_pin_disabled(self.int_pin_disabled)
+            .with_interrupt_mode(self.interrupt_mode)
+            .with_int_pin_mode(self.int_pin_mode)
+            .with_threshold_interrupt_enabled(self.threshold_interrupt_enabled)
+            .with_conversion_complete_interrupt_enabled(self.conversion_complete_interrupt_enabled)
+            .build()
     }
-    /// Set the device configuration.
-    pub fn set_device_config(&mut self, config: DeviceConfig) -> Result<(), TMag5273Error> {
-        let register = DeviceConfig1Register::builder()
-            .with_i2c_read_mode(config.i2c_read_mode)
-            .with_conv_avg(config.conv_avg)
-            .with_mag_tempo(config.mag_tempo)
-            .with_i2c_crc_enabled(config.i2c_crc_enabled)
+
+    /// Decodes the interrupt configuration register.
+    pub(crate) fn from_register(config: InterruptConfigRegister) -> Result<Self, TMag5273Error> {
+        let Ok(interrupt_mode) = config.interrupt_mode() else {
+            return Err(TMag5273Error::MalformedRegister);
+        };
+        Ok(InterruptConfig {
+            int_pin_disabled: config.int_pin_disabled(),
+            interrupt_mode,
+            int_pin_mode: config.int_pin_mode(),
+            threshold_interrupt_enabled: config.threshold_interrupt_enabled(),
+            conversion_complete_interrupt_enabled: config.conversion_complete_interrupt_enabled(),
+        })
+    }
+}
+
+impl DeviceConfig {
+    /// Builds the device configuration registers.
+    pub(crate) fn into_registers(self) -> (DeviceConfig1Register, DeviceConfig2Register) {
+        let config1 = DeviceConfig1Register::builder()
+            .with_i2c_read_mode(self.i2c_read_mode)
+            .with_conv_avg(self.conv_avg)
+            .with_mag_tempo(self.mag_tempo)
+            .with_i2c_crc_enabled(self.i2c_crc_enabled)
             .build();
-        self.set_config_register(register)?;
-        let register = DeviceConfig2Register::builder()
-            .with_operating_mode(config.operating_mode)
-            .with_trigger_mode(config.trigger_mode)
-            .with_i2c_glitch_filter_enabled(config.i2c_glitch_filter_enabled)
-            .with_power_mode(config.power_mode)
-            .with_threshold(config.threshold)
+        let config2 = DeviceConfig2Register::builder()
+            .with_operating_mode(self.operating_mode)
+            .with_trigger_mode(self.trigger_mode)
+            .with_i2c_glitch_filter_enabled(self.i2c_glitch_filter_enabled)
+            .with_power_mode(self.power_mode)
+            .with_threshold(self.threshold)
             .build();
-        self.set_config_register(register)?;
-        Ok(())
+        (config1, config2)
     }
-    /// Set the sensor configuration.
-    pub fn set_sensor_config(&mut self, config: SensorConfig) -> Result<(), TMag5273Error> {
-        let register = SensorConfig1Register::builder()
-            .with_sleep_time(config.sleep_time)
-            .with_mag_channel(config.mag_channel)
+
+    /// Decodes the device configuration registers.
+    pub(crate) fn from_registers(config: DeviceConfigRegisters) -> Result<Self, TMag5273Error> {
+        let (config1, config2) = (config.device_config1(), config.device_config2());
+        let Ok(conv_avg) = config1.conv_avg() else {
+            return Err(TMag5273Error::MalformedRegister);
+        };
+        let Ok(threshold) = config2.threshold() else {
+            return Err(TMag5273Error::MalformedRegister);
+        };
+        Ok(DeviceConfig {
+            i2c_read_mode: config1.i2c_read_mode(),
+            conv_avg,
+            mag_tempo: config1.mag_tempo(),
+            i2c_crc_enabled: config1.i2c_crc_enabled(),
+            operating_mode: config2.operating_mode(),
+            trigger_mode: config2.trigger_mode(),
+            i2c_glitch_filter_enabled: config2.i2c_glitch_filter_enabled(),
+            power_mode: config2.power_mode(),
+            threshold,
+        })
+    }
+}
+
+impl SensorConfig {
+    /// Builds the sensor configuration registers.
+    pub(crate) fn into_registers(self) -> (SensorConfig1Register, SensorConfig2Register) {
+        let config1 = SensorConfig1Register::builder()
+            .with_sleep_time(self.sleep_time)
+            .with_mag_channel(self.mag_channel)
             .build();
-        self.set_config_register(register)?;
-        let register = SensorConfig2Register::builder()
-            .with_z_range(config.z_range)
-            .with_xy_range(config.xy_range)
-            .with_angle(config.angle)
-            .with_gain_channel(config.gain_channel)
-            .with_threshold_direction(config.threshold_direction)
-            .with_threshold_crossing_count(config.threshold_crossing_count)
+        let config2 = SensorConfig2Register::builder()
+            .with_z_range(self.z_range)
+            .with_xy_range(self.xy_range)
+            .with_angle(self.angle)
+            .with_gain_channel(self.gain_channel)
+            .with_threshold_direction(self.threshold_direction)
+            .with_threshold_crossing_count(self.threshold_crossing_count)
             .build();
-        self.set_config_register(register)?;
-        Ok(())
+        (config1, config2)
     }
-    /// Get the sensor configuration.
-    pub fn get_sensor_config(&mut self) -> Result<SensorConfig, TMag5273Error> {
-        let config = self.get_dual_config_register::<SensorConfigRegisters>()?;
-        let t_config = self.get_config_register::<TConfigRegister>()?;
+
+    /// Decodes the sensor configuration registers.
+    pub(crate) fn from_registers(
+        config: SensorConfigRegisters,
+        t_config: TConfigRegister,
+    ) -> Result<Self, TMag5273Error> {
         let (config1, config2) = (config.sensor_config1(), config.sensor_config2());
         let Ok(sleep_time) = config1.sleep_time() else {
             return Err(TMag5273Error::MalformedRegister);
@@ -141,40 +175,45 @@ where
             temperature_channel_enabled: t_config.temperature_channel_enabled(),
         })
     }
+}
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Set the interrupt configuration.
+    pub fn set_interrupts(&mut self, config: InterruptConfig) -> Result<(), TMag5273Error> {
+        self.set_config_register(config.into_register())?;
+        Ok(())
+    }
+    /// Set the device configuration.
+    pub fn set_device_config(&mut self, config: DeviceConfig) -> Result<(), TMag5273Error> {
+        let (config1, config2) = config.into_registers();
+        self.set_config_register(config1)?;
+        self.set_config_register(config2)?;
+        Ok(())
+    }
+    /// Set the sensor configuration.
+    pub fn set_sensor_config(&mut self, config: SensorConfig) -> Result<(), TMag5273Error> {
+        let (config1, config2) = config.into_registers();
+        self.set_config_register(config1)?;
+        self.set_config_register(config2)?;
+        Ok(())
+    }
+    /// Get the sensor configuration.
+    pub fn get_sensor_config(&mut self) -> Result<SensorConfig, TMag5273Error> {
+        let config = self.get_dual_config_register::<SensorConfigRegisters>()?;
+        let t_config = self.get_config_register::<TConfigRegister>()?;
+        SensorConfig::from_registers(config, t_config)
+    }
     /// Get the device configuration.
     pub fn get_device_config(&mut self) -> Result<DeviceConfig, TMag5273Error> {
         let config = self.get_dual_config_register::<DeviceConfigRegisters>()?;
-        let (config1, config2) = (config.device_config1(), config.device_config2());
-        let Ok(conv_avg) = config1.conv_avg() else {
-            return Err(TMag5273Error::MalformedRegister);
-        };
-        let Ok(threshold) = config2.threshold() else {
-            return Err(TMag5273Error::MalformedRegister);
-        };
-        Ok(DeviceConfig {
-            i2c_read_mode: config1.i2c_read_mode(),
-            conv_avg,
-            mag_tempo: config1.mag_tempo(),
-            i2c_crc_enabled: config1.i2c_crc_enabled(),
-            operating_mode: config2.operating_mode(),
-            trigger_mode: config2.trigger_mode(),
-            i2c_glitch_filter_enabled: config2.i2c_glitch_filter_enabled(),
-            power_mode: config2.power_mode(),
-            threshold,
-        })
+        DeviceConfig::from_registers(config)
     }
     /// Get the interrupt configuration.
     pub fn get_interrupt_config(&mut self) -> Result<InterruptConfig, TMag5273Error> {
         let config = self.get_config_register::<InterruptConfigRegister>()?;
-        let Ok(interrupt_mode) = config.interrupt_mode() else {
-            return Err(TMag5273Error::MalformedRegister);
-        };
-        Ok(InterruptConfig {
-            int_pin_disabled: config.int_pin_disabled(),
-            interrupt_mode,
-            int_pin_mode: config.int_pin_mode(),
-            threshold_interrupt_enabled: config.threshold_interrupt_enabled(),
-            conversion_complete_interrupt_enabled: config.conversion_complete_interrupt_enabled(),
-        })
+        InterruptConfig::from_register(config)
     }
 }
@@ -0,0 +1,1110 @@
+//! Conversion and validation logic shared by the blocking and async drivers.
+//!
+//! Everything in here is free of I/O so that both driver variants can decode the
+//! same bytes and raise the same errors. The register writes made by the setters and
+//! the timeouts of the waits are worked out here as well, leaving the drivers to do
+//! the transfers.
+use arbitrary_int::u7;
+use embedded_hal::i2c::SevenBitAddress;
+
+#[cfg(feature = "float")]
+use crate::types::{
+    MagneticChannelOffset, TMag5273ChannelData, TMag5273EnabledData, TMag5273FastReadData,
+    TMag5273FrameData,
+};
+#[cfg(feature = "float")]
+use crate::AngleCalibration;
+use crate::{
+    registers::*,
+    types::{
+        Axis, DeviceVersion, HealthReport, RawMagneticSample, RawTemperature, SampleFreshness,
+        TMag5273Error, TMag5273RawChannelData, TMag5273RawEnabledData, TMag5273RawFastReadData,
+        TMag5273RawFrameData,
+    },
+    MANUFACTURER_ID_VALUE,
+};
+
+/// Convert the magnetism data from the device to a floating point value.
+#[cfg(feature = "float")]
+pub(crate) fn convert_magnetism(axis: Axis, data: [u8; 2], range: f32) -> f32 {
+    let data = i16::from_be_bytes(data) as f32;
+    let value = (data * range) / 32768.0;
+    if axis == Axis::X {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Convert the temperature data from the device to a floating point value in (degC).
+#[cfg(feature = "float")]
+pub(crate) fn convert_temp(data: [u8; 2]) -> f32 {
+    const TSENSE_T0: f32 = 25.0; // Reference temperature for TADC_T0
+    const TADC_T0: f32 = 17508.0; // Temp result in decimal value (from 16-bit format)
+    const TADC_RES: f32 = 60.1; // Temperature sensing resolution (in 16-bit format)
+    let raw_temp = u16::from_be_bytes(data) as f32;
+    TSENSE_T0 + ((raw_temp - TADC_T0) / TADC_RES)
+}
+
+/// Convert the angle result registers to degrees.
+#[cfg(feature = "float")]
+pub(crate) fn convert_angle(data: [u8; 2]) -> f32 {
+    // The angle is calculated as follows:
+    //  x x x x x x x x   x x x x x x x x
+    //  |____0x19_____|   |_____0x1A____|
+    //        |_________________| |_____|
+    //                 ^             ^ Divide by 16 to give angle fraction in degrees
+    //                 '- Angle integral in degrees
+    let angle_reg = i16::from_be_bytes(data);
+    let dec_value = (data[1] & 0b1111) as f32 / 16.0;
+    let angle_val = (angle_reg >> 4) as f32;
+    angle_val + dec_value
+}
+
+/// Converts the magnetism data from the device to a raw sample, inverting X like convert_magnetism.
+pub(crate) fn raw_magnetic_sample(axis: Axis, data: [u8; 2], range_mt: u16) -> RawMagneticSample {
+    let code = i32::from(i16::from_be_bytes(data));
+    let code = match axis {
+        Axis::X => -code,
+        Axis::Y | Axis::Z => code,
+    };
+    RawMagneticSample { code, range_mt }
+}
+
+/// Converts the temperature code from the device to hundredths of a degree C, rounded to the nearest.
+/// Integer equivalent of convert_temp, as 60.1 LSB per degC is 601 LSB per 10 degC.
+pub(crate) fn temp_centi_degrees(code: u16) -> i32 {
+    const TSENSE_T0: i32 = 2500; // Reference temperature for TADC_T0 in centi-degC
+    const TADC_T0: i32 = 17508; // Temp result in decimal value (from 16-bit format)
+    const TADC_RES: i32 = 601; // Temperature sensing resolution per 10 degC
+    let scaled = (i32::from(code) - TADC_T0) * 1000;
+    let half = TADC_RES / 2 * scaled.signum();
+    TSENSE_T0 + (scaled + half) / TADC_RES
+}
+
+/// Converts the angle result registers to steps of 1/16 degree, the 9 bit integral part followed by
+/// the 4 bit fraction.
+pub(crate) fn angle_sixteenths(data: [u8; 2]) -> u16 {
+    u16::from_be_bytes(data) & 0x1FFF
+}
+
+/// Checks the manufacturer ID read from the device matches the expected value.
+pub(crate) fn check_manufacturer_id(data: [u8; 2]) -> Result<u16, TMag5273Error> {
+    let manufacturer_id = u16::from_le_bytes(data);
+    match manufacturer_id != MANUFACTURER_ID_VALUE {
+        true => Err(TMag5273Error::WrongDevice),
+        false => Ok(manufacturer_id),
+    }
+}
+
+/// Decodes the device ID register.
+pub(crate) fn convert_device_id(register: DeviceIdRegister) -> Result<DeviceId, TMag5273Error> {
+    register
+        .device_id()
+        .map_err(|_| TMag5273Error::MalformedRegister)
+}
+
+/// Checks the device ID reported by the device matches the chosen hardware version.
+pub(crate) fn check_device_version(
+    device_id: DeviceId,
+    version: DeviceVersion,
+) -> Result<(), TMag5273Error> {
+    match device_id != version.get_device_id() {
+        true => Err(TMag5273Error::WrongDevice),
+        false => Ok(()),
+    }
+}
+
+/// Validates a new 7-bit I2C address, rejecting the addresses reserved by the I2C specification
+/// (0x00 to 0x07 and 0x78 to 0x7F).
+pub(crate) fn check_i2c_address(address: SevenBitAddress) -> Result<u7, TMag5273Error> {
+    match address {
+        0x08..=0x77 => Ok(u7::new(address)),
+        _ => Err(TMag5273Error::OutOfRange),
+    }
+}
+
+/// Validates the address plan for a bus of sensors which all start at `default_address`. Every address must be
+/// valid and unique, and only the last sensor may keep the default address, as the sensors after it are brought
+/// up there. The default address is always allowed, as the TMAG5273C parts start in the reserved range.
+pub(crate) fn check_address_plan(
+    addresses: &[SevenBitAddress],
+    default_address: SevenBitAddress,
+) -> Result<(), TMag5273Error> {
+    for (index, address) in addresses.iter().enumerate() {
+        if *address != default_address {
+            check_i2c_address(*address)?;
+        }
+        let is_last = index + 1 == addresses.len();
+        if addresses[..index].contains(address) || (*address == default_address && !is_last) {
+            return Err(TMag5273Error::AddressInUse);
+        }
+    }
+    Ok(())
+}
+
+/// Checks the temp channel is enabled
+pub(crate) fn check_temp_channel(config: TConfigRegister) -> Result<(), TMag5273Error> {
+    if config.temperature_channel_enabled() {
+        Ok(())
+    } else {
+        Err(TMag5273Error::ChannelDisabled)
+    }
+}
+
+/// Returns the threshold register for the axis, alongside the range (in mT) it is scaled by.
+#[cfg(feature = "float")]
+pub(crate) fn mag_threshold_register(
+    axis: Axis,
+    config: SensorConfig2Register,
+    version: DeviceVersion,
+) -> (TMAG5273Register, f32) {
+    match axis {
+        Axis::X => (
+            TMAG5273Register::XThrConfig,
+            config.xy_range().get_range(version),
+        ),
+        Axis::Y => (
+            TMAG5273Register::YThrConfig,
+            config.xy_range().get_range(version),
+        ),
+        Axis::Z => (
+            TMAG5273Register::ZThrConfig,
+            config.z_range().get_range(version),
+        ),
+    }
+}
+
+/// Converts a threshold in mT to the raw register value.
+#[cfg(feature = "float")]
+pub(crate) fn mag_threshold_to_raw(threshold: f32, range: f32) -> Result<u8, TMag5273Error> {
+    // check that the threshold is within the range of the device
+    if threshold > range || threshold < -range {
+        return Err(TMag5273Error::OutOfRange);
+    }
+    let threshold_normalized = (threshold / range).clamp(-1.0, 1.0);
+    let threshold_raw = (threshold_normalized * 128.0) as i8;
+    Ok(threshold_raw.to_le_bytes()[0])
+}
+
+/// Returns the register write which sets the threshold in mT for the axis.
+#[cfg(feature = "float")]
+pub(crate) fn mag_threshold_write(
+    threshold: f32,
+    axis: Axis,
+    config: SensorConfig2Register,
+    version: DeviceVersion,
+) -> Result<(TMAG5273Register, u8), TMag5273Error> {
+    let (register, range) = mag_threshold_register(axis, config, version);
+    Ok((register, mag_threshold_to_raw(threshold, range)?))
+}
+
+/// Converts the raw threshold register value to mT.
+#[cfg(feature = "float")]
+pub(crate) fn mag_threshold_from_raw(raw: u8, range: f32) -> f32 {
+    let threshold_raw = i8::from_le_bytes([raw]); // always one byte (-128 to 127)
+    let threshold_normalized = threshold_raw as f32 / 128.0; // convert to -1.0 to 1.0
+    threshold_normalized * range
+}
+
+/// Returns the offset register for the channel, alongside the offset resolution in mT per LSB.
+/// The axis each offset applies to is set by the angle calculation: the first axis is always X or Y,
+/// while the second axis is Y for the XY pair and Z otherwise. The resolution is the range of that axis / 2048.
+#[cfg(feature = "float")]
+pub(crate) fn mag_offset_register(
+    mag_offset: MagneticChannelOffset,
+    config: SensorConfig2Register,
+    version: DeviceVersion,
+) -> Result<(TMAG5273Register, f32), TMag5273Error> {
+    const OFFSET_DIVISOR: f32 = 2048.0; // Equation 16 in the datasheet
+    let range = match (config.angle(), mag_offset) {
+        (Angle::Disabled, _) => return Err(TMag5273Error::WrongMode),
+        (_, MagneticChannelOffset::FirstAxis) | (Angle::XY, MagneticChannelOffset::SecondAxis) => {
+            config.xy_range()
+        }
+        (Angle::YZ | Angle::XZ, MagneticChannelOffset::SecondAxis) => config.z_range(),
+    }
+    .get_range(version);
+    Ok((mag_offset.into(), range / OFFSET_DIVISOR))
+}
+
+/// Converts an offset in mT to the raw two's complement register value, rounding to the nearest LSB.
+#[cfg(feature = "float")]
+pub(crate) fn mag_offset_to_raw(offset: f32, resolution: f32) -> Result<u8, TMag5273Error> {
+    let raw_offset = offset / resolution;
+    let raw_offset = match raw_offset >= 0.0 {
+        true => raw_offset + 0.5,
+        false => raw_offset - 0.5,
+    };
+    // check that the offset fits in the register
+    if raw_offset <= i8::MIN as f32 - 1.0 || raw_offset >= i8::MAX as f32 + 1.0 {
+        return Err(TMag5273Error::OutOfRange);
+    }
+    Ok((raw_offset as i8).to_le_bytes()[0])
+}
+
+/// Returns the register write which sets the offset in mT for the channel of the angle calculation.
+#[cfg(feature = "float")]
+pub(crate) fn mag_offset_write(
+    offset: f32,
+    mag_offset: MagneticChannelOffset,
+    config: SensorConfig2Register,
+    version: DeviceVersion,
+) -> Result<(TMAG5273Register, u8), TMag5273Error> {
+    let (register, resolution) = mag_offset_register(mag_offset, config, version)?;
+    Ok((register, mag_offset_to_raw(offset, resolution)?))
+}
+
+/// Returns the register writes which apply an angle calibration, the gain channel first so the gain is applied
+/// to the right channel. Throws a WrongMode error if the device is not set to the angle calculation the
+/// calibration was made for.
+#[cfg(feature = "float")]
+pub(crate) fn angle_calibration_writes(
+    config: SensorConfig2Register,
+    calibration: &AngleCalibration,
+) -> Result<[(TMAG5273Register, u8); 4], TMag5273Error> {
+    if config.angle() != calibration.angle {
+        return Err(TMag5273Error::WrongMode);
+    }
+    Ok([
+        (
+            SensorConfig2Register::get_address(),
+            config
+                .with_gain_channel(calibration.gain_channel)
+                .raw_value(),
+        ),
+        (TMAG5273Register::MagGainConfig, calibration.gain),
+        (
+            TMAG5273Register::MagOffsetConfig1,
+            calibration.first_offset.to_le_bytes()[0],
+        ),
+        (
+            TMAG5273Register::MagOffsetConfig2,
+            calibration.second_offset.to_le_bytes()[0],
+        ),
+    ])
+}
+
+/// Converts the raw two's complement offset register value to mT.
+#[cfg(feature = "float")]
+pub(crate) fn mag_offset_from_raw(raw: u8, resolution: f32) -> f32 {
+    i8::from_le_bytes([raw]) as f32 * resolution
+}
+
+/// Temperature threshold code for -41 degC, the lowest valid threshold.
+#[cfg(feature = "float")]
+const TEMP_THRESHOLD_MIN_CODE: u8 = 0x1A;
+/// Temperature threshold code for 167 degC, the highest valid threshold.
+#[cfg(feature = "float")]
+const TEMP_THRESHOLD_MAX_CODE: u8 = 0x34;
+/// Temperature threshold at the lowest valid code, in degC.
+#[cfg(feature = "float")]
+const TEMP_THRESHOLD_MIN: f32 = -41.0;
+/// Temperature threshold at the highest valid code, in degC.
+#[cfg(feature = "float")]
+const TEMP_THRESHOLD_MAX: f32 = 167.0;
+/// Resolution of the temperature threshold code, in degC per LSB.
+#[cfg(feature = "float")]
+const TEMP_THRESHOLD_RESOLUTION: f32 = 8.0;
+
+/// Converts a temperature threshold in degC to the T_THR_CONFIG code, rounding to the nearest 8 degC step.
+#[cfg(feature = "float")]
+pub(crate) fn temp_threshold_to_code(threshold: f32) -> Result<u7, TMag5273Error> {
+    if !(TEMP_THRESHOLD_MIN..=TEMP_THRESHOLD_MAX).contains(&threshold) {
+        return Err(TMag5273Error::OutOfRange);
+    }
+    let steps = ((threshold - TEMP_THRESHOLD_MIN) / TEMP_THRESHOLD_RESOLUTION + 0.5) as u8;
+    let code = (TEMP_THRESHOLD_MIN_CODE + steps).min(TEMP_THRESHOLD_MAX_CODE);
+    Ok(u7::new(code))
+}
+
+/// Returns the Temperature Config Register with the threshold in degC set, or with the threshold comparison
+/// disabled for `None`.
+#[cfg(feature = "float")]
+pub(crate) fn temp_threshold_config(
+    threshold: Option<f32>,
+    config: TConfigRegister,
+) -> Result<TConfigRegister, TMag5273Error> {
+    let code = match threshold {
+        Some(threshold) => temp_threshold_to_code(threshold)?,
+        None => u7::new(0),
+    };
+    Ok(config.with_t_thr_config(code))
+}
+
+/// Converts the T_THR_CONFIG code to a temperature threshold in degC, or `None` if the threshold is disabled.
+#[cfg(feature = "float")]
+pub(crate) fn temp_threshold_from_code(code: u7) -> Result<Option<f32>, TMag5273Error> {
+    match code.value() {
+        0 => Ok(None),
+        code @ TEMP_THRESHOLD_MIN_CODE..=TEMP_THRESHOLD_MAX_CODE => Ok(Some(
+            TEMP_THRESHOLD_MIN
+                + (code - TEMP_THRESHOLD_MIN_CODE) as f32 * TEMP_THRESHOLD_RESOLUTION,
+        )),
+        _ => Err(TMag5273Error::MalformedRegister),
+    }
+}
+
+/// Verifies the axis is enabled and returns the result register for it, alongside its range in mT.
+pub(crate) fn mag_data_register(
+    axis: Axis,
+    configs: SensorConfigRegisters,
+    version: DeviceVersion,
+) -> Result<(TMAG5273Register, u16), TMag5273Error> {
+    let config2 = configs.sensor_config2();
+    // Verify the axis is enabled
+    let mag_channel = configs.sensor_config1().mag_channel();
+
+    // Guard against fault states
+    match mag_channel {
+        MagneticChannel::Default => return Err(TMag5273Error::WrongMode),
+        MagneticChannel::Reserved1
+        | MagneticChannel::Reserved2
+        | MagneticChannel::Reserved3
+        | MagneticChannel::Reserved4 => return Err(TMag5273Error::MalformedRegister),
+        _ => (),
+    }
+    // Verify that the axis we want is enabled
+    let (register, range) = match axis {
+        Axis::X => match mag_channel {
+            MagneticChannel::Y
+            | MagneticChannel::Z
+            | MagneticChannel::YZ
+            | MagneticChannel::YZY => return Err(TMag5273Error::ChannelDisabled),
+            _ => (TMAG5273Register::XMsbResult, config2.xy_range()),
+        },
+        Axis::Y => match mag_channel {
+            MagneticChannel::X
+            | MagneticChannel::Z
+            | MagneticChannel::XZ
+            | MagneticChannel::XZX => return Err(TMag5273Error::ChannelDisabled),
+            _ => (TMAG5273Register::YMSBResult, config2.xy_range()),
+        },
+        Axis::Z => match mag_channel {
+            MagneticChannel::X
+            | MagneticChannel::Y
+            | MagneticChannel::XY
+            | MagneticChannel::XYX => return Err(TMag5273Error::ChannelDisabled),
+            _ => (TMAG5273Register::ZMSBResult, config2.z_range()),
+        },
+    };
+    Ok((register, range.get_range_mt(version)))
+}
+
+/// Verifies the channels needed for a full data read are enabled and returns the XY and Z ranges in mT.
+pub(crate) fn all_data_ranges(
+    configs: SensorConfigRegisters,
+    t_config: TConfigRegister,
+    version: DeviceVersion,
+) -> Result<(u16, u16), TMag5273Error> {
+    // Only valid in XYZ mode
+    if configs.sensor_config1().mag_channel() != MagneticChannel::XYZ {
+        return Err(TMag5273Error::ChannelDisabled);
+    }
+    // Verify the temperature channel is enabled
+    check_temp_channel(t_config)?;
+    let config2 = configs.sensor_config2();
+    Ok((
+        config2.xy_range().get_range_mt(version),
+        config2.z_range().get_range_mt(version),
+    ))
+}
+
+/// Decodes a full data read starting at `TMsbResult`.
+#[cfg(feature = "float")]
+pub(crate) fn convert_all_data(data: [u8; 8], xy_range: u16, z_range: u16) -> TMag5273ChannelData {
+    let temp = convert_temp([data[0], data[1]]);
+    let x = convert_magnetism(Axis::X, [data[2], data[3]], xy_range.into());
+    let y = convert_magnetism(Axis::Y, [data[4], data[5]], xy_range.into());
+    let z = convert_magnetism(Axis::Z, [data[6], data[7]], z_range.into());
+    TMag5273ChannelData { x, y, z, temp }
+}
+
+/// Decodes a full data read starting at `TMsbResult`, without any floating point conversion.
+pub(crate) fn convert_raw_all_data(
+    data: [u8; 8],
+    xy_range: u16,
+    z_range: u16,
+) -> TMag5273RawChannelData {
+    TMag5273RawChannelData {
+        x: raw_magnetic_sample(Axis::X, [data[2], data[3]], xy_range),
+        y: raw_magnetic_sample(Axis::Y, [data[4], data[5]], xy_range),
+        z: raw_magnetic_sample(Axis::Z, [data[6], data[7]], z_range),
+        temp: RawTemperature {
+            code: u16::from_be_bytes([data[0], data[1]]),
+        },
+    }
+}
+
+/// Checks the angle calculation is enabled
+pub(crate) fn check_angle_enabled(config: SensorConfig2Register) -> Result<(), TMag5273Error> {
+    if config.angle() == Angle::Disabled {
+        return Err(TMag5273Error::ChannelDisabled);
+    }
+    Ok(())
+}
+
+/// Checks the device does not compensate the magnet temperature drift itself, so a software compensation is not
+/// applied on top of it.
+#[cfg(feature = "float")]
+pub(crate) fn check_compensation_conflict(
+    config: DeviceConfig1Register,
+) -> Result<(), TMag5273Error> {
+    match config.mag_tempo() {
+        MagnetTemperatureCoefficient::ZeroCompensation => Ok(()),
+        _ => Err(TMag5273Error::CompensationConflict),
+    }
+}
+
+/// Setting the MSB of the register address starts a conversion when the device is waiting for an I2C trigger.
+pub(crate) const TRIGGER_BIT: u8 = 0x80;
+
+/// Time between reads of the Conversion Status Register while waiting for a conversion, in us.
+pub(crate) const CONVERSION_POLL_INTERVAL_US: u32 = 50;
+
+/// Keeps track of the time spent waiting on the device, for the waits which poll it until a timeout.
+pub(crate) struct PollTimeout {
+    interval_us: u32,
+    timeout_us: u32,
+    waited_us: u32,
+}
+
+impl PollTimeout {
+    /// Starts a wait which polls every `interval_us`, for up to `timeout_us`.
+    pub(crate) fn new(interval_us: u32, timeout_us: u32) -> Self {
+        Self {
+            interval_us,
+            timeout_us,
+            waited_us: 0,
+        }
+    }
+
+    /// Returns the time to wait before polling again, or throws a Timeout error once the timeout has passed.
+    pub(crate) fn next_delay(&mut self) -> Result<u32, TMag5273Error> {
+        if self.waited_us >= self.timeout_us {
+            return Err(TMag5273Error::Timeout);
+        }
+        self.waited_us = self.waited_us.saturating_add(self.interval_us);
+        Ok(self.interval_us)
+    }
+}
+
+/// Registers changed by init_default, in the order they are written. DeviceConfig2 goes last, so the device only
+/// starts measuring once the channels and ranges are in place.
+pub(crate) const DEFAULT_CONFIG_REGISTERS: [TMAG5273Register; 4] = [
+    TMAG5273Register::SensorConfig1,
+    TMAG5273Register::SensorConfig2,
+    TMAG5273Register::TConfig,
+    TMAG5273Register::DeviceConfig2,
+];
+
+/// Sets the fields changed by init_default on the value read from one of the DEFAULT_CONFIG_REGISTERS.
+pub(crate) fn default_config(register: TMAG5273Register, value: u8) -> u8 {
+    match register {
+        TMAG5273Register::SensorConfig1 => SensorConfig1Register::new_with_raw_value(value)
+            .with_sleep_time(SleepTime::Ms20000)
+            .with_mag_channel(MagneticChannel::XYZ)
+            .raw_value(),
+        TMAG5273Register::SensorConfig2 => SensorConfig2Register::new_with_raw_value(value)
+            .with_xy_range(Range::High)
+            .with_z_range(Range::High)
+            .raw_value(),
+        TMAG5273Register::TConfig => TConfigRegister::new_with_raw_value(value)
+            .with_temperature_channel_enabled(true)
+            .raw_value(),
+        TMAG5273Register::DeviceConfig2 => DeviceConfig2Register::new_with_raw_value(value)
+            .with_operating_mode(OperatingMode::ContinuousMeasure)
+            .raw_value(),
+        _ => value,
+    }
+}
+
+/// Checks the device is in stand-by mode with conversions started by the I2C trigger.
+pub(crate) fn check_i2c_trigger(config: DeviceConfig2Register) -> Result<(), TMag5273Error> {
+    match (config.operating_mode(), config.trigger_mode()) {
+        (OperatingMode::StandBy, TriggerMode::Default) => Ok(()),
+        _ => Err(TMag5273Error::WrongMode),
+    }
+}
+
+/// Checks if a conversion started after `previous` was read has completed. The set count is compared as well
+/// as the ready flag, so the data from an earlier conversion is not mistaken for the new one.
+pub(crate) fn conversion_complete(
+    previous: ConversionStatusRegister,
+    status: ConversionStatusRegister,
+) -> bool {
+    status.conversion_ready() && status.set_count() != previous.set_count()
+}
+
+/// Length of the burst from TMsbResult (0x10) up to and including the Conversion Status Register (0x18).
+pub(crate) const SAMPLE_BURST_LEN: usize = 9;
+
+/// Works out how a sample relates to the previous one from their set counts. The count rolls over after 8
+/// conversions, so 8 or more missed conversions can not be told apart from fewer.
+pub(crate) fn sample_freshness(previous: Option<u8>, set_count: u8) -> SampleFreshness {
+    let Some(previous) = previous else {
+        return SampleFreshness::Fresh;
+    };
+    match set_count.wrapping_sub(previous) & 0b111 {
+        0 => SampleFreshness::Stale,
+        1 => SampleFreshness::Fresh,
+        step => SampleFreshness::Missed(step - 1),
+    }
+}
+
+/// Splits a burst from TMsbResult up to the Conversion Status Register into the result registers and the set count.
+pub(crate) fn split_sample(data: [u8; SAMPLE_BURST_LEN]) -> ([u8; 8], u8) {
+    let mut results = [0x00; 8];
+    results.copy_from_slice(&data[..8]);
+    let status = ConversionStatusRegister::new_with_raw_value(data[SAMPLE_BURST_LEN - 1]);
+    (results, status.set_count().value())
+}
+
+/// Checks the conversion complete interrupt is sent through the INT pin, returning true if it is latched.
+pub(crate) fn check_conversion_interrupt(
+    config: InterruptConfigRegister,
+) -> Result<bool, TMag5273Error> {
+    let int_pin_used = matches!(
+        config.interrupt_mode(),
+        Ok(InterruptMode::INTInterrupt | InterruptMode::INTInterruptNotI2cBusy)
+    );
+    if !int_pin_used || config.int_pin_disabled() || !config.conversion_complete_interrupt_enabled()
+    {
+        return Err(TMag5273Error::WrongMode);
+    }
+    Ok(matches!(config.int_pin_mode(), INTPinMode::Latched))
+}
+
+/// Length of the burst from the Conversion Status Register (0x18) up to and including DeviceStatus (0x1C).
+pub(crate) const HEALTH_BURST_LEN: usize = 5;
+
+/// The error flags of the Device Status Register, which are cleared by writing '1'.
+pub(crate) const DEVICE_STATUS_ERRORS: u8 = 0x0F;
+
+/// Returns the Device Status Register write which clears the error flags set in a health burst, or `None` if no
+/// flag is set. The error flags are cleared by writing '1'.
+pub(crate) fn clear_errors_write(data: &[u8; HEALTH_BURST_LEN]) -> Option<(TMAG5273Register, u8)> {
+    let errors = data[HEALTH_BURST_LEN - 1] & DEVICE_STATUS_ERRORS;
+    (errors != 0).then_some((TMAG5273Register::DeviceStatus, errors))
+}
+
+/// Decodes the fault flags from a burst starting at the Conversion Status Register and ending at the Device Status
+/// Register.
+pub(crate) fn health_report(data: [u8; HEALTH_BURST_LEN]) -> HealthReport {
+    let conversion_status = ConversionStatusRegister::new_with_raw_value(data[0]);
+    let device_status = DeviceStatusRegister::new_with_raw_value(data[HEALTH_BURST_LEN - 1]);
+    HealthReport {
+        vcc_under_voltage: device_status.vcc_under_voltage_error(),
+        otp_crc_error: device_status.crc_error(),
+        int_pin_error: device_status.int_pin_error(),
+        oscillator_error: device_status.oscillator_error(),
+        diagnostic_error: conversion_status.diagnostic_error(),
+        power_on_reset: conversion_status.power_on_reset(),
+    }
+}
+
+/// Longest burst of the result registers, from TMsbResult (0x10) up to and including DeviceStatus (0x1C).
+pub(crate) const MAX_RESULTS_BURST_LEN: usize = 13;
+
+/// Length of the full frame, from TMsbResult (0x10) up to and including DeviceStatus (0x1C).
+pub(crate) const FRAME_LEN: usize = MAX_RESULTS_BURST_LEN;
+
+/// Decodes a full frame read starting at `TMsbResult`, keeping only the fields valid for the configuration.
+pub(crate) fn convert_raw_frame(
+    data: [u8; FRAME_LEN],
+    configs: SensorConfigRegisters,
+    t_config: TConfigRegister,
+    version: DeviceVersion,
+) -> Result<TMag5273RawFrameData, TMag5273Error> {
+    let (x, y, z) = enabled_axes(configs.sensor_config1().mag_channel())?;
+    let config2 = configs.sensor_config2();
+    let xy_range = config2.xy_range().get_range_mt(version);
+    let z_range = config2.z_range().get_range_mt(version);
+    let angle_enabled = config2.angle() != Angle::Disabled;
+    Ok(TMag5273RawFrameData {
+        temp: t_config
+            .temperature_channel_enabled()
+            .then_some(RawTemperature {
+                code: u16::from_be_bytes([data[0], data[1]]),
+            }),
+        x: x.then(|| raw_magnetic_sample(Axis::X, [data[2], data[3]], xy_range)),
+        y: y.then(|| raw_magnetic_sample(Axis::Y, [data[4], data[5]], xy_range)),
+        z: z.then(|| raw_magnetic_sample(Axis::Z, [data[6], data[7]], z_range)),
+        conversion_status: ConversionStatusRegister::new_with_raw_value(data[8]),
+        angle: angle_enabled.then_some(angle_sixteenths([data[9], data[10]])),
+        magnitude: angle_enabled.then_some(data[11]),
+        device_status: DeviceStatusRegister::new_with_raw_value(data[12]),
+    })
+}
+
+/// Converts a decoded magnetic sample to mT, matching convert_magnetism as the code is within ±32768.
+#[cfg(feature = "float")]
+fn sample_millitesla(sample: RawMagneticSample) -> f32 {
+    (sample.code as f32 * f32::from(sample.range_mt)) / 32768.0
+}
+
+/// Converts a decoded temperature to degrees C.
+#[cfg(feature = "float")]
+fn temperature_celsius(temp: RawTemperature) -> f32 {
+    convert_temp(temp.code.to_be_bytes())
+}
+
+/// Converts a decoded full frame to floating point values.
+#[cfg(feature = "float")]
+pub(crate) fn convert_frame(frame: TMag5273RawFrameData) -> TMag5273FrameData {
+    TMag5273FrameData {
+        temp: frame.temp.map(temperature_celsius),
+        x: frame.x.map(sample_millitesla),
+        y: frame.y.map(sample_millitesla),
+        z: frame.z.map(sample_millitesla),
+        conversion_status: frame.conversion_status,
+        angle: frame.angle.map(|angle| f32::from(angle) / 16.0),
+        magnitude: frame.magnitude,
+        device_status: frame.device_status,
+    }
+}
+
+/// The channels enabled on the device, alongside the ranges in mT the axes are scaled by.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct EnabledChannels {
+    temp: bool,
+    x: bool,
+    y: bool,
+    z: bool,
+    xy_range: u16,
+    z_range: u16,
+}
+
+impl EnabledChannels {
+    /// Works out the enabled channels from the configuration registers. Returns `ChannelDisabled` if neither an
+    /// axis nor the temperature is enabled.
+    pub(crate) fn new(
+        configs: SensorConfigRegisters,
+        t_config: TConfigRegister,
+        version: DeviceVersion,
+    ) -> Result<Self, TMag5273Error> {
+        let (x, y, z) = enabled_axes(configs.sensor_config1().mag_channel())?;
+        let temp = t_config.temperature_channel_enabled();
+        if !(temp || x || y || z) {
+            return Err(TMag5273Error::ChannelDisabled);
+        }
+        let config2 = configs.sensor_config2();
+        Ok(Self {
+            temp,
+            x,
+            y,
+            z,
+            xy_range: config2.xy_range().get_range_mt(version),
+            z_range: config2.z_range().get_range_mt(version),
+        })
+    }
+
+    /// The span of the result registers covering the enabled channels, as the offsets from TMsbResult of its first
+    /// byte and of the byte after it. Channels in between which are not enabled are read as well, as a single
+    /// transfer is cheaper than one per channel.
+    pub(crate) fn span(&self) -> (usize, usize) {
+        let enabled = [self.temp, self.x, self.y, self.z];
+        let first = enabled.iter().position(|enabled| *enabled).unwrap_or(0);
+        let last = enabled.iter().rposition(|enabled| *enabled).unwrap_or(0);
+        (first * 2, (last + 1) * 2)
+    }
+
+    /// Works out the enabled channels again from the configuration refreshed after a power-on-reset. The data was
+    /// read for the old channels, so a ChannelDisabled error is thrown if they no longer cover the same span.
+    pub(crate) fn refreshed(
+        &self,
+        configs: SensorConfigRegisters,
+        t_config: TConfigRegister,
+        version: DeviceVersion,
+    ) -> Result<Self, TMag5273Error> {
+        let refreshed = Self::new(configs, t_config, version)?;
+        if refreshed.span() != self.span() {
+            return Err(TMag5273Error::ChannelDisabled);
+        }
+        Ok(refreshed)
+    }
+
+    /// The register the read of the span starts at.
+    pub(crate) fn start_register(&self) -> Result<TMAG5273Register, TMag5273Error> {
+        let (start, _) = self.span();
+        TMAG5273Register::try_from(u8::from(TMAG5273Register::TMsbResult) + start as u8)
+            .map_err(|_| TMag5273Error::MalformedRegister)
+    }
+
+    /// Decodes the enabled channels from the result registers, laid out from TMsbResult.
+    pub(crate) fn decode_raw(&self, data: [u8; 8]) -> TMag5273RawEnabledData {
+        TMag5273RawEnabledData {
+            temp: self.temp.then_some(RawTemperature {
+                code: u16::from_be_bytes([data[0], data[1]]),
+            }),
+            x: self
+                .x
+                .then(|| raw_magnetic_sample(Axis::X, [data[2], data[3]], self.xy_range)),
+            y: self
+                .y
+                .then(|| raw_magnetic_sample(Axis::Y, [data[4], data[5]], self.xy_range)),
+            z: self
+                .z
+                .then(|| raw_magnetic_sample(Axis::Z, [data[6], data[7]], self.z_range)),
+        }
+    }
+}
+
+/// Converts the decoded enabled channels to floating point values.
+#[cfg(feature = "float")]
+pub(crate) fn convert_enabled_data(data: TMag5273RawEnabledData) -> TMag5273EnabledData {
+    TMag5273EnabledData {
+        x: data.x.map(sample_millitesla),
+        y: data.y.map(sample_millitesla),
+        z: data.z.map(sample_millitesla),
+        temp: data.temp.map(temperature_celsius),
+    }
+}
+
+/// Value written to the Conversion Status Register to clear the POR flag, which is cleared by writing '1'.
+pub(crate) const CLEAR_POR: u8 = 0x10;
+
+/// What a driver does once the POR flag read alongside the data has been checked.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ResetAction {
+    /// The device has not been reset, so the data can be used
+    None,
+    /// Auto recovery restores the device, and the data read from the reset device is dropped
+    Recover,
+    /// The shadow is refreshed from the reset device and the POR flag cleared, then the configuration the data
+    /// was decoded against is checked again
+    Refresh,
+}
+
+/// Works out how to handle the POR flag of the Conversion Status Register read alongside the data.
+pub(crate) fn reset_action(status: ConversionStatusRegister, auto_recovery: bool) -> ResetAction {
+    match (status.power_on_reset(), auto_recovery) {
+        (false, _) => ResetAction::None,
+        (true, true) => ResetAction::Recover,
+        (true, false) => ResetAction::Refresh,
+    }
+}
+
+/// A read of result registers widened so that it also covers the Conversion Status Register, which carries the
+/// POR flag checked alongside the data.
+pub(crate) struct ResultsBurst {
+    /// First register of the burst
+    pub(crate) start: TMAG5273Register,
+    /// Offset of the requested data within the burst
+    offset: usize,
+    /// Offset of the Conversion Status Register within the burst
+    status_offset: usize,
+    len: usize,
+    buffer: [u8; MAX_RESULTS_BURST_LEN],
+}
+
+impl ResultsBurst {
+    /// Widens a read of `len` result registers starting at `register`.
+    pub(crate) fn new(register: TMAG5273Register, len: usize) -> Result<Self, TMag5273Error> {
+        let status = u8::from(TMAG5273Register::ConvStatus) as usize;
+        let first = u8::from(TMAG5273Register::TMsbResult) as usize;
+        let requested = u8::from(register) as usize;
+        if requested < first || requested + len > first + MAX_RESULTS_BURST_LEN {
+            return Err(TMag5273Error::OutOfRange);
+        }
+        let start = requested.min(status);
+        let end = (requested + len).max(status + 1);
+        Ok(Self {
+            start: TMAG5273Register::try_from(start as u8)
+                .map_err(|_| TMag5273Error::MalformedRegister)?,
+            offset: requested - start,
+            status_offset: status - start,
+            len: end - start,
+            buffer: [0x00; MAX_RESULTS_BURST_LEN],
+        })
+    }
+
+    /// The registers of the burst, to be read from the device starting at `start`.
+    pub(crate) fn buffer(&mut self) -> &mut [u8] {
+        &mut self.buffer[..self.len]
+    }
+
+    /// Copies the requested data out of the burst once it has been read, returning the Conversion Status Register.
+    pub(crate) fn split(&self, data: &mut [u8]) -> ConversionStatusRegister {
+        data.copy_from_slice(&self.buffer[self.offset..self.offset + data.len()]);
+        ConversionStatusRegister::new_with_raw_value(self.buffer[self.status_offset])
+    }
+}
+
+/// Number of register bytes covered by each CRC byte in a standard 3-byte read.
+pub(crate) const CRC_DATA_LEN: usize = 4;
+
+/// Length of a standard 3-byte read frame with the CRC enabled, the register bytes followed by their CRC.
+pub(crate) const CRC_FRAME_LEN: usize = CRC_DATA_LEN + 1;
+
+/// Calculates the CRC the device sends during an I2C read. The code is generated by the polynomial
+/// x^8 + x^2 + x + 1, with the initial CRC bits set to 0xFF.
+pub(crate) fn crc8(data: &[u8]) -> u8 {
+    crc8_update(0xFF, data)
+}
+
+/// Continues a CRC calculation over `data`, starting from a previously calculated `crc`.
+fn crc8_update(crc: u8, data: &[u8]) -> u8 {
+    const POLYNOMIAL: u8 = 0x07;
+    data.iter().fold(crc, |crc, byte| {
+        (0..8).fold(crc ^ byte, |crc, _| match crc & 0x80 != 0 {
+            true => (crc << 1) ^ POLYNOMIAL,
+            false => crc << 1,
+        })
+    })
+}
+
+/// Returns the register address a CRC read frame should start from in order to cover `address`.
+/// In the standard 3-byte read the CRC is only sent after 4 register bytes, so frames near the
+/// end of the register map are moved back to avoid reading past the Device Status Register.
+fn crc_frame_address(address: u8) -> u8 {
+    let last_frame_address = u8::from(TMAG5273Register::DeviceStatus) + 1 - CRC_DATA_LEN as u8;
+    address.min(last_frame_address)
+}
+
+/// Verifies a standard 3-byte read frame (4 register bytes followed by their CRC) and returns the register bytes.
+fn check_crc_frame(frame: [u8; CRC_FRAME_LEN]) -> Result<[u8; CRC_DATA_LEN], TMag5273Error> {
+    let data = [frame[0], frame[1], frame[2], frame[3]];
+    match crc8(&data) != frame[CRC_DATA_LEN] {
+        true => Err(TMag5273Error::CrcMismatch),
+        false => Ok(data),
+    }
+}
+
+/// A CRC read frame covering one chunk of a register read.
+pub(crate) struct CrcFrame {
+    /// Register address the frame is read from
+    pub(crate) address: u8,
+    /// Position of the chunk within the register bytes of the frame
+    offset: usize,
+}
+
+impl CrcFrame {
+    /// Verifies the frame read from the device and copies the register bytes of the chunk out of it.
+    pub(crate) fn copy_to(
+        &self,
+        frame: [u8; CRC_FRAME_LEN],
+        chunk: &mut [u8],
+    ) -> Result<(), TMag5273Error> {
+        let data = check_crc_frame(frame)?;
+        let data = data
+            .get(self.offset..self.offset + chunk.len())
+            .ok_or(TMag5273Error::OutOfRange)?;
+        chunk.copy_from_slice(data);
+        Ok(())
+    }
+}
+
+/// Splits a read of consecutive registers starting at `register` into the CRC frames covering it, each paired
+/// with the part of `data` it fills.
+pub(crate) fn crc_frames(
+    register: TMAG5273Register,
+    data: &mut [u8],
+) -> impl Iterator<Item = (CrcFrame, &mut [u8])> {
+    let register_address = u8::from(register);
+    data.chunks_mut(CRC_DATA_LEN)
+        .enumerate()
+        .map(move |(index, chunk)| {
+            let chunk_address = register_address + (index * CRC_DATA_LEN) as u8;
+            let address = crc_frame_address(chunk_address);
+            let offset = (chunk_address - address) as usize;
+            (CrcFrame { address, offset }, chunk)
+        })
+}
+
+/// Returns the I2C Address Register write which moves a device answering at another address back to `address`.
+pub(crate) fn address_move(address: SevenBitAddress) -> Result<[u8; 2], TMag5273Error> {
+    let register = I2cAddressRegister::new_with_raw_value(0)
+        .with_i2c_address(check_i2c_address(address)?)
+        .with_i2c_address_update_enabled(true);
+    Ok([TMAG5273Register::I2CAddress.into(), register.raw_value()])
+}
+
+/// Returns which magnetic axes (X, Y, Z) are sampled for the magnetic channel setting.
+/// Channels repeated in the pseudo-simultaneous modes (e.g. XYX) are only reported once.
+pub(crate) fn enabled_axes(
+    mag_channel: MagneticChannel,
+) -> Result<(bool, bool, bool), TMag5273Error> {
+    match mag_channel {
+        MagneticChannel::Default => Ok((false, false, false)),
+        MagneticChannel::X => Ok((true, false, false)),
+        MagneticChannel::Y => Ok((false, true, false)),
+        MagneticChannel::XY | MagneticChannel::XYX | MagneticChannel::YXY => {
+            Ok((true, true, false))
+        }
+        MagneticChannel::Z => Ok((false, false, true)),
+        MagneticChannel::XZ | MagneticChannel::XZX => Ok((true, false, true)),
+        MagneticChannel::YZ | MagneticChannel::YZY => Ok((false, true, true)),
+        MagneticChannel::XYZ => Ok((true, true, true)),
+        MagneticChannel::Reserved1
+        | MagneticChannel::Reserved2
+        | MagneticChannel::Reserved3
+        | MagneticChannel::Reserved4 => Err(TMag5273Error::MalformedRegister),
+    }
+}
+
+/// Returns the magnetic channel setting which enables the given axes.
+/// Returns a ChannelDisabled error if no axis is enabled.
+#[cfg(feature = "float")]
+pub(crate) fn mag_channel_from_axes(
+    x: bool,
+    y: bool,
+    z: bool,
+) -> Result<MagneticChannel, TMag5273Error> {
+    match (x, y, z) {
+        (false, false, false) => Err(TMag5273Error::ChannelDisabled),
+        (true, false, false) => Ok(MagneticChannel::X),
+        (false, true, false) => Ok(MagneticChannel::Y),
+        (true, true, false) => Ok(MagneticChannel::XY),
+        (false, false, true) => Ok(MagneticChannel::Z),
+        (true, false, true) => Ok(MagneticChannel::XZ),
+        (false, true, true) => Ok(MagneticChannel::YZ),
+        (true, true, true) => Ok(MagneticChannel::XYZ),
+    }
+}
+
+/// Checks if a result register value is past a threshold set with the 2's complement threshold mode.
+/// Both are compared with the sign of the result register, which is how the device compares them.
+#[cfg(feature = "float")]
+pub(crate) fn threshold_crossed(data: [u8; 2], range: f32, threshold: f32, below: bool) -> bool {
+    let value = (i16::from_be_bytes(data) as f32 * range) / 32768.0;
+    match below {
+        true => value < threshold,
+        false => value > threshold,
+    }
+}
+
+/// Largest packet sent in a 1-byte read: 4 channels of 16 bit data, the conversion status and the CRC.
+pub(crate) const MAX_FAST_READ_LEN: usize = 10;
+
+/// Layout of the packet the device sends in one of the 1-byte I2C read modes.
+/// The channels are sent in the T, X, Y, Z order, followed by the Conversion Status Register
+/// and, if enabled, a CRC calculated over the command byte and the data.
+pub(crate) struct FastReadFrame {
+    temp: bool,
+    /// Ranges in mT of the enabled axes
+    x: Option<u16>,
+    y: Option<u16>,
+    z: Option<u16>,
+    sample_len: usize,
+    crc_enabled: bool,
+}
+
+impl FastReadFrame {
+    /// Works out the packet layout from the configuration registers.
+    /// Returns `WrongMode` if the device is not configured for a 1-byte read, or if more than 3 channels
+    /// are enabled in the 16 bit mode with CRC, which the device does not support.
+    pub(crate) fn new(
+        device_config_1: DeviceConfig1Register,
+        sensor_config: SensorConfigRegisters,
+        t_config: TConfigRegister,
+        version: DeviceVersion,
+    ) -> Result<Self, TMag5273Error> {
+        let sample_len = match device_config_1.i2c_read_mode() {
+            I2cReadMode::OneByte16Bit => 2,
+            I2cReadMode::TwoByte8Bit => 1,
+            I2cReadMode::Standard3Byte | I2cReadMode::Reserved => {
+                return Err(TMag5273Error::WrongMode)
+            }
+        };
+        let crc_enabled = device_config_1.i2c_crc_enabled();
+        let (x, y, z) = enabled_axes(sensor_config.sensor_config1().mag_channel())?;
+        let temp = t_config.temperature_channel_enabled();
+        let channels = [temp, x, y, z].iter().filter(|enabled| **enabled).count();
+        if sample_len == 2 && crc_enabled && channels > 3 {
+            return Err(TMag5273Error::WrongMode);
+        }
+
+        let config2 = sensor_config.sensor_config2();
+        let xy_range = config2.xy_range().get_range_mt(version);
+        let z_range = config2.z_range().get_range_mt(version);
+        Ok(Self {
+            temp,
+            x: x.then_some(xy_range),
+            y: y.then_some(xy_range),
+            z: z.then_some(z_range),
+            sample_len,
+            crc_enabled,
+        })
+    }
+
+    /// Number of bytes the device sends in the packet.
+    pub(crate) fn len(&self) -> usize {
+        let channels = [
+            self.temp,
+            self.x.is_some(),
+            self.y.is_some(),
+            self.z.is_some(),
+        ]
+        .iter()
+        .filter(|enabled| **enabled)
+        .count();
+        channels * self.sample_len + 1 + self.crc_enabled as usize
+    }
+
+    /// Verifies and decodes a packet read from the device at `address`.
+    pub(crate) fn decode(
+        &self,
+        address: u8,
+        frame: &[u8],
+    ) -> Result<TMag5273RawFastReadData, TMag5273Error> {
+        if frame.len() != self.len() {
+            return Err(TMag5273Error::OutOfRange);
+        }
+        let frame = match self.crc_enabled {
+            true => {
+                let (data, crc) = frame.split_at(frame.len() - 1);
+                // The CRC covers the command byte, which is the address with the read bit set
+                let command = crc8(&[(address << 1) | 1]);
+                if crc8_update(command, data) != crc[0] {
+                    return Err(TMag5273Error::CrcMismatch);
+                }
+                data
+            }
+            false => frame,
+        };
+        let (samples, status) = frame.split_at(frame.len() - 1);
+        let mut samples = samples.chunks(self.sample_len).map(|sample| match sample {
+            [msb, lsb] => [*msb, *lsb],
+            // Only the MSB is sent in the 8 bit mode
+            [msb] => [*msb, 0x00],
+            _ => [0x00, 0x00],
+        });
+
+        let temp = match self.temp {
+            true => samples.next().map(|sample| RawTemperature {
+                code: u16::from_be_bytes(sample),
+            }),
+            false => None,
+        };
+        let mut next_axis = |axis: Axis, range: Option<u16>| {
+            range.and_then(|range| {
+                samples
+                    .next()
+                    .map(|sample| raw_magnetic_sample(axis, sample, range))
+            })
+        };
+        let x = next_axis(Axis::X, self.x);
+        let y = next_axis(Axis::Y, self.y);
+        let z = next_axis(Axis::Z, self.z);
+        Ok(TMag5273RawFastReadData {
+            temp,
+            x,
+            y,
+            z,
+            conversion_status: ConversionStatusRegister::new_with_raw_value(status[0]),
+        })
+    }
+}
+
+/// Converts a decoded 1-byte read packet to floating point values.
+#[cfg(feature = "float")]
+pub(crate) fn convert_fast_read_data(data: TMag5273RawFastReadData) -> TMag5273FastReadData {
+    TMag5273FastReadData {
+        x: data.x.map(sample_millitesla),
+        y: data.y.map(sample_millitesla),
+        z: data.z.map(sample_millitesla),
+        temp: data.temp.map(temperature_celsius),
+        conversion_status: data.conversion_status,
+    }
+}
@@ -0,0 +1,156 @@
+//! # Bus Discovery
+//!
+//! Finds the TMAG5273 parts on a bus without knowing their variant up front, for tooling such as factory test
+//! stations. Every address which acknowledges is reported, along with whether it holds a TMAG5273 and which
+//! variants it could be.
+use core::ops::RangeInclusive;
+
+use embedded_hal::i2c::{Error, ErrorKind, I2c, SevenBitAddress};
+
+use crate::{
+    conversions,
+    registers::*,
+    types::{DeviceVersion, TMag5273Error},
+};
+
+/// Every variant of the TMAG5273.
+const VERSIONS: [DeviceVersion; 8] = [
+    DeviceVersion::TMAG5273A1,
+    DeviceVersion::TMAG5273B1,
+    DeviceVersion::TMAG5273C1,
+    DeviceVersion::TMAG5273D1,
+    DeviceVersion::TMAG5273A2,
+    DeviceVersion::TMAG5273B2,
+    DeviceVersion::TMAG5273C2,
+    DeviceVersion::TMAG5273D2,
+];
+
+/// Addresses probed by a full scan: every address outside the reserved ranges, plus 0x78 which the TMAG5273C
+/// parts start at.
+const SCAN_ADDRESSES: RangeInclusive<SevenBitAddress> = 0x08..=0x78;
+
+/// A device which acknowledged its address during a scan.
+#[derive(Debug, PartialEq)]
+pub struct DiscoveredDevice {
+    /// The address the device answered at
+    pub address: SevenBitAddress,
+    /// Whether the manufacturer ID matches [MANUFACTURER_ID_VALUE](crate::MANUFACTURER_ID_VALUE)
+    pub manufacturer_id_matches: bool,
+    /// The device ID, `None` if the device is not a TMAG5273 or the register is malformed
+    pub device_id: Option<DeviceId>,
+}
+
+impl DiscoveredDevice {
+    /// Returns the variants the device could be. At a factory address this is the variant which starts there,
+    /// while a device which has been moved to another address could be any variant with its device ID.
+    pub fn version_candidates(&self) -> impl Iterator<Item = DeviceVersion> + '_ {
+        let moved = !VERSIONS
+            .iter()
+            .any(|version| version.get_default_address() == self.address);
+        VERSIONS.into_iter().filter(move |version| {
+            self.manufacturer_id_matches
+                && Some(version.get_device_id()) == self.device_id
+                && (moved || version.get_default_address() == self.address)
+        })
+    }
+}
+
+/// Iterator over the devices found on a bus, created by [scan_bus] or [scan_factory_addresses].
+/// The addresses are probed lazily, as the iterator is advanced.
+pub struct BusScan<'a, I2C, A> {
+    i2c: &'a mut I2C,
+    addresses: A,
+}
+
+/// Probes every 7-bit address outside the reserved ranges, as well as 0x78 where the TMAG5273C parts start.
+///
+/// ## Example
+///
+/// ```Rust
+/// for device in scan_bus(&mut i2c) {
+///     let device = device?;
+///     for version in device.version_candidates() {
+///         println!("{:x}: {version:?}", device.address);
+///     }
+/// }
+/// ```
+pub fn scan_bus<I2C>(i2c: &mut I2C) -> BusScan<'_, I2C, RangeInclusive<SevenBitAddress>>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    BusScan {
+        i2c,
+        addresses: SCAN_ADDRESSES,
+    }
+}
+
+/// Probes only the factory addresses of the A, B, C and D variants, which is much quicker than a full scan.
+pub fn scan_factory_addresses<I2C>(
+    i2c: &mut I2C,
+) -> BusScan<'_, I2C, core::array::IntoIter<SevenBitAddress, 4>>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    let addresses = [
+        DeviceVersion::TMAG5273A1,
+        DeviceVersion::TMAG5273B1,
+        DeviceVersion::TMAG5273C1,
+        DeviceVersion::TMAG5273D1,
+    ]
+    .map(DeviceVersion::get_default_address);
+    BusScan {
+        i2c,
+        addresses: addresses.into_iter(),
+    }
+}
+
+impl<I2C, A> Iterator for BusScan<'_, I2C, A>
+where
+    I2C: I2c<SevenBitAddress>,
+    A: Iterator<Item = SevenBitAddress>,
+{
+    type Item = Result<DiscoveredDevice, TMag5273Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.addresses
+            .by_ref()
+            .find_map(|address| probe(self.i2c, address).transpose())
+    }
+}
+
+/// Reads the ID registers of the device at the address. Returns `None` if nothing acknowledges the address.
+/// The address is first probed with a plain 1-byte read, so a register address is only written to the addresses
+/// which acknowledge.
+fn probe<I2C>(
+    i2c: &mut I2C,
+    address: SevenBitAddress,
+) -> Result<Option<DiscoveredDevice>, TMag5273Error>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    match i2c.read(address, &mut [0x00]) {
+        Ok(()) => (),
+        Err(err) if matches!(err.kind(), ErrorKind::NoAcknowledge(_)) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    }
+
+    let mut manufacturer_id = [0x00; 2];
+    let register = u8::from(TMAG5273Register::ManufacturerIdLsb);
+    i2c.write_read(address, &[register], &mut manufacturer_id)?;
+    let manufacturer_id_matches = conversions::check_manufacturer_id(manufacturer_id).is_ok();
+
+    // Other devices can answer at the same addresses, so only a TMAG5273 has its device ID read
+    let mut device_id = None;
+    if manufacturer_id_matches {
+        let mut data = [0x00];
+        i2c.write_read(address, &[u8::from(TMAG5273Register::DeviceID)], &mut data)?;
+        device_id =
+            conversions::convert_device_id(DeviceIdRegister::new_with_raw_value(data[0])).ok();
+    }
+
+    Ok(Some(DiscoveredDevice {
+        address,
+        manufacturer_id_matches,
+        device_id,
+    }))
+}
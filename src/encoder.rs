@@ -0,0 +1,233 @@
+//! # Rotary Encoder
+//!
+//! The angle result covers a single turn. The [RotaryEncoder] follows it across the 0/360 degree wrap-around to count
+//! whole turns, with a configurable zero position and direction, and works out the velocity from timestamped
+//! samples. Everything is kept in steps of 1/16 degree, like [get_raw_angle](TMag5273::get_raw_angle), so no floating
+//! point maths is needed.
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+
+use crate::{types::TMag5273Error, TMag5273};
+
+/// One full turn in steps of 1/16 degree.
+pub const FULL_TURN: i32 = 360 * 16;
+
+/// Direction of rotation which counts as positive.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EncoderDirection {
+    /// The position follows the angle calculated by the device
+    #[default]
+    Normal,
+    /// The position counts against the angle calculated by the device
+    Reversed,
+}
+
+/// Zero position, direction and hysteresis of a [RotaryEncoder].
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EncoderConfig {
+    /// The angle calculated by the device which reads as position 0, in steps of 1/16 degree
+    pub zero: u16,
+    /// Direction of rotation which counts as positive
+    pub direction: EncoderDirection,
+    /// How far the angle has to move back against the last movement before the position follows it, in steps of
+    /// 1/16 degree. Keeps jitter from toggling the turn count while the shaft rests near the zero position.
+    pub hysteresis: u16,
+}
+
+impl Default for EncoderConfig {
+    fn default() -> Self {
+        Self {
+            zero: 0,
+            direction: EncoderDirection::Normal,
+            // Half a degree
+            hysteresis: 8,
+        }
+    }
+}
+
+/// Position and velocity of a [RotaryEncoder] after a sample.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EncoderState {
+    /// Position from the zero position across all turns, in steps of 1/16 degree
+    pub position: i32,
+    /// Whole turns from the zero position, rounded towards minus infinity
+    pub turns: i32,
+    /// Angle within the current turn, in steps of 1/16 degree below 5760
+    pub angle: u16,
+    /// Velocity between the last two samples, in steps of 1/16 degree per second
+    pub velocity: i32,
+}
+
+#[cfg(feature = "float")]
+impl EncoderState {
+    /// Position from the zero position across all turns, in degrees.
+    pub fn position_degrees(&self) -> f32 {
+        self.position as f32 / 16.0
+    }
+
+    /// Velocity between the last two samples, in degrees per second.
+    pub fn velocity_degrees_per_second(&self) -> f32 {
+        self.velocity as f32 / 16.0
+    }
+}
+
+/// Last sample taken by the encoder.
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    /// Angle relative to the zero position and direction
+    angle: i32,
+    /// Position without the hysteresis applied
+    position: i32,
+    timestamp_us: u64,
+}
+
+/// Multi-turn encoder which owns a sensor set up for the angle calculation, created with [RotaryEncoder::new].
+///
+/// For polling, [update](RotaryEncoder::update) reads the angle from the device. In an interrupt driven loop the
+/// timestamp is best taken when the conversion complete interrupt fires, and passed to `update` once the loop gets
+/// to it. An angle read elsewhere, e.g. from an interrupt handler, is passed to
+/// [update_with_angle](RotaryEncoder::update_with_angle) instead.
+///
+/// ## Example
+///
+/// ```Rust
+/// let mut encoder = RotaryEncoder::new(mag5273, EncoderConfig::default());
+/// loop {
+///     let state = encoder.update(timer.now_us())?;
+///     println!("{} turns, {} deg/s", state.turns, state.velocity / 16);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct RotaryEncoder<SENSOR> {
+    sensor: SENSOR,
+    config: EncoderConfig,
+    last: Option<Sample>,
+    state: EncoderState,
+    /// Sign of the last movement the position followed
+    moving: i32,
+}
+
+impl<SENSOR> RotaryEncoder<SENSOR> {
+    /// Wraps a sensor, which must already have the angle calculation enabled through `SensorConfig2Register`.
+    /// The position starts from the angle of the first sample, within the turn after the zero position.
+    pub fn new(sensor: SENSOR, config: EncoderConfig) -> Self {
+        Self {
+            sensor,
+            config,
+            last: None,
+            state: EncoderState::default(),
+            moving: 0,
+        }
+    }
+
+    /// Adds an angle read from the device, in steps of 1/16 degree, taken at the timestamp in microseconds. The
+    /// angle is followed across the wrap-around by the shortest way, so it has to be sampled at least twice per
+    /// half turn.
+    pub fn update_with_angle(&mut self, angle: u16, timestamp_us: u64) -> EncoderState {
+        let relative = (i32::from(angle) - i32::from(self.config.zero)).rem_euclid(FULL_TURN);
+        let relative = match self.config.direction {
+            EncoderDirection::Normal => relative,
+            EncoderDirection::Reversed => (FULL_TURN - relative) % FULL_TURN,
+        };
+
+        let Some(last) = self.last else {
+            self.last = Some(Sample {
+                angle: relative,
+                position: relative,
+                timestamp_us,
+            });
+            self.state = state_at(relative, 0);
+            return self.state;
+        };
+
+        // The shortest way round from the last angle
+        let step = (relative - last.angle + FULL_TURN / 2).rem_euclid(FULL_TURN) - FULL_TURN / 2;
+        let position = last.position + step;
+        let elapsed_us = timestamp_us.saturating_sub(last.timestamp_us);
+        let velocity = match elapsed_us {
+            0 => self.state.velocity,
+            elapsed_us => {
+                (i64::from(position - last.position) * 1_000_000 / elapsed_us as i64) as i32
+            }
+        };
+        self.last = Some(Sample {
+            angle: relative,
+            position,
+            timestamp_us,
+        });
+
+        // Only follow a movement back against the last one once it is larger than the hysteresis
+        let movement = position - self.state.position;
+        let reversing = movement.signum() == -self.moving;
+        if movement != 0 && (!reversing || movement.abs() > i32::from(self.config.hysteresis)) {
+            self.moving = movement.signum();
+            self.state = state_at(position, velocity);
+        } else {
+            self.state.velocity = velocity;
+        }
+        self.state
+    }
+
+    /// The state after the last sample.
+    pub fn state(&self) -> EncoderState {
+        self.state
+    }
+
+    /// Makes the angle of the last sample the zero position, and starts counting turns from it again. Does nothing
+    /// before the first sample.
+    pub fn zero_here(&mut self) {
+        let Some(last) = self.last else {
+            return;
+        };
+        let offset = match self.config.direction {
+            EncoderDirection::Normal => last.angle,
+            EncoderDirection::Reversed => -last.angle,
+        };
+        self.config.zero = (i32::from(self.config.zero) + offset).rem_euclid(FULL_TURN) as u16;
+        self.last = Some(Sample {
+            angle: 0,
+            position: 0,
+            timestamp_us: last.timestamp_us,
+        });
+        self.state = state_at(0, self.state.velocity);
+        self.moving = 0;
+    }
+
+    /// The zero position, direction and hysteresis in use.
+    pub fn config(&self) -> EncoderConfig {
+        self.config
+    }
+
+    /// Gives access to the sensor, for example to change the configuration between samples.
+    pub fn sensor(&mut self) -> &mut SENSOR {
+        &mut self.sensor
+    }
+
+    /// Hands back the sensor.
+    pub fn release(self) -> SENSOR {
+        self.sensor
+    }
+}
+
+impl<I2C> RotaryEncoder<TMag5273<I2C>>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Reads the angle from the device and adds it, taken at the timestamp in microseconds.
+    /// This will throw an error if the angle calculation is not enabled.
+    pub fn update(&mut self, timestamp_us: u64) -> Result<EncoderState, TMag5273Error> {
+        let angle = self.sensor.get_raw_angle()?;
+        Ok(self.update_with_angle(angle, timestamp_us))
+    }
+}
+
+fn state_at(position: i32, velocity: i32) -> EncoderState {
+    EncoderState {
+        position,
+        turns: position.div_euclid(FULL_TURN),
+        angle: position.rem_euclid(FULL_TURN) as u16,
+        velocity,
+    }
+}
@@ -0,0 +1,135 @@
+//! # Interrupt Driven Acquisition
+//!
+//! Instead of polling the device over I2C, the host can wait on the INT pin for the conversion complete interrupt
+//! and only then read the new data. The INT pin is active low and can either be latched, held low until the device
+//! is addressed, or pulsed low for 10us. As the pin is polled, a pulse can be missed, so the blocking driver always
+//! latches the interrupt.
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{Error, InputPin};
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+
+#[cfg(feature = "float")]
+use crate::types::TMag5273ChannelData;
+use crate::{
+    conversions,
+    registers::*,
+    types::{TMag5273Error, TMag5273RawChannelData},
+    TMag5273,
+};
+
+/// Time between reads of the INT pin while waiting for the interrupt, in us.
+const INT_POLL_INTERVAL_US: u32 = 5;
+
+/// Handle which owns the INT pin of a device, created by [TMag5273::interrupt_acquisition].
+pub struct InterruptAcquisition<'a, I2C, PIN> {
+    sensor: &'a mut TMag5273<I2C>,
+    pin: PIN,
+}
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Takes the INT pin of the device to wait for the conversion complete interrupt before reading the data.
+    /// The interrupt must already be configured through the InterruptConfig, with the conversion complete interrupt
+    /// enabled and sent through the INT pin, otherwise a WrongMode error is thrown.
+    /// A pulsed interrupt is switched to latched, as a 10us pulse can be missed while the pin is polled, and stays
+    /// latched once the pin is released. Reading the data addresses the device, which clears the latch.
+    ///
+    /// ## Example
+    ///
+    /// ```Rust
+    /// mag5273.set_interrupts(InterruptConfig {
+    ///     interrupt_mode: InterruptMode::INTInterrupt,
+    ///     int_pin_mode: INTPinMode::Latched,
+    ///     conversion_complete_interrupt_enabled: true,
+    ///     ..Default::default()
+    /// })?;
+    /// let mut acquisition = mag5273.interrupt_acquisition(pin)?;
+    /// loop {
+    ///     let data = acquisition.wait_for_data(&mut delay, 100_000)?;
+    /// }
+    /// ```
+    pub fn interrupt_acquisition<PIN>(
+        &mut self,
+        pin: PIN,
+    ) -> Result<InterruptAcquisition<'_, I2C, PIN>, TMag5273Error>
+    where
+        PIN: InputPin,
+    {
+        let config: InterruptConfigRegister = self.get_config_register()?;
+        if !conversions::check_conversion_interrupt(config)? {
+            self.set_config_register(config.with_int_pin_mode(INTPinMode::Latched))?;
+        }
+        Ok(InterruptAcquisition { sensor: self, pin })
+    }
+}
+
+impl<I2C, PIN> InterruptAcquisition<'_, I2C, PIN>
+where
+    I2C: I2c<SevenBitAddress>,
+    PIN: InputPin,
+{
+    /// Waits for the conversion complete interrupt, then reads all the axis data alongside the temperature of the
+    /// device in one burst. If the interrupt is not taken within `timeout_us` a Timeout error is thrown.
+    /// An interrupt which was asserted before this call returns straight away with the data it flagged.
+    /// Like get_all_data, the X, Y, Z and temperature channels must be enabled, otherwise an error is thrown. The
+    /// configuration is only read once the interrupt is taken, as addressing the device clears a latched interrupt.
+    ///
+    /// ### Arguments
+    ///
+    /// * `delay` - Delay used between reads of the INT pin
+    /// * `timeout_us` - Time to wait for the interrupt in us
+    #[cfg(feature = "float")]
+    pub fn wait_for_data<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<TMag5273ChannelData, TMag5273Error> {
+        let (data, xy_range, z_range) = self.wait_for_results(delay, timeout_us)?;
+        Ok(conversions::convert_all_data(data, xy_range, z_range))
+    }
+
+    /// Waits for the conversion complete interrupt like wait_for_data, returning the data without any floating
+    /// point conversion.
+    pub fn wait_for_raw_data<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<TMag5273RawChannelData, TMag5273Error> {
+        let (data, xy_range, z_range) = self.wait_for_results(delay, timeout_us)?;
+        Ok(conversions::convert_raw_all_data(data, xy_range, z_range))
+    }
+
+    /// Gives access to the sensor, for example to change the configuration between acquisitions.
+    pub fn sensor(&mut self) -> &mut TMag5273<I2C> {
+        self.sensor
+    }
+
+    /// Hands back the INT pin.
+    pub fn release(self) -> PIN {
+        self.pin
+    }
+
+    /// Waits for the interrupt, then reads the result registers alongside the XY and Z ranges in mT
+    fn wait_for_results<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<([u8; 8], u16, u16), TMag5273Error> {
+        let mut timeout = conversions::PollTimeout::new(INT_POLL_INTERVAL_US, timeout_us);
+        while !self.int_asserted()? {
+            delay.delay_us(timeout.next_delay()?);
+        }
+
+        // Reading the results addresses the device, which also clears a latched interrupt
+        self.sensor.read_all_data()
+    }
+
+    /// INT is active low
+    fn int_asserted(&mut self) -> Result<bool, TMag5273Error> {
+        self.pin
+            .is_low()
+            .map_err(|err| TMag5273Error::Pin(err.kind()))
+    }
+}
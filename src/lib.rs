@@ -31,6 +31,52 @@
  * However for fine grained control of the device you can set and configure
  * the raw registers outlined in registers module.
  *
+ * ## Async
+ *
+ * When running on an async executor such as [Embassy](https://embassy.dev) the `async` feature can be enabled. This adds the
+ * `asynchronous` module which contains an async TMAG5273 struct built on the [embedded-hal-async](https://docs.rs/embedded-hal-async) traits.
+ * It shares its API, configuration structs and conversion logic with the blocking driver, with each method awaited instead.
+ *
+ * ## Without Floating Point
+ *
+ * Every measurement can also be read without any floating point maths through the `get_raw_*` methods, e.g. `get_raw_all_data`, which return the
+ * codes from the result registers alongside the range they are scaled by, with helpers for the field in mT or uT as an `i32` and the temperature in
+ * hundredths of a degree. The angle is returned as a `u16` in steps of 1/16 degree. On targets without an FPU, such as Cortex-M0 parts, the `float`
+ * feature can be disabled (it is part of the default features) to compile out the `f32` API and avoid pulling in soft-float.
+ *
+ * ## Physical Units
+ *
+ * The `uom` feature adds the `units` module, with `_quantity` variants of the data getters and of the threshold, offset and temperature threshold
+ * setters which take and return [uom](https://docs.rs/uom) `MagneticFluxDensity`, `ThermodynamicTemperature` and `Angle` quantities instead of bare
+ * `f32`s in mT, degC and degrees, e.g. `get_all_data_quantity` or `set_mag_threshold_quantity`.
+ *
+ * ## Calibration
+ *
+ * Nearby steel and PCB currents offset and distort the measured field. A `Calibrator` collects `get_all_data` samples while a magnet is swept
+ * around the sensor and fits an ellipsoid to them, giving a `Calibration` with a hard iron offset and a soft iron matrix which `get_calibrated_data`
+ * applies to later readings. It works without an allocator and serialises to a fixed size byte array with a CRC for storage in flash, or with serde
+ * when the `serde` feature is enabled.
+ *
+ * For the angle calculation an `AngleCalibrator` records the two channels while the magnet turns through a full rotation, and works out the
+ * offsets and the gain which make them an ideal sine and cosine. `set_angle_calibration` writes them to the device, and the fit reports the
+ * largest angle error of the samples before and after the correction.
+ *
+ * The device compensates the temperature drift of NdFeB and ceramic magnets itself through `mag_tempo`. For any other magnet a
+ * `TemperatureCompensation` takes a linear coefficient or a lookup table and normalises the field to a reference temperature using the temperature
+ * measured on the chip, with `get_compensated_data` refusing to apply it on top of the on-chip compensation.
+ *
+ * ## Rotary Encoder
+ *
+ * A `RotaryEncoder` wraps a sensor set up for the angle calculation and counts whole turns across the 0/360 degree wrap-around, with a configurable
+ * zero position and direction, hysteresis against jitter near the zero position and the velocity worked out from timestamped samples. It works in
+ * steps of 1/16 degree without any floating point maths, and with both the blocking and async drivers.
+ *
+ * ## Typestate
+ *
+ * The `typestate` module wraps the driver in a TMag5273 struct which tracks the operating mode and enabled channels in its type,
+ * e.g. `TMag5273<I2C, Continuous<XYZ>>` or `TMag5273<I2C, AngleMode<XY>>`. Mode transitions consume the sensor, and only the getters
+ * which are valid in the current mode are available, so calls which would fail with a WrongMode or ChannelDisabled error do not compile.
+ *
  * ## Fault Handling
  *
  * The goal of this library is to prioritise robustness. To achieve this, certain design choices were made that introduce minimal overhead in I2C communication. Traditionally,
@@ -38,18 +84,76 @@
  * our library first reads the relevant configuration register and raises a WrongMode error if the sensor is in an incorrect state. Only then does it proceed to retrieve the
  * requested data, preventing potential system corruption. While this approach introduces a slight performance overhead, it significantly enhances the overall reliability and
  * robustness of the code.
+ *
+ * Where the bus load matters more, the `set_cache_mode` method lets the driver use its own copy of the configuration registers instead. In the `Cached`
+ * mode the power-on-reset flag is read in the same burst as the data, so a device which has reset back to its default configuration is still caught
+ * and the copy refreshed, while the `Unchecked` mode skips the configuration entirely.
+ *
+ * The `health` method decodes the fault flags of the Device Status and Conversion Status Registers into a `HealthReport` and clears the latched
+ * error flags, and `set_health_check` makes the driver check them after every data read, turning a fault such as a supply undervoltage or an
+ * oscillator failure into an error instead of returning data which can not be trusted.
+ *
+ * A dip in the supply resets the device to stand-by with all channels disabled, and back to its factory address. The driver remembers the
+ * configuration last written through it, and `recover` puts a reset device back as it was, moving it back to its address first. With
+ * `set_auto_recovery` a reset is detected while reading data, the device restored and a DeviceReset error returned for that read.
  */
 #![no_std]
+#[cfg(feature = "float")]
+mod angle_calibration;
+#[cfg(feature = "float")]
+mod calibration;
 mod config;
+mod conversions;
+mod discovery;
+mod encoder;
+mod health;
+mod interrupt;
+mod multi_sensor;
+mod recovery;
+mod shadow;
+#[cfg(feature = "float")]
+mod temperature_compensation;
+#[cfg(feature = "float")]
+mod wake_up;
+
+#[cfg(feature = "async")]
+pub mod asynchronous;
 
 pub mod registers;
 pub mod types;
+pub mod typestate;
+#[cfg(feature = "uom")]
+pub mod units;
+#[cfg(feature = "float")]
+pub use angle_calibration::*;
+#[cfg(feature = "float")]
+pub use calibration::*;
 pub use config::*;
-
+pub use discovery::*;
+pub use encoder::*;
+pub use interrupt::*;
+pub use multi_sensor::*;
+#[cfg(feature = "float")]
+pub use temperature_compensation::*;
+#[cfg(feature = "float")]
+pub use wake_up::*;
+
+use embedded_hal::delay::DelayNs;
 use embedded_hal::i2c::{I2c, SevenBitAddress};
 
+use recovery::AppliedConfig;
 use registers::*;
-use types::{Axis, DeviceVersion, MagneticChannelOffset, TMag5273ChannelData, TMag5273Error};
+use shadow::ShadowRegisters;
+use types::{
+    Axis, CacheMode, DeviceVersion, RawMagneticSample, RawTemperature, TMag5273Error,
+    TMag5273RawChannelData, TMag5273RawEnabledData, TMag5273RawFastReadData, TMag5273RawFrameData,
+    TMag5273Sample,
+};
+#[cfg(feature = "float")]
+use types::{
+    MagneticChannelOffset, TMag5273ChannelData, TMag5273EnabledData, TMag5273FastReadData,
+    TMag5273FrameData,
+};
 
 /// Value found in the device ID register
 pub const MANUFACTURER_ID_VALUE: u16 = 0x5449;
@@ -60,6 +164,18 @@ pub struct TMag5273<I2C> {
     i2c: I2C,
     /// The I²C device address.
     address: SevenBitAddress,
+    /// Copy of the configuration registers, kept in step with every write made through the driver.
+    shadow: ShadowRegisters,
+    /// Where the configuration used to validate and convert reads comes from.
+    cache_mode: CacheMode,
+    /// Whether the health of the device is checked after every data read.
+    health_check: bool,
+    /// The configuration last written through the driver, restored after a power-on-reset.
+    applied: AppliedConfig,
+    /// Whether a power-on-reset is detected and recovered from while reading data.
+    auto_recovery: bool,
+    /// Set count of the last sample read through get_sampled_data or get_raw_sampled_data.
+    last_set_count: Option<u8>,
 }
 
 impl<I2C> TMag5273<I2C>
@@ -93,9 +209,14 @@ where
             i2c,
             address,
             device_version: version,
+            shadow: ShadowRegisters::default(),
+            cache_mode: CacheMode::default(),
+            health_check: false,
+            applied: AppliedConfig::default(),
+            auto_recovery: false,
+            last_set_count: None,
         };
-        device.get_manufacturer_id()?;
-        device.is_correct_device_version()?;
+        device.connect()?;
         Ok(device)
     }
 
@@ -107,24 +228,84 @@ where
     /// Get the manufacturer ID.
     pub fn get_manufacturer_id(&mut self) -> Result<u16, TMag5273Error> {
         let mut data: [u8; 2] = [0x00, 0x00];
-        let register_address = TMAG5273Register::ManufacturerIdLsb.into();
-        self.i2c
-            .write_read(self.address, &[register_address], &mut data)?;
-
-        let manufacturer_id = u16::from_le_bytes(data);
-        match manufacturer_id != MANUFACTURER_ID_VALUE {
-            true => Err(TMag5273Error::WrongDevice),
-            false => Ok(manufacturer_id),
-        }
+        self.read_registers(TMAG5273Register::ManufacturerIdLsb, &mut data)?;
+        conversions::check_manufacturer_id(data)
     }
 
     /// Returns the Device ID of the device, otherwise an Error if the device is not connected or the register is malformed.
     pub fn get_device_id(&mut self) -> Result<DeviceId, TMag5273Error> {
         let device_id_register = self.get_config_register::<DeviceIdRegister>()?;
-        let device_id = device_id_register
-            .device_id()
-            .map_err(|_| TMag5273Error::MalformedRegister)?;
-        Ok(device_id)
+        conversions::convert_device_id(device_id_register)
+    }
+
+    /// Changes the I2C address of the device while keeping the driver valid. The new address is written with the
+    /// update enabled, then the driver moves to it and confirms the device answers there by reading the manufacturer ID.
+    /// If the device does not answer, the old address is written back at the new address, in case the device moved
+    /// but the confirmation was lost, then the driver goes back to the old address and the error is returned. If that
+    /// write fails as well, the device may be left at either address.
+    /// The address must be a 7-bit address outside the reserved ranges, otherwise an OutOfRange error is thrown.
+    /// If another device already answers at the address nothing is written and an AddressInUse error is thrown.
+    ///
+    /// NOTE: the device returns to its factory address after a power cycle.
+    ///
+    /// ### Arguments
+    ///
+    /// * `address` - The new 7-bit I2C address
+    pub fn change_address(&mut self, address: SevenBitAddress) -> Result<(), TMag5273Error> {
+        let new_address = conversions::check_i2c_address(address)?;
+        if address != self.address && self.i2c.read(address, &mut [0x00]).is_ok() {
+            return Err(TMag5273Error::AddressInUse);
+        }
+        let register = self
+            .get_config_register::<I2cAddressRegister>()?
+            .with_i2c_address(new_address)
+            .with_i2c_address_update_enabled(true);
+        self.set_config_register(register)?;
+
+        let previous_address = self.address;
+        self.address = address;
+        if let Err(err) = self.get_manufacturer_id() {
+            // The original error is returned whether or not the device could be moved back
+            if let Ok(command) = conversions::address_move(previous_address) {
+                let _ = self.i2c.write(address, &command);
+            }
+            self.address = previous_address;
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Sets where the driver gets the configuration it needs to validate and convert a measurement.
+    ///
+    /// * `ReadThrough` - the default, the configuration registers are read back before every measurement
+    /// * `Cached` - the driver's copy of the configuration is used. The copy is updated by every register written
+    ///   through the driver, and the POR flag is read alongside the data so that a device which has reset is
+    ///   noticed and the copy refreshed
+    /// * `Unchecked` - the driver's copy of the configuration is used without any check, for the lowest bus load
+    ///
+    /// NOTE: the cached modes assume nothing but this driver changes the configuration of the device.
+    /// Call refresh_cache after the configuration has been changed any other way.
+    ///
+    /// ## Example
+    ///
+    /// ```Rust
+    /// mag5273.set_cache_mode(CacheMode::Cached);
+    /// loop {
+    ///     let data = mag5273.get_all_data()?;
+    /// }
+    /// ```
+    pub fn set_cache_mode(&mut self, mode: CacheMode) {
+        self.cache_mode = mode;
+    }
+
+    /// Returns the current cache mode.
+    pub fn get_cache_mode(&self) -> CacheMode {
+        self.cache_mode
+    }
+
+    /// Reads the configuration registers back from the device into the driver's copy.
+    pub fn refresh_cache(&mut self) -> Result<(), TMag5273Error> {
+        self.sync_shadow()
     }
 
     /// Set the threshold for the device. The threshold is a floating point value, which is converted to a
@@ -136,25 +317,12 @@ where
     ///
     /// * `threshold` - The threshold value in mT
     /// * `axis` - The axis to set the threshold for
+    #[cfg(feature = "float")]
     pub fn set_mag_threshold(&mut self, threshold: f32, axis: Axis) -> Result<(), TMag5273Error> {
-        let config = self.get_config_register::<SensorConfig2Register>()?;
-        let (register, range) = match axis {
-            Axis::X => (TMAG5273Register::XThrConfig, config.xy_range()),
-            Axis::Y => (TMAG5273Register::YThrConfig, config.xy_range()),
-            Axis::Z => (TMAG5273Register::ZThrConfig, config.z_range()),
-        };
-        // check that the threshold is within the range of the device
-        if threshold > range.get_range(self.device_version)
-            || threshold < -range.get_range(self.device_version)
-        {
-            return Err(TMag5273Error::OutOfRange);
-        }
-        let threshold_normalized =
-            (threshold / range.get_range(self.device_version)).clamp(-1.0, 1.0);
-        let threshold_raw = (threshold_normalized * 128.0) as i8;
-        let write = [register.into(), threshold_raw.to_le_bytes()[0]];
-        self.i2c.write(self.address, &write)?;
-        Ok(())
+        let config = self.config_register::<SensorConfig2Register>()?;
+        let (register, value) =
+            conversions::mag_threshold_write(threshold, axis, config, self.device_version)?;
+        self.write_register(register, value)
     }
 
     /// Get the threshold for the device. The threshold is a floating point value, which is converted from a
@@ -163,263 +331,341 @@ where
     ///
     /// Output in mT.
     ///
+    #[cfg(feature = "float")]
     pub fn get_mag_threshold(&mut self, axis: Axis) -> Result<f32, TMag5273Error> {
-        let config = self.get_config_register::<SensorConfig2Register>()?;
-        let (register, range) = match axis {
-            Axis::X => (TMAG5273Register::XThrConfig, config.xy_range()),
-            Axis::Y => (TMAG5273Register::YThrConfig, config.xy_range()),
-            Axis::Z => (TMAG5273Register::ZThrConfig, config.z_range()),
-        };
-        let threshold_normalized = {
-            let mut buf: [u8; 1] = [0x00];
-            let register_address = register.into();
-            self.i2c
-                .write_read(self.address, &[register_address], &mut buf)?;
-            let threshold_raw = i8::from_le_bytes(buf); // always one byte (-128 to 127)
-            threshold_raw as f32 / 128.0 // convert to -1.0 to 1.0
-        };
-        Ok(threshold_normalized * range.get_range(self.device_version))
+        let config = self.config_register::<SensorConfig2Register>()?;
+        let (register, range) =
+            conversions::mag_threshold_register(axis, config, self.device_version);
+        let mut buf: [u8; 1] = [0x00];
+        self.read_registers(register, &mut buf)?;
+        Ok(conversions::mag_threshold_from_raw(buf[0], range))
     }
 
-    pub fn set_temperature_threshold() {
-        // TODO: Implement see datasheet Page 29
-        unimplemented!()
+    /// Set the temperature threshold for the device in degrees Celsius. When the temperature channel is enabled,
+    /// crossing the threshold can be used to assert an interrupt.
+    /// The threshold has a resolution of 8 degC and is rounded to the nearest step.
+    ///
+    /// ### Arguments
+    ///
+    /// * `threshold` - The threshold in degC, from -41 to 167. `None` disables the threshold comparison.
+    #[cfg(feature = "float")]
+    pub fn set_temperature_threshold(
+        &mut self,
+        threshold: Option<f32>,
+    ) -> Result<(), TMag5273Error> {
+        let t_config = self.config_register::<TConfigRegister>()?;
+        self.set_config_register(conversions::temp_threshold_config(threshold, t_config)?)
     }
-    pub fn get_temperature_threshold() {
-        // TODO: Implement see datasheet Page 29
-        unimplemented!()
+
+    /// Get the temperature threshold for the device in degrees Celsius.
+    /// Returns `None` if the threshold comparison is disabled.
+    ///
+    /// Output in degC.
+    #[cfg(feature = "float")]
+    pub fn get_temperature_threshold(&mut self) -> Result<Option<f32>, TMag5273Error> {
+        let t_config: TConfigRegister = self.config_register()?;
+        conversions::temp_threshold_from_code(t_config.t_thr_config())
     }
 
     /// Set the magnetic gain for the device.
     /// Gain value mapped 0 - 255 = 0 - 100%
     pub fn set_magnetic_gain(&mut self, gain: u8) -> Result<(), TMag5273Error> {
-        let register_address = TMAG5273Register::MagGainConfig.into();
-        self.i2c.write(self.address, &[register_address, gain])?;
-        Ok(())
+        self.write_register(TMAG5273Register::MagGainConfig, gain)
     }
 
     /// Get the magnetic gain for the device.
     /// Gain value mapped 0 - 255 = 0 - 100%
     pub fn get_magnetic_gain(&mut self) -> Result<u8, TMag5273Error> {
         let mut data: [u8; 1] = [0x00];
-        let register_address = TMAG5273Register::MagGainConfig.into();
-        self.i2c
-            .write_read(self.address, &[register_address], &mut data)?;
+        self.read_registers(TMAG5273Register::MagGainConfig, &mut data)?;
         Ok(data[0])
     }
 
-    /// Set the magnetic offset for the device.
+    /// Set the magnetic offset correction for the device in mT. The offset is added to the axis selected by
+    /// the angle calculation before the angle is calculated, so an axis reading +2 mT too high needs an offset of -2 mT.
+    /// The offset resolution is the range of the axis / 2048, e.g. 0.0195 mT for a 40 mT range, giving a
+    /// correction of -128 to +127 times the resolution. The offset is rounded to the nearest step, so reading it back
+    /// is accurate to within half of the resolution.
+    ///
+    /// NOTE: the angle must be set first, as it selects which axis and range the offset applies to.
     ///
-    /// NOTE: the angle must be set first.
+    /// ### Arguments
+    ///
+    /// * `offset` - The offset in mT
+    /// * `mag_offset` - The axis of the angle calculation to set the offset for
+    #[cfg(feature = "float")]
     pub fn set_magnetic_offset(
         &mut self,
         offset: f32,
         mag_offset: MagneticChannelOffset,
     ) -> Result<(), TMag5273Error> {
-        let _register_address: TMAG5273Register = mag_offset.into();
-        let config: SensorConfig2Register = self.get_config_register()?;
-        let range = match config.angle() {
-            Angle::Disabled => return Err(TMag5273Error::WrongMode),
-            Angle::YZ | Angle::XZ => config.z_range(),
-            Angle::XY => config.xy_range(),
-        }
-        .get_range(self.device_version);
-        // make sure offset is within full range
-        let full_range = range * 2.0;
-        if offset > full_range || offset < -full_range {
-            return Err(TMag5273Error::OutOfRange);
-        }
-        const COEFFICIENT: f32 = 2_i16.pow(12) as f32; // 2048 from the datasheet
-        let _raw_offset = (COEFFICIENT * offset) / full_range;
-        unimplemented!("Offset doesn't work yet");
-        // let write = register_address.into();
-        // self.i2c
-        //     .write(self.address, &[write, raw_offset.to_le_bytes()[0]])?;
-        // Ok(())
+        let config: SensorConfig2Register = self.config_register()?;
+        let (register, value) =
+            conversions::mag_offset_write(offset, mag_offset, config, self.device_version)?;
+        self.write_register(register, value)
     }
 
-    /// Get the magnetic offset for the device.
+    /// Get the magnetic offset correction for the device. The resolution is calculated from the
+    /// angle and range that the device is currently configured for.
+    ///
+    /// Output in mT.
+    #[cfg(feature = "float")]
     pub fn get_magnetic_offset(
         &mut self,
         mag_offset: MagneticChannelOffset,
-    ) -> Result<u8, TMag5273Error> {
-        let register_address: TMAG5273Register = mag_offset.into();
-        let config: SensorConfig2Register = self.get_config_register()?;
-        let _range = match config.angle() {
-            Angle::Disabled => return Err(TMag5273Error::WrongMode),
-            Angle::YZ | Angle::XZ => config.z_range(),
-            Angle::XY => config.xy_range(),
-        }
-        .get_range(self.device_version);
-        let _data = {
-            let mut buf: [u8; 1] = [0x00];
-            let register_address = register_address.into();
-            self.i2c
-                .write_read(self.address, &[register_address], &mut buf)?;
-            buf[0] // always one byte
-        };
-        unimplemented!("Offset doesn't work yet");
-        // if mag_offset == MagOffset::Offset1 {
-        //     Ok(((data as f32) * (xy_range) / 2048.0) as u8)
-        // } else {
-        //     let angle_en: AngleEn = config.angle_en().into();
-        //     let z_range = config.z_range().get_range(self.version);
-        //     Ok(((data as f32) * (range) / 2048.0) as u8)
-        // }
+    ) -> Result<f32, TMag5273Error> {
+        let config: SensorConfig2Register = self.config_register()?;
+        let (register, resolution) =
+            conversions::mag_offset_register(mag_offset, config, self.device_version)?;
+        let mut buf: [u8; 1] = [0x00];
+        self.read_registers(register, &mut buf)?;
+        Ok(conversions::mag_offset_from_raw(buf[0], resolution))
     }
 
     /// Get the temperature of the device in degrees Celsius. This will
     /// throw an error if the temperature channel is not enabled.
+    #[cfg(feature = "float")]
     pub fn get_temperature(&mut self) -> Result<f32, TMag5273Error> {
-        // Check if channel is enabled, throw error otherwise
-        self.check_temp_channel()?;
-        let mut data: [u8; 2] = [0x00; 2];
-        let register_address = TMAG5273Register::TMsbResult.into();
-        self.i2c
-            .write_read(self.address, &[register_address], &mut data)?;
-        Ok(Self::convert_temp(data))
+        let data = self.read_temperature()?;
+        Ok(conversions::convert_temp(data))
+    }
+
+    /// Get the temperature code of the device, without any floating point conversion. This will
+    /// throw an error if the temperature channel is not enabled.
+    pub fn get_raw_temperature(&mut self) -> Result<RawTemperature, TMag5273Error> {
+        let data = self.read_temperature()?;
+        Ok(RawTemperature {
+            code: u16::from_be_bytes(data),
+        })
     }
 
     /// Gets the specific axis channel data. This will throw an error if the channel is not enabled.
+    #[cfg(feature = "float")]
     pub fn get_mag_data(&mut self, axis: Axis) -> Result<f32, TMag5273Error> {
-        let configs: SensorConfigRegisters = self.get_dual_config_register()?;
-        let config2 = configs.sensor_config2();
-        // Verify the axis is enabled
-        let mag_channel = configs.sensor_config1().mag_channel();
-
-        // Guard against fault states
-        match mag_channel {
-            MagneticChannel::Default => return Err(TMag5273Error::WrongMode),
-            MagneticChannel::Reserved1
-            | MagneticChannel::Reserved2
-            | MagneticChannel::Reserved3
-            | MagneticChannel::Reserved4 => return Err(TMag5273Error::MalformedRegister),
-            _ => (),
-        }
-        // Verify that the axis we want is enabled
-        let (register, range) = match axis {
-            Axis::X => match mag_channel {
-                MagneticChannel::Y
-                | MagneticChannel::Z
-                | MagneticChannel::YZ
-                | MagneticChannel::YZY => return Err(TMag5273Error::ChannelDisabled),
-                _ => (TMAG5273Register::XMsbResult, config2.xy_range()),
-            },
-            Axis::Y => match mag_channel {
-                MagneticChannel::X
-                | MagneticChannel::Z
-                | MagneticChannel::XZ
-                | MagneticChannel::XZX => return Err(TMag5273Error::ChannelDisabled),
-                _ => (TMAG5273Register::YMSBResult, config2.xy_range()),
-            },
-            Axis::Z => match mag_channel {
-                MagneticChannel::X
-                | MagneticChannel::Y
-                | MagneticChannel::XY
-                | MagneticChannel::XYX => return Err(TMag5273Error::ChannelDisabled),
-                _ => (TMAG5273Register::ZMSBResult, config2.z_range()),
-            },
-        };
+        let (data, range) = self.read_mag_data(axis)?;
+        Ok(conversions::convert_magnetism(axis, data, range.into()))
+    }
 
-        let range = range.get_range(self.device_version);
-        let mut data: [u8; 2] = [0x00; 2];
-        let register_address = register.into();
-        self.i2c
-            .write_read(self.address, &[register_address], &mut data)?;
-        Ok(Self::convert_magnetism(axis, data, range))
+    /// Gets the specific axis channel data as the code from the result registers, alongside the range it
+    /// is scaled by. This will throw an error if the channel is not enabled.
+    pub fn get_raw_mag_data(&mut self, axis: Axis) -> Result<RawMagneticSample, TMag5273Error> {
+        let (data, range) = self.read_mag_data(axis)?;
+        Ok(conversions::raw_magnetic_sample(axis, data, range))
     }
 
     /// Get all the axis data from the device, alongside the temperature of the device
     /// The data is returned as a TMag5273Data struct. If the correct Channels are not enabled,
-    /// an error is thrown.
+    /// an error is thrown. For any other set of channels use get_enabled_data.
+    #[cfg(feature = "float")]
     pub fn get_all_data(&mut self) -> Result<TMag5273ChannelData, TMag5273Error> {
-        let configs: SensorConfigRegisters = self.get_dual_config_register()?;
-        let config2 = configs.sensor_config2();
-        // Only valid in XYZ mode
-        if configs.sensor_config1().mag_channel() != MagneticChannel::XYZ {
-            return Err(TMag5273Error::ChannelDisabled);
-        }
-        // Verify the temperature channel is enabled
-        self.check_temp_channel()?;
+        let (data, xy_range, z_range) = self.read_all_data()?;
+        Ok(conversions::convert_all_data(data, xy_range, z_range))
+    }
 
-        // Full Data Read
-        let mut data: [u8; 8] = [0x00; 8];
-        let register_address = TMAG5273Register::TMsbResult.into();
-        self.i2c
-            .write_read(self.address, &[register_address], &mut data)?;
+    /// Get all the axis data from the device, alongside the temperature of the device, without any floating
+    /// point conversion. If the correct Channels are not enabled, an error is thrown.
+    pub fn get_raw_all_data(&mut self) -> Result<TMag5273RawChannelData, TMag5273Error> {
+        let (data, xy_range, z_range) = self.read_all_data()?;
+        Ok(conversions::convert_raw_all_data(data, xy_range, z_range))
+    }
+
+    /// Get the data of the channels enabled on the device, in any magnetic channel mode and with or without the
+    /// temperature. Channels which are not enabled are `None`. Only the result registers from the first to the last
+    /// enabled channel are read, so e.g. in the XY mode the temperature and Z result registers are skipped.
+    /// If no channel is enabled, a ChannelDisabled error is thrown.
+    ///
+    /// ## Example
+    ///
+    /// ```Rust
+    /// let data = mag5273.get_enabled_data()?;
+    /// if let (Some(x), Some(y)) = (data.x, data.y) {
+    ///     println!("X: {x} mT, Y: {y} mT");
+    /// }
+    /// ```
+    #[cfg(feature = "float")]
+    pub fn get_enabled_data(&mut self) -> Result<TMag5273EnabledData, TMag5273Error> {
+        let data = self.get_raw_enabled_data()?;
+        Ok(conversions::convert_enabled_data(data))
+    }
+
+    /// Get the data of the channels enabled on the device, without any floating point conversion.
+    /// See get_enabled_data.
+    pub fn get_raw_enabled_data(&mut self) -> Result<TMag5273RawEnabledData, TMag5273Error> {
+        let channels = self.enabled_channels()?;
+        self.read_enabled_data(channels)
+    }
+
+    /// Get all the axis data from the device, alongside the temperature of the device, together with the set count
+    /// of the conversion it comes from. The set count is compared with the one of the previous sample read through
+    /// this method or get_raw_sampled_data, flagging whether the data is from the next conversion, the same conversion
+    /// again or whether conversions were missed in between. If the correct Channels are not enabled, an error is thrown.
+    ///
+    /// ## Example
+    ///
+    /// ```Rust
+    /// let sample = mag5273.get_sampled_data()?;
+    /// if sample.freshness != SampleFreshness::Stale {
+    ///     controller.update(sample.data);
+    /// }
+    /// ```
+    #[cfg(feature = "float")]
+    pub fn get_sampled_data(
+        &mut self,
+    ) -> Result<TMag5273Sample<TMag5273ChannelData>, TMag5273Error> {
+        let (data, xy_range, z_range, set_count) = self.read_sample()?;
+        Ok(self.track_sample(
+            conversions::convert_all_data(data, xy_range, z_range),
+            set_count,
+        ))
+    }
 
-        let xy_range = config2.xy_range().get_range(self.device_version);
-        let z_range = config2.z_range().get_range(self.device_version);
+    /// Get all the axis data from the device, alongside the temperature of the device, together with the set count
+    /// of the conversion it comes from, without any floating point conversion. See get_sampled_data.
+    pub fn get_raw_sampled_data(
+        &mut self,
+    ) -> Result<TMag5273Sample<TMag5273RawChannelData>, TMag5273Error> {
+        let (data, xy_range, z_range, set_count) = self.read_sample()?;
+        Ok(self.track_sample(
+            conversions::convert_raw_all_data(data, xy_range, z_range),
+            set_count,
+        ))
+    }
+
+    /// Reads every result and status register, from the temperature up to the Device Status Register, in a single
+    /// transfer. Only the channels enabled on the device, and the angle and magnitude when the angle calculation is
+    /// enabled, are decoded, any other field is `None`. Unlike get_all_data no channel has to be enabled.
+    ///
+    /// ## Example
+    ///
+    /// ```Rust
+    /// let frame = mag5273.get_frame_data()?;
+    /// if let (Some(angle), Some(magnitude)) = (frame.angle, frame.magnitude) {
+    ///     println!("{angle} deg at a magnitude of {magnitude}");
+    /// }
+    /// ```
+    #[cfg(feature = "float")]
+    pub fn get_frame_data(&mut self) -> Result<TMag5273FrameData, TMag5273Error> {
+        let frame = self.get_raw_frame_data()?;
+        Ok(conversions::convert_frame(frame))
+    }
+
+    /// Reads every result and status register in a single transfer, without any floating point conversion.
+    /// See get_frame_data.
+    pub fn get_raw_frame_data(&mut self) -> Result<TMag5273RawFrameData, TMag5273Error> {
+        let mut configs: SensorConfigRegisters = self.sensor_config_registers()?;
+        let mut t_config: TConfigRegister = self.config_register()?;
+        let mut data = [0x00; conversions::FRAME_LEN];
+        if self.read_results(TMAG5273Register::TMsbResult, &mut data)? {
+            configs = self.shadow.sensor_config();
+            t_config = self.shadow.t_config();
+        }
+        conversions::convert_raw_frame(data, configs, t_config, self.device_version)
+    }
 
-        let temp = Self::convert_temp([data[0], data[1]]);
-        let x = Self::convert_magnetism(Axis::X, [data[2], data[3]], xy_range);
-        let y = Self::convert_magnetism(Axis::Y, [data[4], data[5]], xy_range);
-        let z = Self::convert_magnetism(Axis::Z, [data[6], data[7]], z_range);
-        Ok(TMag5273ChannelData { x, y, z, temp })
+    /// Reads the enabled channels using one of the 1-byte I2C read modes, set through `i2c_read_mode` in the
+    /// DeviceConfig. No register address is written, the device sends the enabled channels in the T, X, Y, Z order
+    /// followed by the Conversion Status Register, which makes this the fastest way of polling the sensor.
+    /// The layout is taken from the driver's copy of the configuration whatever the cache mode, so nothing but the
+    /// frame is read. The configuration must therefore only be changed through the driver.
+    /// This will throw a WrongMode error if the device is in the standard 3-byte read mode.
+    #[cfg(feature = "float")]
+    pub fn get_fast_read_data(&mut self) -> Result<TMag5273FastReadData, TMag5273Error> {
+        let data = self.get_fast_read_data_raw()?;
+        Ok(conversions::convert_fast_read_data(data))
+    }
+
+    /// Reads the enabled channels using one of the 1-byte I2C read modes like get_fast_read_data, returning the data
+    /// without any floating point conversion.
+    pub fn get_fast_read_data_raw(&mut self) -> Result<TMag5273RawFastReadData, TMag5273Error> {
+        let frame = conversions::FastReadFrame::new(
+            self.shadow.device_config_1(),
+            self.shadow.sensor_config(),
+            self.shadow.t_config(),
+            self.device_version,
+        )?;
+        let mut data = [0x00; conversions::MAX_FAST_READ_LEN];
+        let data = &mut data[..frame.len()];
+        self.i2c.read(self.address, data)?;
+        let data = frame.decode(self.address, data)?;
+        // The frame already carries the diagnostic flag, so the full report is only read once it is set
+        if self.health_check && data.conversion_status.diagnostic_error() {
+            self.health()?.check()?;
+        }
+        Ok(data)
     }
 
     /// Gets the measured/calculated angle in degrees of the enabled channel
     /// This will throw an error if the channels are not enabled.
+    #[cfg(feature = "float")]
     pub fn get_angle(&mut self) -> Result<f32, TMag5273Error> {
-        // Check if the channel is enabled
-        let config: SensorConfig2Register = self.get_config_register()?;
-        if config.angle() == Angle::Disabled {
-            return Err(TMag5273Error::ChannelDisabled);
-        }
-        let mut data = [0x00; 2];
-        let register_address = TMAG5273Register::AngleResultMSB.into();
-        self.i2c
-            .write_read(self.address, &[register_address], &mut data)?;
-
-        // The angle is calculated as follows:
-        //  x x x x x x x x   x x x x x x x x
-        //  |____0x19_____|   |_____0x1A____|
-        //        |_________________| |_____|
-        //                 ^             ^ Divide by 16 to give angle fraction in degrees
-        //                 '- Angle integral in degrees
-        let angle_reg = i16::from_be_bytes(data);
-        let dec_value = (data[1] & 0b1111) as f32 / 16.0;
-        let angle_val = (angle_reg >> 4) as f32;
-        Ok(angle_val + dec_value)
+        let data = self.read_angle()?;
+        Ok(conversions::convert_angle(data))
+    }
+
+    /// Gets the measured/calculated angle of the enabled channel in steps of 1/16 degree, below 5760.
+    /// This will throw an error if the channels are not enabled.
+    pub fn get_raw_angle(&mut self) -> Result<u16, TMag5273Error> {
+        let data = self.read_angle()?;
+        Ok(conversions::angle_sixteenths(data))
     }
 
     /// Returns the resultant vector magnitude (during the angle measurement) result. This value should be consistent during 360 degrees measurements.
     pub fn get_magnitude(&mut self) -> Result<u8, TMag5273Error> {
         let mut data: [u8; 1] = [0x00];
-        let register_address = TMAG5273Register::MagnitudeResult.into();
-        self.i2c
-            .write_read(self.address, &[register_address], &mut data)?;
+        self.read_registers(TMAG5273Register::MagnitudeResult, &mut data)?;
         Ok(data[0])
     }
 
+    /// Starts a single conversion of the enabled channels through the I2C trigger. The device must be in stand-by
+    /// mode with the trigger mode set to the I2C command, otherwise a WrongMode error is thrown.
+    pub fn trigger_conversion(&mut self) -> Result<(), TMag5273Error> {
+        let device_config_2: DeviceConfig2Register = self.config_register()?;
+        conversions::check_i2c_trigger(device_config_2)?;
+        // The trigger is the MSB of the register address, no register is written
+        let command = conversions::TRIGGER_BIT | u8::from(TMAG5273Register::ConvStatus);
+        self.i2c.write(self.address, &[command])?;
+        Ok(())
+    }
+
+    /// Makes a single measurement of the enabled channels while the device is in stand-by mode, so the device only
+    /// draws its active current for the length of one conversion. The conversion is started through the I2C
+    /// trigger, then the Conversion Status Register is polled until the new data is ready. If the data is not ready
+    /// within `timeout_us` a Timeout error is thrown.
+    /// Like get_enabled_data, channels which are not enabled are `None`, and if no channel is enabled a
+    /// ChannelDisabled error is thrown before the conversion is started.
+    ///
+    /// ### Arguments
+    ///
+    /// * `delay` - Delay used between polls of the Conversion Status Register
+    /// * `timeout_us` - Time to wait for the conversion in us
+    #[cfg(feature = "float")]
+    pub fn measure_once<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<TMag5273EnabledData, TMag5273Error> {
+        let data = self.measure_once_raw(delay, timeout_us)?;
+        Ok(conversions::convert_enabled_data(data))
+    }
+
+    /// Makes a single measurement like measure_once, returning the data without any floating point conversion.
+    pub fn measure_once_raw<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<TMag5273RawEnabledData, TMag5273Error> {
+        let channels = self.enabled_channels()?;
+        self.wait_for_conversion(delay, timeout_us)?;
+        self.read_enabled_data(channels)
+    }
+
     /// Initialise the device with the default settings.
     pub fn init_default(mut self) -> Result<Self, TMag5273Error> {
-        // Set the Mag Channels to be enabled
-        let sensor_config_1_register: SensorConfig1Register = self
-            .get_config_register::<SensorConfig1Register>()?
-            .with_sleep_time(SleepTime::Ms20000)
-            .with_mag_channel(MagneticChannel::XYZ);
-        self.set_config_register(sensor_config_1_register)?;
-
-        // Set the Sensor Config 2 Register
-        let sensor_config_2_register: SensorConfig2Register = self
-            .get_config_register::<SensorConfig2Register>()?
-            .with_xy_range(Range::High)
-            .with_z_range(Range::High);
-        self.set_config_register(sensor_config_2_register)?;
-
-        // Set the Temperature Sensor to be enabled
-        let t_config_register: TConfigRegister = self
-            .get_config_register::<TConfigRegister>()?
-            .with_temperature_channel_enabled(true);
-        self.set_config_register(t_config_register)?;
-
-        // Set the Operating Mode
-        let device_config2_register: DeviceConfig2Register = self
-            .get_config_register::<DeviceConfig2Register>()?
-            .with_operating_mode(OperatingMode::ContinuousMeasure);
-        self.set_config_register(device_config2_register)?;
-
+        for register in conversions::DEFAULT_CONFIG_REGISTERS {
+            let mut value = [0x00];
+            self.read_registers(register, &mut value)?;
+            self.write_register(register, conversions::default_config(register, value[0]))?;
+        }
         Ok(self)
     }
 }
@@ -427,46 +673,153 @@ impl<I2C> TMag5273<I2C>
 where
     I2C: I2c<SevenBitAddress>,
 {
-    /// Convert the magnetism data from the device to a floating point value.
-    fn convert_magnetism(axis: Axis, data: [u8; 2], range: f32) -> f32 {
-        let data = i16::from_be_bytes(data) as f32;
-        let value = (data * range) / 32768.0;
-        if axis == Axis::X {
-            -value
-        } else {
-            value
+    /// Checks the temp channel is enabled
+    fn check_temp_channel(&mut self) -> Result<(), TMag5273Error> {
+        let t_config: TConfigRegister = self.config_register()?;
+        conversions::check_temp_channel(t_config)
+    }
+
+    /// Reads the temperature result registers, checking the channel is enabled
+    fn read_temperature(&mut self) -> Result<[u8; 2], TMag5273Error> {
+        // Check if channel is enabled, throw error otherwise
+        self.check_temp_channel()?;
+        let mut data: [u8; 2] = [0x00; 2];
+        if self.read_results(TMAG5273Register::TMsbResult, &mut data)? {
+            self.check_temp_channel()?;
+        }
+        Ok(data)
+    }
+
+    /// Reads the result registers of the axis, alongside the range in mT they are scaled by
+    fn read_mag_data(&mut self, axis: Axis) -> Result<([u8; 2], u16), TMag5273Error> {
+        let configs: SensorConfigRegisters = self.sensor_config_registers()?;
+        let (register, mut range) =
+            conversions::mag_data_register(axis, configs, self.device_version)?;
+
+        let mut data: [u8; 2] = [0x00; 2];
+        if self.read_results(register, &mut data)? {
+            let configs = self.shadow.sensor_config();
+            (_, range) = conversions::mag_data_register(axis, configs, self.device_version)?;
         }
+        Ok((data, range))
     }
 
-    /// Convert the temperature data from the device to a floating point value in (degC).
-    fn convert_temp(data: [u8; 2]) -> f32 {
-        const TSENSE_T0: f32 = 25.0; // Reference temperature for TADC_T0
-        const TADC_T0: f32 = 17508.0; // Temp result in decimal value (from 16-bit format)
-        const TADC_RES: f32 = 60.1; // Temperature sensing resolution (in 16-bit format)
-        let raw_temp = u16::from_be_bytes(data) as f32;
-        TSENSE_T0 + ((raw_temp - TADC_T0) / TADC_RES)
+    /// Reads the temperature and axis result registers in one burst, alongside the XY and Z ranges in mT
+    fn read_all_data(&mut self) -> Result<([u8; 8], u16, u16), TMag5273Error> {
+        let configs: SensorConfigRegisters = self.sensor_config_registers()?;
+        let t_config: TConfigRegister = self.config_register()?;
+        let (mut xy_range, mut z_range) =
+            conversions::all_data_ranges(configs, t_config, self.device_version)?;
+
+        // Full Data Read
+        let mut data: [u8; 8] = [0x00; 8];
+        if self.read_results(TMAG5273Register::TMsbResult, &mut data)? {
+            (xy_range, z_range) = conversions::all_data_ranges(
+                self.shadow.sensor_config(),
+                self.shadow.t_config(),
+                self.device_version,
+            )?;
+        }
+        Ok((data, xy_range, z_range))
     }
 
-    /// Checks the temp channel is enabled
-    fn check_temp_channel(&mut self) -> Result<(), TMag5273Error> {
-        let register_address = TMAG5273Register::TConfig.into();
-        let mut data: [u8; 1] = [0x00];
-        self.i2c
-            .write_read(self.address, &[register_address], &mut data)?;
-        let tch_en = TConfigRegister::new_with_raw_value(data[0]).temperature_channel_enabled();
-
-        if tch_en {
-            Ok(())
-        } else {
-            Err(TMag5273Error::ChannelDisabled)
+    /// Reads the temperature and axis result registers and the Conversion Status Register in one burst, alongside
+    /// the XY and Z ranges in mT and the set count of the conversion
+    fn read_sample(&mut self) -> Result<([u8; 8], u16, u16, u8), TMag5273Error> {
+        let configs: SensorConfigRegisters = self.sensor_config_registers()?;
+        let t_config: TConfigRegister = self.config_register()?;
+        let (mut xy_range, mut z_range) =
+            conversions::all_data_ranges(configs, t_config, self.device_version)?;
+
+        let mut data = [0x00; conversions::SAMPLE_BURST_LEN];
+        if self.read_results(TMAG5273Register::TMsbResult, &mut data)? {
+            // The set count starts again after a power-on-reset
+            self.last_set_count = None;
+            (xy_range, z_range) = conversions::all_data_ranges(
+                self.shadow.sensor_config(),
+                self.shadow.t_config(),
+                self.device_version,
+            )?;
+        }
+        let (data, set_count) = conversions::split_sample(data);
+        Ok((data, xy_range, z_range, set_count))
+    }
+
+    /// Wraps data with its set count, flagging it against the previous sample
+    fn track_sample<DATA>(&mut self, data: DATA, set_count: u8) -> TMag5273Sample<DATA> {
+        let freshness = conversions::sample_freshness(self.last_set_count, set_count);
+        self.last_set_count = Some(set_count);
+        TMag5273Sample {
+            data,
+            set_count,
+            freshness,
+        }
+    }
+
+    /// Reads the angle result registers, checking the angle calculation is enabled
+    fn read_angle(&mut self) -> Result<[u8; 2], TMag5273Error> {
+        // Check if the channel is enabled
+        let config: SensorConfig2Register = self.config_register()?;
+        conversions::check_angle_enabled(config)?;
+        let mut data = [0x00; 2];
+        if self.read_results(TMAG5273Register::AngleResultMSB, &mut data)? {
+            conversions::check_angle_enabled(self.shadow.sensor_config().sensor_config2())?;
+        }
+        Ok(data)
+    }
+
+    /// Gets the channels enabled on the device, alongside the ranges in mT the axes are scaled by
+    fn enabled_channels(&mut self) -> Result<conversions::EnabledChannels, TMag5273Error> {
+        let configs: SensorConfigRegisters = self.sensor_config_registers()?;
+        let t_config: TConfigRegister = self.config_register()?;
+        conversions::EnabledChannels::new(configs, t_config, self.device_version)
+    }
+
+    /// Reads the result registers covering the enabled channels and decodes them
+    fn read_enabled_data(
+        &mut self,
+        mut channels: conversions::EnabledChannels,
+    ) -> Result<TMag5273RawEnabledData, TMag5273Error> {
+        let (start, end) = channels.span();
+        let mut data = [0x00; 8];
+        if self.read_results(channels.start_register()?, &mut data[start..end])? {
+            channels = channels.refreshed(
+                self.shadow.sensor_config(),
+                self.shadow.t_config(),
+                self.device_version,
+            )?;
         }
+        Ok(channels.decode_raw(data))
+    }
+
+    /// Triggers a single conversion and waits for it to complete
+    fn wait_for_conversion<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<(), TMag5273Error> {
+        let previous: ConversionStatusRegister = self.get_config_register()?;
+        self.trigger_conversion()?;
+
+        let mut timeout =
+            conversions::PollTimeout::new(conversions::CONVERSION_POLL_INTERVAL_US, timeout_us);
+        while !conversions::conversion_complete(previous, self.get_config_register()?) {
+            delay.delay_us(timeout.next_delay()?);
+        }
+        Ok(())
     }
 
     /// Checks if the connect device has the correct hardware version for what the user has chosen
     fn is_correct_device_version(&mut self) -> Result<(), TMag5273Error> {
-        match self.get_device_id()? != self.device_version.get_device_id() {
-            true => Err(TMag5273Error::WrongDevice),
-            false => Ok(()),
-        }
+        let device_id = self.get_device_id()?;
+        conversions::check_device_version(device_id, self.device_version)
+    }
+
+    /// Checks the device answers at the address and is the chosen hardware version, then reads its configuration
+    fn connect(&mut self) -> Result<(), TMag5273Error> {
+        self.get_manufacturer_id()?;
+        self.sync_shadow()?;
+        self.sync_applied()?;
+        self.is_correct_device_version()
     }
 }
@@ -0,0 +1,105 @@
+//! # Multi-Sensor Bring-Up
+//!
+//! Every TMAG5273 of a variant starts at the same factory address, so several of them can only share a bus once
+//! each has been given its own address. The address is lost on every power cycle, so the bring-up enables the
+//! sensors one at a time through their enable or power pins and moves each one to its address before the next one
+//! is enabled.
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{Error, OutputPin};
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+
+use crate::{
+    conversions,
+    types::{DeviceVersion, TMag5273Error},
+    TMag5273,
+};
+
+/// Time given to the sensors to power down or start up when their enable pins change, in us.
+const ENABLE_SETTLE_TIME_US: u32 = 1_000;
+
+/// Brings up a bus of sensors of the same variant, giving each one its own address from the address plan.
+///
+/// All the enable pins are first driven low, then each sensor is enabled in turn by driving its pin high and moved
+/// from the factory address to its address. A sensor which already answers at its address, for example because
+/// its pin isolates it from the bus rather than cutting its power, is left where it is. This makes the bring-up
+/// safe to re-run after a brown-out has sent some or all of the sensors back to the factory address.
+///
+/// The address plan must hold valid, unique addresses. Only the last sensor may keep the factory address, as the
+/// sensors after it are brought up there. Before each sensor is enabled the factory address and its planned address
+/// are checked to be free, otherwise an AddressInUse error is thrown.
+///
+/// ### Arguments
+///
+/// * `new_device` - Creates a new handle to the shared bus, e.g. a `RefCellDevice` from embedded-hal-bus
+/// * `enable_pins` - Enable or power pin of each sensor, active high
+/// * `addresses` - Address to give each sensor, in the same order as the pins
+/// * `version` - The variant of all the sensors on the bus
+/// * `delay` - Delay used while the sensors power down and start up
+///
+/// ## Example
+///
+/// ```Rust
+/// let bus = RefCell::new(i2c);
+/// let mut enable_pins = [pin_0, pin_1, pin_2, pin_3];
+/// let [mut sensor_0, mut sensor_1, mut sensor_2, mut sensor_3] = bring_up_sensors(
+///     || RefCellDevice::new(&bus),
+///     &mut enable_pins,
+///     [0x30, 0x31, 0x32, 0x33],
+///     DeviceVersion::TMAG5273B1,
+///     &mut delay,
+/// )?;
+/// ```
+pub fn bring_up_sensors<I2C, PIN, D, F, const N: usize>(
+    mut new_device: F,
+    enable_pins: &mut [PIN; N],
+    addresses: [SevenBitAddress; N],
+    version: DeviceVersion,
+    delay: &mut D,
+) -> Result<[TMag5273<I2C>; N], TMag5273Error>
+where
+    I2C: I2c<SevenBitAddress>,
+    PIN: OutputPin,
+    D: DelayNs,
+    F: FnMut() -> I2C,
+{
+    let default_address = version.get_default_address();
+    conversions::check_address_plan(&addresses, default_address)?;
+
+    for pin in enable_pins.iter_mut() {
+        pin.set_low()
+            .map_err(|err| TMag5273Error::Pin(err.kind()))?;
+    }
+    delay.delay_us(ENABLE_SETTLE_TIME_US);
+
+    let mut sensors: [Option<TMag5273<I2C>>; N] = [const { None }; N];
+    for ((pin, address), sensor) in enable_pins.iter_mut().zip(addresses).zip(&mut sensors) {
+        // Only the sensors already brought up are enabled, so anything else on these addresses is another device
+        if answers(&mut new_device(), default_address)
+            || (address != default_address && answers(&mut new_device(), address))
+        {
+            return Err(TMag5273Error::AddressInUse);
+        }
+
+        pin.set_high()
+            .map_err(|err| TMag5273Error::Pin(err.kind()))?;
+        delay.delay_us(ENABLE_SETTLE_TIME_US);
+        *sensor = Some(if answers(&mut new_device(), address) {
+            TMag5273::new_with_address(new_device(), address, version)?
+        } else {
+            let mut moved = TMag5273::new_with_address(new_device(), default_address, version)?;
+            moved.change_address(address)?;
+            moved
+        });
+    }
+
+    // Every sensor is set by the loop above, as any failure returns early
+    Ok(sensors.map(|sensor| sensor.expect("every sensor is brought up")))
+}
+
+/// Checks if any device acknowledges the address.
+fn answers<I2C>(i2c: &mut I2C, address: SevenBitAddress) -> bool
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    i2c.read(address, &mut [0x00]).is_ok()
+}
@@ -0,0 +1,189 @@
+//! # Power-On-Reset Recovery
+//!
+//! A dip in the supply resets the device back to its defaults: stand-by mode, all channels disabled and the factory
+//! address. The driver remembers the configuration last written through it, from DeviceConfig1 up to and including
+//! the magnetic offsets, and the address it was moved to, so that a reset device can be put back as it was.
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+
+use crate::{
+    conversions,
+    registers::*,
+    types::{CacheMode, TMag5273Error},
+    TMag5273,
+};
+
+/// Number of registers restored after a power-on-reset, from DeviceConfig1 (0x00) up to and including
+/// MagOffsetConfig2 (0x0B).
+pub(crate) const RESTORED_LEN: usize = 12;
+
+/// The configuration last written to the device through the driver.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct AppliedConfig {
+    values: [u8; RESTORED_LEN],
+}
+
+impl AppliedConfig {
+    /// Records a value written to a register. Registers which are not restored are ignored.
+    pub(crate) fn update(&mut self, register: TMAG5273Register, value: u8) {
+        if let Some(applied) = self.values.get_mut(u8::from(register) as usize) {
+            *applied = value;
+        }
+    }
+
+    /// Records the values read from consecutive registers starting at `register`.
+    pub(crate) fn update_from_read(&mut self, register: TMAG5273Register, data: &[u8]) {
+        let start = u8::from(register) as usize;
+        for (applied, value) in self.values.iter_mut().skip(start).zip(data) {
+            *applied = *value;
+        }
+    }
+
+    /// Checks a value read back from the device against the applied configuration. Registers which are not
+    /// restored always match.
+    pub(crate) fn matches(&self, register: TMAG5273Register, data: &[u8]) -> bool {
+        let start = u8::from(register) as usize;
+        self.values
+            .iter()
+            .skip(start)
+            .zip(data)
+            .all(|(applied, value)| applied == value)
+    }
+
+    /// Checks a configuration register read back before a measurement against the applied configuration. A read
+    /// which failed never matches, so that it is checked for a reset as well.
+    pub(crate) fn matches_read<Register>(&self, read: &Result<Register, TMag5273Error>) -> bool
+    where
+        Register: BitFieldDeviceConfiguration,
+    {
+        read.as_ref()
+            .is_ok_and(|register| self.matches(Register::get_address(), &[register.raw_value()]))
+    }
+
+    /// Checks a pair of configuration registers read back before a measurement like matches_read.
+    pub(crate) fn matches_dual_read<Register>(&self, read: &Result<Register, TMag5273Error>) -> bool
+    where
+        Register: ByteFieldDeviceConfiguration,
+    {
+        read.as_ref().is_ok_and(|registers| {
+            self.matches(
+                Register::get_address(),
+                &registers.raw_value().to_le_bytes(),
+            )
+        })
+    }
+
+    /// The register writes which put a reset device back as it was. DeviceConfig2 goes after the rest of the
+    /// configuration, so the device only starts measuring once it is in place, and the POR flag is cleared last.
+    pub(crate) fn restore_writes(&self) -> impl Iterator<Item = (TMAG5273Register, u8)> + '_ {
+        (0..RESTORED_LEN as u8)
+            .filter(|address| *address != u8::from(TMAG5273Register::DeviceConfig2))
+            .chain([u8::from(TMAG5273Register::DeviceConfig2)])
+            .filter_map(|address| {
+                let register = TMAG5273Register::try_from(address).ok()?;
+                Some((register, self.values[address as usize]))
+            })
+            .chain([(TMAG5273Register::ConvStatus, conversions::CLEAR_POR)])
+    }
+}
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Checks if the device has been through a power-on-reset, and if so restores the configuration last written
+    /// through the driver and clears the POR flag. Returns true if the device was restored.
+    /// A device which has been moved to another address is looked for at its factory address when it no longer
+    /// answers, and moved back before it is restored.
+    ///
+    /// NOTE: with several sensors on one bus, a supply dip resets them all to the same factory address. They must
+    /// be brought up again with bring_up_sensors instead.
+    ///
+    /// ## Example
+    ///
+    /// ```Rust
+    /// if mag5273.recover()? {
+    ///     println!("The sensor was reset and has been restored");
+    /// }
+    /// ```
+    pub fn recover(&mut self) -> Result<bool, TMag5273Error> {
+        let factory_address = self.device_version.get_default_address();
+        let (status, answering) = match self.read_conversion_status(self.address) {
+            Ok(status) => (status, self.address),
+            Err(err) if self.address != factory_address => {
+                match self.read_conversion_status(factory_address) {
+                    Ok(status) if status.power_on_reset() => (status, factory_address),
+                    _ => return Err(err),
+                }
+            }
+            Err(err) => return Err(err),
+        };
+        if !status.power_on_reset() {
+            return Ok(false);
+        }
+
+        if answering != self.address {
+            let command = conversions::address_move(self.address)?;
+            self.i2c.write(answering, &command)?;
+        }
+        // The CRC and read mode are back to their defaults until DeviceConfig1 is restored
+        self.shadow = Default::default();
+        self.last_set_count = None;
+        let applied = self.applied;
+        for (register, value) in applied.restore_writes() {
+            self.write_register(register, value)?;
+        }
+        Ok(true)
+    }
+
+    /// Sets whether a power-on-reset is detected and recovered from while reading data, see
+    /// [recover](TMag5273::recover). The read which finds the reset returns a DeviceReset error once the device is
+    /// restored, as its data comes from the reset device, and the next read returns data again. Disabled by default.
+    ///
+    /// A reset is looked for when a configuration read back before a measurement differs from the one last written,
+    /// when the device stops answering, and through the POR flag read in the same burst as the data, so a healthy
+    /// device costs no extra transfers.
+    pub fn set_auto_recovery(&mut self, enabled: bool) {
+        self.auto_recovery = enabled;
+    }
+
+    /// Returns whether a power-on-reset is detected and recovered from while reading data.
+    pub fn get_auto_recovery(&self) -> bool {
+        self.auto_recovery
+    }
+
+    /// ### Raw Registers
+    ///
+    /// In auto recovery, checks whether a read which failed or returned an unexpected configuration was caused by a
+    /// power-on-reset, and recovers from it. Returns true if the device was restored.
+    pub(crate) fn recovered(&mut self) -> bool {
+        self.auto_recovery && matches!(self.recover(), Ok(true))
+    }
+
+    /// ### Raw Registers
+    ///
+    /// Reads the configuration back from the device into the applied configuration.
+    pub(crate) fn sync_applied(&mut self) -> Result<(), TMag5273Error> {
+        let mut data = [0x00; RESTORED_LEN];
+        self.read_registers(TMAG5273Register::DeviceConfig1, &mut data)?;
+        self.applied
+            .update_from_read(TMAG5273Register::DeviceConfig1, &data);
+        Ok(())
+    }
+
+    /// Whether the POR flag has to be read alongside the data.
+    pub(crate) fn reads_conversion_status(&self) -> bool {
+        self.cache_mode == CacheMode::Cached || self.auto_recovery
+    }
+
+    /// Reads the Conversion Status Register at an address. A single register read never reaches the CRC byte, so
+    /// this works whatever the CRC setting of the device is.
+    fn read_conversion_status(
+        &mut self,
+        address: SevenBitAddress,
+    ) -> Result<ConversionStatusRegister, TMag5273Error> {
+        let mut data = [0x00];
+        self.i2c
+            .write_read(address, &[TMAG5273Register::ConvStatus.into()], &mut data)?;
+        Ok(ConversionStatusRegister::new_with_raw_value(data[0]))
+    }
+}
@@ -26,7 +26,12 @@ pub use sensor_config_1::*;
 pub use sensor_config_2::*;
 pub use temperature_config::*;
 
-use crate::{types::TMag5273Error, TMag5273};
+use crate::{
+    conversions::{self, ResetAction},
+    shadow::{ShadowRegisters, SHADOW_LEN},
+    types::TMag5273Error,
+    TMag5273,
+};
 
 impl<I2C> TMag5273<I2C>
 where
@@ -40,10 +45,7 @@ where
     where
         Register: BitFieldDeviceConfiguration,
     {
-        let register_address = Register::get_address().into();
-        let data = register.raw_value();
-        self.i2c.write(self.address, &[register_address, data])?;
-        Ok(())
+        self.write_register(Register::get_address(), register.raw_value())
     }
     /// ### Raw Registers
     ///
@@ -54,9 +56,7 @@ where
         Register: BitFieldDeviceConfiguration,
     {
         let mut data: [u8; 1] = [0x00];
-        let register_address = Register::get_address().into();
-        self.i2c
-            .write_read(self.address, &[register_address], &mut data)?;
+        self.read_registers(Register::get_address(), &mut data)?;
         Ok(Register::new_with_raw_value(data[0]))
     }
 
@@ -69,9 +69,145 @@ where
         Register: ByteFieldDeviceConfiguration,
     {
         let mut data: [u8; 2] = [0x00, 2];
-        let register_address = Register::get_address().into();
-        self.i2c
-            .write_read(self.address, &[register_address], &mut data)?;
+        self.read_registers(Register::get_address(), &mut data)?;
         Ok(Register::new_with_raw_value(u16::from_le_bytes(data)))
     }
+
+    /// ### Raw Registers
+    ///
+    /// Reads consecutive registers from the device starting at `register`. When the I2C CRC is enabled
+    /// the read is split into frames of 4 register bytes, and the CRC byte sent after each frame is verified
+    /// before any data is returned.
+    pub(crate) fn read_registers(
+        &mut self,
+        register: TMAG5273Register,
+        data: &mut [u8],
+    ) -> Result<(), TMag5273Error> {
+        if !self.shadow.device_config_1().i2c_crc_enabled() {
+            self.i2c
+                .write_read(self.address, &[register.into()], data)?;
+        } else {
+            for (frame, chunk) in conversions::crc_frames(register, data) {
+                let mut frame_data = [0x00; conversions::CRC_FRAME_LEN];
+                self.i2c
+                    .write_read(self.address, &[frame.address], &mut frame_data)?;
+                frame.copy_to(frame_data, chunk)?;
+            }
+        }
+        // Keep the shadow in step with anything the device reports
+        self.shadow.update_from_read(register, data);
+        Ok(())
+    }
+
+    /// ### Raw Registers
+    ///
+    /// Writes a single register on the device, keeping the shadow copy of the configuration registers in step.
+    pub(crate) fn write_register(
+        &mut self,
+        register: TMAG5273Register,
+        value: u8,
+    ) -> Result<(), TMag5273Error> {
+        self.i2c.write(self.address, &[register.into(), value])?;
+        self.shadow.update(register, value);
+        self.applied.update(register, value);
+        Ok(())
+    }
+
+    /// ### Raw Registers
+    ///
+    /// Reads the configuration registers back from the device into the shadow copy.
+    pub(crate) fn sync_shadow(&mut self) -> Result<(), TMag5273Error> {
+        // A single register read never reaches the CRC byte, so the CRC setting can be read before it is known
+        let mut device_config_1 = [0x00];
+        self.i2c.write_read(
+            self.address,
+            &[TMAG5273Register::DeviceConfig1.into()],
+            &mut device_config_1,
+        )?;
+        self.shadow
+            .update(TMAG5273Register::DeviceConfig1, device_config_1[0]);
+
+        let mut data = [0x00; SHADOW_LEN];
+        self.read_registers(TMAG5273Register::DeviceConfig1, &mut data)?;
+        self.shadow = ShadowRegisters::from_bytes(data);
+        Ok(())
+    }
+
+    /// ### Raw Registers
+    ///
+    /// Gets a configuration register needed by an operation. The register is read from the device in the
+    /// ReadThrough cache mode, otherwise it is taken from the shadow copy when the shadow holds it.
+    /// In auto recovery, a register read back with a value other than the one last written is checked for a reset.
+    pub(crate) fn config_register<Register>(&mut self) -> Result<Register, TMag5273Error>
+    where
+        Register: BitFieldDeviceConfiguration,
+    {
+        if let Some(register) = self.shadow.cached(self.cache_mode) {
+            return Ok(register);
+        }
+        let register = self.get_config_register::<Register>();
+        if !self.applied.matches_read(&register) && self.recovered() {
+            return Err(TMag5273Error::DeviceReset);
+        }
+        register
+    }
+
+    /// ### Raw Registers
+    ///
+    /// Gets both Sensor Config registers, following the cache mode like config_register.
+    pub(crate) fn sensor_config_registers(
+        &mut self,
+    ) -> Result<SensorConfigRegisters, TMag5273Error> {
+        if let Some(registers) = self.shadow.cached_sensor_config(self.cache_mode) {
+            return Ok(registers);
+        }
+        let registers = self.get_dual_config_register::<SensorConfigRegisters>();
+        if !self.applied.matches_dual_read(&registers) && self.recovered() {
+            return Err(TMag5273Error::DeviceReset);
+        }
+        registers
+    }
+
+    /// ### Raw Registers
+    ///
+    /// Reads result registers starting at `register`. In the Cached mode and in auto recovery the Conversion Status
+    /// Register is read in the same burst. If the device has been through a power-on-reset since the configuration
+    /// was cached, auto recovery restores the device and throws a DeviceReset error, otherwise the shadow is
+    /// refreshed from the device and the POR flag cleared. Returns true if the shadow was refreshed, in which case
+    /// the configuration the data was decoded against must be checked again.
+    pub(crate) fn read_results(
+        &mut self,
+        register: TMAG5273Register,
+        data: &mut [u8],
+    ) -> Result<bool, TMag5273Error> {
+        if !self.reads_conversion_status() {
+            self.read_registers(register, data)?;
+            self.check_health_after_read()?;
+            return Ok(false);
+        }
+
+        let mut burst = conversions::ResultsBurst::new(register, data.len())?;
+        if let Err(err) = self.read_registers(burst.start, burst.buffer()) {
+            // A device which stopped answering may have been reset back to its factory address
+            return Err(match self.recovered() {
+                true => TMag5273Error::DeviceReset,
+                false => err,
+            });
+        }
+        let status = burst.split(data);
+        self.check_health_after_read()?;
+
+        match conversions::reset_action(status, self.auto_recovery) {
+            ResetAction::None => Ok(false),
+            ResetAction::Recover => {
+                self.recover()?;
+                Err(TMag5273Error::DeviceReset)
+            }
+            ResetAction::Refresh => {
+                self.sync_shadow()?;
+                self.write_register(TMAG5273Register::ConvStatus, conversions::CLEAR_POR)?;
+                Ok(true)
+            }
+        }
+    }
 }
@@ -17,7 +17,14 @@ pub enum Range {
 impl Range {
     /// Returns the range value in mT
     /// 40mT or 80mT for X1 and 133mT or 266mT for X2
+    #[cfg(feature = "float")]
     pub fn get_range(&self, version: DeviceVersion) -> f32 {
+        f32::from(self.get_range_mt(version))
+    }
+
+    /// Returns the range value in whole mT, for use without floating point
+    /// 40mT or 80mT for X1 and 133mT or 266mT for X2
+    pub fn get_range_mt(&self, version: DeviceVersion) -> u16 {
         match (self, version) {
             (
                 Range::Low,
@@ -25,28 +32,28 @@ impl Range {
                 | DeviceVersion::TMAG5273B1
                 | DeviceVersion::TMAG5273C1
                 | DeviceVersion::TMAG5273D1,
-            ) => 40.0,
+            ) => 40,
             (
                 Range::Low,
                 DeviceVersion::TMAG5273A2
                 | DeviceVersion::TMAG5273B2
                 | DeviceVersion::TMAG5273C2
                 | DeviceVersion::TMAG5273D2,
-            ) => 133.0,
+            ) => 133,
             (
                 Range::High,
                 DeviceVersion::TMAG5273A1
                 | DeviceVersion::TMAG5273B1
                 | DeviceVersion::TMAG5273C1
                 | DeviceVersion::TMAG5273D1,
-            ) => 80.0,
+            ) => 80,
             (
                 Range::High,
                 DeviceVersion::TMAG5273A2
                 | DeviceVersion::TMAG5273B2
                 | DeviceVersion::TMAG5273C2
                 | DeviceVersion::TMAG5273D2,
-            ) => 266.0,
+            ) => 266,
         }
     }
 }
@@ -0,0 +1,78 @@
+//! Shadow copy of the device configuration registers.
+//!
+//! The driver needs to know how the device is configured to decode some reads, for example the CRC setting or
+//! the layout of the frame sent in the 1-byte I2C read modes. Rather than reading the configuration back before
+//! every transfer, the driver syncs this copy when it is created and keeps it in step with every register it writes or reads.
+//! Unless the [CacheMode](crate::types::CacheMode) is `ReadThrough`, the copy is also used in place of reading the
+//! configuration back before a measurement.
+use crate::{registers::*, types::CacheMode};
+
+/// Number of registers held in the shadow, from DeviceConfig1 (0x00) up to and including TConfig (0x07).
+pub(crate) const SHADOW_LEN: usize = 8;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct ShadowRegisters {
+    values: [u8; SHADOW_LEN],
+}
+
+impl ShadowRegisters {
+    /// Creates the shadow from a read of the configuration registers starting at DeviceConfig1.
+    pub(crate) fn from_bytes(values: [u8; SHADOW_LEN]) -> Self {
+        Self { values }
+    }
+
+    /// Records a value written to a register. Registers outside of the shadow are ignored.
+    pub(crate) fn update(&mut self, register: TMAG5273Register, value: u8) {
+        if let Some(shadow) = self.values.get_mut(u8::from(register) as usize) {
+            *shadow = value;
+        }
+    }
+
+    /// Records the values read from consecutive registers starting at `register`.
+    pub(crate) fn update_from_read(&mut self, register: TMAG5273Register, data: &[u8]) {
+        let start = u8::from(register) as usize;
+        for (shadow, value) in self.values.iter_mut().skip(start).zip(data) {
+            *shadow = *value;
+        }
+    }
+
+    /// Returns the value of a register, or `None` if the register is outside of the shadow.
+    pub(crate) fn get(&self, register: TMAG5273Register) -> Option<u8> {
+        self.values.get(u8::from(register) as usize).copied()
+    }
+
+    /// Returns a configuration register from the shadow in the cached modes, or `None` if it has to be read from
+    /// the device.
+    pub(crate) fn cached<Register>(&self, mode: CacheMode) -> Option<Register>
+    where
+        Register: BitFieldDeviceConfiguration,
+    {
+        let value = self.get(Register::get_address())?;
+        (mode != CacheMode::ReadThrough).then(|| Register::new_with_raw_value(value))
+    }
+
+    /// Returns both Sensor Config registers from the shadow in the cached modes, or `None` if they have to be read
+    /// from the device.
+    pub(crate) fn cached_sensor_config(&self, mode: CacheMode) -> Option<SensorConfigRegisters> {
+        (mode != CacheMode::ReadThrough).then(|| self.sensor_config())
+    }
+
+    fn value(&self, register: TMAG5273Register) -> u8 {
+        self.values[u8::from(register) as usize]
+    }
+
+    pub(crate) fn device_config_1(&self) -> DeviceConfig1Register {
+        DeviceConfig1Register::new_with_raw_value(self.value(TMAG5273Register::DeviceConfig1))
+    }
+
+    pub(crate) fn sensor_config(&self) -> SensorConfigRegisters {
+        SensorConfigRegisters::new_with_raw_value(u16::from_le_bytes([
+            self.value(TMAG5273Register::SensorConfig1),
+            self.value(TMAG5273Register::SensorConfig2),
+        ]))
+    }
+
+    pub(crate) fn t_config(&self) -> TConfigRegister {
+        TConfigRegister::new_with_raw_value(self.value(TMAG5273Register::TConfig))
+    }
+}
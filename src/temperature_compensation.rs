@@ -0,0 +1,154 @@
+//! # Magnet Temperature Compensation
+//!
+//! The field of a permanent magnet drops as it warms up. The device can correct for this itself through `mag_tempo`,
+//! but only for 0.12%/degC (NdFeB) and 0.2%/degC (ceramic) magnets. A [TemperatureCompensation] corrects for any
+//! other magnet in software, from a linear coefficient or a lookup table, using the temperature measured on the chip
+//! to scale the field back to what it would be at a reference temperature.
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+
+use crate::{
+    conversions,
+    registers::DeviceConfig1Register,
+    types::{TMag5273ChannelData, TMag5273Error},
+    TMag5273,
+};
+
+/// Lowest temperature in degrees C the device operates at.
+const MIN_TEMPERATURE: f32 = -40.0;
+
+/// Highest temperature in degrees C the device operates at.
+const MAX_TEMPERATURE: f32 = 170.0;
+
+/// How the field of the magnet changes with its temperature.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MagnetTemperatureModel<'a> {
+    /// The reversible temperature coefficient of the remanence in % per degree C, as quoted by magnet datasheets.
+    /// It is negative for almost every magnet, e.g. -0.035 for SmCo
+    Linear(f32),
+    /// Pairs of a temperature in degrees C and the field of the magnet at it, in any unit as only the ratio between
+    /// two entries is used. The temperatures must be in ascending order. Temperatures in between are linearly
+    /// interpolated, and those beyond either end take the value at that end
+    Table(&'a [(f32, f32)]),
+}
+
+/// Normalises field readings to a reference temperature, created with [TemperatureCompensation::new].
+///
+/// ## Example
+///
+/// ```Rust
+/// // SmCo, normalised to 25 degC
+/// let compensation = TemperatureCompensation::new(MagnetTemperatureModel::Linear(-0.035), 25.0)?;
+/// let data = mag5273.get_compensated_data(&compensation)?;
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TemperatureCompensation<'a> {
+    model: MagnetTemperatureModel<'a>,
+    reference_temperature: f32,
+}
+
+impl<'a> TemperatureCompensation<'a> {
+    /// Creates a compensation normalising the field to `reference_temperature` in degrees C.
+    /// This will throw an OutOfRange error if the model would give a field of 0 or below anywhere in the operating
+    /// range of the device, or if a table has fewer than 2 entries or is not in ascending order of temperature.
+    pub fn new(
+        model: MagnetTemperatureModel<'a>,
+        reference_temperature: f32,
+    ) -> Result<Self, TMag5273Error> {
+        let valid = match model {
+            MagnetTemperatureModel::Linear(coefficient) => [MIN_TEMPERATURE, MAX_TEMPERATURE]
+                .iter()
+                .all(|temperature| {
+                    1.0 + coefficient / 100.0 * (temperature - reference_temperature) > 0.0
+                }),
+            MagnetTemperatureModel::Table(table) => {
+                table.len() >= 2
+                    && table.iter().all(|(_, field)| *field > 0.0)
+                    && table.windows(2).all(|pair| pair[0].0 < pair[1].0)
+            }
+        };
+        if !valid || !reference_temperature.is_finite() {
+            return Err(TMag5273Error::OutOfRange);
+        }
+        Ok(Self {
+            model,
+            reference_temperature,
+        })
+    }
+
+    /// The model of the magnet in use.
+    pub fn model(&self) -> MagnetTemperatureModel<'a> {
+        self.model
+    }
+
+    /// The temperature in degrees C the field is normalised to.
+    pub fn reference_temperature(&self) -> f32 {
+        self.reference_temperature
+    }
+
+    /// Returns the field of the magnet at `temperature` relative to its field at the reference temperature.
+    pub fn field_ratio(&self, temperature: f32) -> f32 {
+        match self.model {
+            MagnetTemperatureModel::Linear(coefficient) => {
+                1.0 + coefficient / 100.0 * (temperature - self.reference_temperature)
+            }
+            MagnetTemperatureModel::Table(table) => {
+                interpolate(table, temperature) / interpolate(table, self.reference_temperature)
+            }
+        }
+    }
+
+    /// Normalises a field measured at `temperature`, e.g. from get_temperature, to the reference temperature.
+    pub fn compensate_field(&self, field: f32, temperature: f32) -> f32 {
+        field / self.field_ratio(temperature)
+    }
+
+    /// Normalises the X, Y and Z axes of a sample to the reference temperature, using the temperature measured
+    /// alongside them. The temperature is left unchanged.
+    pub fn compensate(&self, data: TMag5273ChannelData) -> TMag5273ChannelData {
+        let ratio = self.field_ratio(data.temp);
+        TMag5273ChannelData {
+            x: data.x / ratio,
+            y: data.y / ratio,
+            z: data.z / ratio,
+            temp: data.temp,
+        }
+    }
+}
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Gets all the axis data like get_all_data, normalised to the reference temperature of the compensation.
+    /// This will throw a CompensationConflict error if the device compensates the magnet itself through
+    /// `mag_tempo`, so the correction is never applied twice.
+    pub fn get_compensated_data(
+        &mut self,
+        compensation: &TemperatureCompensation<'_>,
+    ) -> Result<TMag5273ChannelData, TMag5273Error> {
+        let config: DeviceConfig1Register = self.config_register()?;
+        conversions::check_compensation_conflict(config)?;
+        Ok(compensation.compensate(self.get_all_data()?))
+    }
+}
+
+/// Linearly interpolates the field at `temperature` from a table in ascending order of temperature.
+fn interpolate(table: &[(f32, f32)], temperature: f32) -> f32 {
+    let (Some(first), Some(last)) = (table.first(), table.last()) else {
+        return 1.0;
+    };
+    if temperature <= first.0 {
+        return first.1;
+    }
+    if temperature >= last.0 {
+        return last.1;
+    }
+    table
+        .windows(2)
+        .find(|pair| temperature <= pair[1].0)
+        .map(|pair| {
+            let ((t0, f0), (t1, f1)) = (pair[0], pair[1]);
+            f0 + (f1 - f0) * (temperature - t0) / (t1 - t0)
+        })
+        .unwrap_or(last.1)
+}
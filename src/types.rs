@@ -1,6 +1,9 @@
 use embedded_hal::i2c::SevenBitAddress;
 
-use crate::{registers::TMAG5273Register, DeviceId};
+use crate::{
+    registers::{ConversionStatusRegister, DeviceStatusRegister, TMAG5273Register},
+    DeviceId,
+};
 
 /// Device version
 #[derive(Debug, PartialEq, Clone, Copy, Default)]
@@ -40,6 +43,7 @@ impl DeviceVersion {
     }
 }
 // TMAG5273 All Sensor Channel Data
+#[cfg(feature = "float")]
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct TMag5273ChannelData {
     /// X Axis in mT
@@ -52,6 +56,189 @@ pub struct TMag5273ChannelData {
     pub temp: f32,
 }
 
+/// TMAG5273 data of the channels enabled on the device, any other channel is `None`.
+#[cfg(feature = "float")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TMag5273EnabledData {
+    /// X Axis in mT
+    pub x: Option<f32>,
+    /// Y Axis in mT
+    pub y: Option<f32>,
+    /// Z Axis in mT
+    pub z: Option<f32>,
+    /// Temperature in degrees C
+    pub temp: Option<f32>,
+}
+
+/// TMAG5273 data sent in one of the 1-byte I2C read modes.
+/// Only the channels enabled on the device are sent, any other channel is `None`.
+/// In the 8 bit read mode only the MSB of each channel is sent, so the values have a reduced resolution.
+#[cfg(feature = "float")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TMag5273FastReadData {
+    /// X Axis in mT
+    pub x: Option<f32>,
+    /// Y Axis in mT
+    pub y: Option<f32>,
+    /// Z Axis in mT
+    pub z: Option<f32>,
+    /// Temperature in degrees C
+    pub temp: Option<f32>,
+    /// The Conversion Status Register sent at the end of the data
+    pub conversion_status: ConversionStatusRegister,
+}
+
+/// Magnetic field sample as the signed code read from the result registers, alongside the range it is scaled by.
+/// Full scale is reached at a code of ±32768.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct RawMagneticSample {
+    /// Signed LSB count, from -32768 to 32767. X is inverted, like the floating point API, so the signs of all axes
+    /// agree, which makes its count run from -32767 to 32768
+    pub code: i32,
+    /// The ± range in mT the code is scaled by
+    pub range_mt: u16,
+}
+
+impl RawMagneticSample {
+    /// Returns the field in mT, rounded towards zero.
+    pub fn millitesla(&self) -> i32 {
+        self.code * i32::from(self.range_mt) / 32768
+    }
+
+    /// Returns the field in uT, rounded towards zero.
+    pub fn microtesla(&self) -> i32 {
+        (i64::from(self.code) * i64::from(self.range_mt) * 1000 / 32768) as i32
+    }
+}
+
+/// Temperature sample as the code read from the temperature result registers.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct RawTemperature {
+    /// Unsigned LSB count, 17508 at 25 degC with 60.1 LSB per degC
+    pub code: u16,
+}
+
+impl RawTemperature {
+    /// Returns the temperature in hundredths of a degree C, rounded to the nearest.
+    pub fn centi_degrees(&self) -> i32 {
+        crate::conversions::temp_centi_degrees(self.code)
+    }
+}
+
+/// TMAG5273 All Sensor Channel Data, without any floating point conversion
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TMag5273RawChannelData {
+    /// X Axis
+    pub x: RawMagneticSample,
+    /// Y Axis
+    pub y: RawMagneticSample,
+    /// Z Axis
+    pub z: RawMagneticSample,
+    /// Temperature
+    pub temp: RawTemperature,
+}
+
+/// TMAG5273 data of the channels enabled on the device, without any floating point conversion. Any other channel
+/// is `None`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TMag5273RawEnabledData {
+    /// X Axis
+    pub x: Option<RawMagneticSample>,
+    /// Y Axis
+    pub y: Option<RawMagneticSample>,
+    /// Z Axis
+    pub z: Option<RawMagneticSample>,
+    /// Temperature
+    pub temp: Option<RawTemperature>,
+}
+
+/// TMAG5273 data sent in one of the 1-byte I2C read modes, without any floating point conversion. Only the channels
+/// enabled on the device are sent, any other channel is `None`. In the 8 bit read mode only the MSB of each channel is
+/// sent, and the LSB of the code is zero.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TMag5273RawFastReadData {
+    /// X Axis
+    pub x: Option<RawMagneticSample>,
+    /// Y Axis
+    pub y: Option<RawMagneticSample>,
+    /// Z Axis
+    pub z: Option<RawMagneticSample>,
+    /// Temperature
+    pub temp: Option<RawTemperature>,
+    /// The Conversion Status Register sent at the end of the data
+    pub conversion_status: ConversionStatusRegister,
+}
+
+/// Every result and status register of the TMAG5273, read in a single transfer.
+/// Only the channels enabled on the device, and the angle and magnitude when the angle calculation is enabled, are
+/// decoded. Any other field is `None`.
+#[cfg(feature = "float")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TMag5273FrameData {
+    /// Temperature in degrees C
+    pub temp: Option<f32>,
+    /// X Axis in mT
+    pub x: Option<f32>,
+    /// Y Axis in mT
+    pub y: Option<f32>,
+    /// Z Axis in mT
+    pub z: Option<f32>,
+    /// The Conversion Status Register
+    pub conversion_status: ConversionStatusRegister,
+    /// Angle in degrees
+    pub angle: Option<f32>,
+    /// Resultant vector magnitude of the angle calculation
+    pub magnitude: Option<u8>,
+    /// The Device Status Register
+    pub device_status: DeviceStatusRegister,
+}
+
+/// Every result and status register of the TMAG5273, read in a single transfer, without any floating point
+/// conversion. Fields which are not valid for the configuration of the device are `None`, see [TMag5273FrameData].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TMag5273RawFrameData {
+    /// Temperature
+    pub temp: Option<RawTemperature>,
+    /// X Axis
+    pub x: Option<RawMagneticSample>,
+    /// Y Axis
+    pub y: Option<RawMagneticSample>,
+    /// Z Axis
+    pub z: Option<RawMagneticSample>,
+    /// The Conversion Status Register
+    pub conversion_status: ConversionStatusRegister,
+    /// Angle in steps of 1/16 degree, below 5760
+    pub angle: Option<u16>,
+    /// Resultant vector magnitude of the angle calculation
+    pub magnitude: Option<u8>,
+    /// The Device Status Register
+    pub device_status: DeviceStatusRegister,
+}
+
+/// How a sample relates to the one read before it, worked out from the rolling set count of the Conversion Status
+/// Register.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SampleFreshness {
+    /// The sample comes from the conversion after the previous one, or is the first sample read
+    Fresh,
+    /// The sample comes from the same conversion as the previous one
+    Stale,
+    /// The sample is new, but this many conversions were made in between and never read
+    Missed(u8),
+}
+
+/// Data read alongside the set count of the conversion it comes from.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TMag5273Sample<DATA> {
+    /// The data of the conversion
+    pub data: DATA,
+    /// The 3-bit rolling count of the conversion, from 0 to 7
+    pub set_count: u8,
+    /// How the sample relates to the one read before it
+    pub freshness: SampleFreshness,
+}
+
 /// Magnetic field Axis
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub enum Axis {
@@ -84,9 +271,65 @@ impl From<MagneticChannelOffset> for TMAG5273Register {
     }
 }
 
+/// Fault flags of the device, decoded from the Device Status and Conversion Status Registers by
+/// [health](crate::TMag5273::health).
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HealthReport {
+    /// The supply voltage dropped below the undervoltage threshold
+    pub vcc_under_voltage: bool,
+    /// The one time programmable memory failed its CRC check
+    pub otp_crc_error: bool,
+    /// The INT pin was found at a different level to the one the device drives
+    pub int_pin_error: bool,
+    /// The internal oscillator failed
+    pub oscillator_error: bool,
+    /// Any of the internal diagnostics failed. Not valid while the supply is below 2.3V
+    pub diagnostic_error: bool,
+    /// The device has been through a power-on-reset. This is not a fault, and is left for the driver to clear
+    pub power_on_reset: bool,
+}
+
+impl HealthReport {
+    /// Checks if no fault is flagged. A power-on-reset alone does not count as a fault.
+    pub fn is_healthy(&self) -> bool {
+        self.check().is_ok()
+    }
+
+    /// Turns the first flagged fault into an error. An undervoltage is reported first, as the other diagnostics are
+    /// not valid while the supply is low.
+    pub fn check(&self) -> Result<(), TMag5273Error> {
+        let faults = [
+            (self.vcc_under_voltage, TMag5273Error::VccUnderVoltage),
+            (self.otp_crc_error, TMag5273Error::OtpCrcError),
+            (self.int_pin_error, TMag5273Error::IntPinError),
+            (self.oscillator_error, TMag5273Error::OscillatorError),
+            (self.diagnostic_error, TMag5273Error::DiagnosticError),
+        ];
+        match faults.into_iter().find(|(flagged, _)| *flagged) {
+            Some((_, error)) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// How the driver gets the configuration it needs to validate and convert a read, see
+/// [set_cache_mode](crate::TMag5273::set_cache_mode).
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum CacheMode {
+    /// The configuration registers are read back from the device before every operation
+    #[default]
+    ReadThrough,
+    /// The configuration is taken from the driver's copy of the registers. The Conversion Status Register is read
+    /// in the same burst as the data, and if the device has been through a power-on-reset the copy is refreshed
+    /// before the data is converted
+    Cached,
+    /// The configuration is taken from the driver's copy of the registers without any check against the device
+    Unchecked,
+}
+
 /// Errors for the TMAG5273 device
 #[derive(PartialEq, Clone, Debug)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum TMag5273Error {
     /// No device at the devices address was found on the bus
     NotConnected,
@@ -100,8 +343,60 @@ pub enum TMag5273Error {
     OutOfRange,
     /// Channel is not enabled
     ChannelDisabled,
+    /// The CRC byte sent by the device does not match the data that was read
+    CrcMismatch,
+    /// The device did not complete the operation before the timeout
+    Timeout,
     /// An I2C error occurred
     I2c(embedded_hal::i2c::ErrorKind),
+    /// A GPIO pin error occurred, on the INT pin or an enable pin
+    Pin(embedded_hal::digital::ErrorKind),
+    /// Another device already answers at the address
+    AddressInUse,
+    /// The calibration samples could not be fitted, usually because they do not cover enough directions
+    CalibrationFailed,
+    /// The supply voltage of the device dropped below its undervoltage threshold
+    VccUnderVoltage,
+    /// The device's one time programmable memory failed its CRC check
+    OtpCrcError,
+    /// The device found the INT pin at a different level to the one it drives
+    IntPinError,
+    /// The internal oscillator of the device failed
+    OscillatorError,
+    /// The device flagged a diagnostic failure without reporting its cause
+    DiagnosticError,
+    /// The device was reset and its configuration has been restored, the operation must be repeated
+    DeviceReset,
+    /// The device already compensates the magnet temperature drift through mag_tempo
+    CompensationConflict,
+}
+
+/// Written by hand as the embedded-hal error kinds do not implement Format
+#[cfg(feature = "defmt")]
+impl defmt::Format for TMag5273Error {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            TMag5273Error::NotConnected => defmt::write!(f, "NotConnected"),
+            TMag5273Error::WrongDevice => defmt::write!(f, "WrongDevice"),
+            TMag5273Error::MalformedRegister => defmt::write!(f, "MalformedRegister"),
+            TMag5273Error::WrongMode => defmt::write!(f, "WrongMode"),
+            TMag5273Error::OutOfRange => defmt::write!(f, "OutOfRange"),
+            TMag5273Error::ChannelDisabled => defmt::write!(f, "ChannelDisabled"),
+            TMag5273Error::CrcMismatch => defmt::write!(f, "CrcMismatch"),
+            TMag5273Error::Timeout => defmt::write!(f, "Timeout"),
+            TMag5273Error::I2c(kind) => defmt::write!(f, "I2c({})", defmt::Debug2Format(kind)),
+            TMag5273Error::Pin(kind) => defmt::write!(f, "Pin({})", defmt::Debug2Format(kind)),
+            TMag5273Error::AddressInUse => defmt::write!(f, "AddressInUse"),
+            TMag5273Error::CalibrationFailed => defmt::write!(f, "CalibrationFailed"),
+            TMag5273Error::VccUnderVoltage => defmt::write!(f, "VccUnderVoltage"),
+            TMag5273Error::OtpCrcError => defmt::write!(f, "OtpCrcError"),
+            TMag5273Error::IntPinError => defmt::write!(f, "IntPinError"),
+            TMag5273Error::OscillatorError => defmt::write!(f, "OscillatorError"),
+            TMag5273Error::DiagnosticError => defmt::write!(f, "DiagnosticError"),
+            TMag5273Error::DeviceReset => defmt::write!(f, "DeviceReset"),
+            TMag5273Error::CompensationConflict => defmt::write!(f, "CompensationConflict"),
+        }
+    }
 }
 
 impl<E: embedded_hal::i2c::Error> From<E> for TMag5273Error {
@@ -122,7 +417,19 @@ impl core::fmt::Display for TMag5273Error {
             TMag5273Error::WrongMode => write!(f, "The device is in the wrong mode for the operation"),
             TMag5273Error::OutOfRange => write!(f, "Input is out of range"),
             TMag5273Error::ChannelDisabled => write!(f, "Channel is not enabled"),
+            TMag5273Error::CrcMismatch => write!(f, "The CRC byte sent by the device does not match the data that was read"),
+            TMag5273Error::Timeout => write!(f, "The device did not complete the operation before the timeout"),
             TMag5273Error::I2c(e) => write!(f, "An I2C error occurred: {e:?}"),
+            TMag5273Error::Pin(e) => write!(f, "A GPIO pin error occurred: {e:?}"),
+            TMag5273Error::AddressInUse => write!(f, "Another device already answers at the address"),
+            TMag5273Error::CalibrationFailed => write!(f, "The calibration samples could not be fitted, usually because they do not cover enough directions"),
+            TMag5273Error::VccUnderVoltage => write!(f, "The supply voltage of the device dropped below its undervoltage threshold"),
+            TMag5273Error::OtpCrcError => write!(f, "The device's one time programmable memory failed its CRC check"),
+            TMag5273Error::IntPinError => write!(f, "The device found the INT pin at a different level to the one it drives"),
+            TMag5273Error::OscillatorError => write!(f, "The internal oscillator of the device failed"),
+            TMag5273Error::DiagnosticError => write!(f, "The device flagged a diagnostic failure without reporting its cause"),
+            TMag5273Error::DeviceReset => write!(f, "The device was reset and its configuration has been restored, the operation must be repeated"),
+            TMag5273Error::CompensationConflict => write!(f, "The device already compensates the magnet temperature drift through mag_tempo"),
         }
     }
 }
@@ -0,0 +1,419 @@
+//! # Typestate API
+//!
+//! A wrapper around the [TMag5273](crate::TMag5273) driver which tracks the operating mode and enabled channels
+//! in its type, so only the getters which are valid in the current mode can be called. Reading the angle of a
+//! device with the angle calculation disabled, or all the data of a device without every channel enabled, is
+//! then a compile time error rather than a WrongMode or ChannelDisabled error at runtime.
+//!
+//! Every mode transition consumes the sensor and writes the configuration the new mode needs. The getters still
+//! read the configuration back like the rest of the driver, so a device which has been reset behind the driver's
+//! back is caught at runtime.
+//!
+//! ## Example
+//!
+//! ```Rust
+//! use tmag5273::typestate::{TMag5273, XY, XYZ};
+//!
+//! let mag5273 = TMag5273::new(i2c, DeviceVersion::TMAG5273B1)?;
+//! let mut mag5273 = mag5273.into_continuous::<XYZ>()?;
+//! let data = mag5273.get_all_data()?;
+//! let mut mag5273 = mag5273.into_angle_mode::<XY>()?;
+//! let angle = mag5273.get_angle()?;
+//! // mag5273.get_all_data() does not compile in the angle mode
+//! ```
+use core::marker::PhantomData;
+
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+
+#[cfg(feature = "float")]
+use crate::types::{MagneticChannelOffset, TMag5273ChannelData};
+use crate::{
+    registers::*,
+    types::{
+        Axis, DeviceVersion, RawMagneticSample, RawTemperature, TMag5273Error,
+        TMag5273RawChannelData,
+    },
+};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Stand-by mode, no conversions are made so no data can be read.
+pub struct Standby;
+
+/// Continuous measure mode, with the magnetic channels `CH` and the temperature channel enabled.
+pub struct Continuous<CH> {
+    _channels: PhantomData<CH>,
+}
+
+/// Continuous measure mode, with the angle calculated between the axis pair `A`.
+pub struct AngleMode<A> {
+    _axes: PhantomData<A>,
+}
+
+/// X channel enabled
+pub struct X;
+/// Y channel enabled
+pub struct Y;
+/// Z channel enabled
+pub struct Z;
+/// X and Y channels enabled, or the angle between X and Y
+pub struct XY;
+/// X and Z channels enabled, or the angle between X and Z
+pub struct XZ;
+/// Y and Z channels enabled, or the angle between Y and Z
+pub struct YZ;
+/// X, Y and Z channels enabled
+pub struct XYZ;
+
+/// A set of magnetic channels which can be enabled together.
+pub trait Channels: sealed::Sealed {
+    /// The MAG_CH_EN value which enables the channels
+    fn mag_channel() -> MagneticChannel;
+}
+
+/// A pair of axes which the angle can be calculated between.
+pub trait AngleAxes: Channels {
+    /// The ANGLE_EN value which selects the axes
+    fn angle() -> Angle;
+}
+
+/// Channel sets which include the X axis
+pub trait HasX: Channels {}
+/// Channel sets which include the Y axis
+pub trait HasY: Channels {}
+/// Channel sets which include the Z axis
+pub trait HasZ: Channels {}
+
+macro_rules! impl_channels {
+    ($channels:ident, $mag_channel:ident $(, $axis:ident)*) => {
+        impl sealed::Sealed for $channels {}
+        impl Channels for $channels {
+            fn mag_channel() -> MagneticChannel {
+                MagneticChannel::$mag_channel
+            }
+        }
+        $(impl $axis for $channels {})*
+    };
+}
+
+impl_channels!(X, X, HasX);
+impl_channels!(Y, Y, HasY);
+impl_channels!(Z, Z, HasZ);
+impl_channels!(XY, XY, HasX, HasY);
+impl_channels!(XZ, XZ, HasX, HasZ);
+impl_channels!(YZ, YZ, HasY, HasZ);
+impl_channels!(XYZ, XYZ, HasX, HasY, HasZ);
+
+impl AngleAxes for XY {
+    fn angle() -> Angle {
+        Angle::XY
+    }
+}
+impl AngleAxes for XZ {
+    fn angle() -> Angle {
+        Angle::XZ
+    }
+}
+impl AngleAxes for YZ {
+    fn angle() -> Angle {
+        Angle::YZ
+    }
+}
+
+/// TMAG5273 driver which tracks its operating mode in the `MODE` type parameter.
+pub struct TMag5273<I2C, MODE> {
+    sensor: crate::TMag5273<I2C>,
+    _mode: PhantomData<MODE>,
+}
+
+impl<I2C> TMag5273<I2C, Standby>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Create an instance of the TMag5273xx device in stand-by mode.
+    /// The device will be checked for connectivity by reading the manufacturer ID.
+    pub fn new(i2c: I2C, version: DeviceVersion) -> Result<Self, TMag5273Error> {
+        Self::from_sensor(crate::TMag5273::new(i2c, version)?)
+    }
+
+    /// Create an instance of a TMag5273xx device with a specific address in stand-by mode.
+    pub fn new_with_address(
+        i2c: I2C,
+        address: SevenBitAddress,
+        version: DeviceVersion,
+    ) -> Result<Self, TMag5273Error> {
+        Self::from_sensor(crate::TMag5273::new_with_address(i2c, address, version)?)
+    }
+
+    /// Takes an existing sensor, putting it in stand-by mode.
+    pub fn from_sensor(sensor: crate::TMag5273<I2C>) -> Result<Self, TMag5273Error> {
+        Self {
+            sensor,
+            _mode: PhantomData,
+        }
+        .into_standby()
+    }
+}
+
+impl<I2C, MODE> TMag5273<I2C, MODE>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Puts the device in stand-by mode.
+    pub fn into_standby(mut self) -> Result<TMag5273<I2C, Standby>, TMag5273Error> {
+        self.set_operating_mode(OperatingMode::StandBy)?;
+        Ok(self.into_mode())
+    }
+
+    /// Puts the device in continuous measure mode with the magnetic channels `CH` and the temperature
+    /// channel enabled, and the angle calculation disabled.
+    pub fn into_continuous<CH: Channels>(
+        mut self,
+    ) -> Result<TMag5273<I2C, Continuous<CH>>, TMag5273Error> {
+        self.set_channels(CH::mag_channel(), Angle::Disabled)?;
+        let t_config = self
+            .sensor
+            .get_config_register::<TConfigRegister>()?
+            .with_temperature_channel_enabled(true);
+        self.sensor.set_config_register(t_config)?;
+        self.set_operating_mode(OperatingMode::ContinuousMeasure)?;
+        Ok(self.into_mode())
+    }
+
+    /// Puts the device in continuous measure mode with the angle calculated between the axes `A`.
+    pub fn into_angle_mode<A: AngleAxes>(
+        mut self,
+    ) -> Result<TMag5273<I2C, AngleMode<A>>, TMag5273Error> {
+        self.set_channels(A::mag_channel(), A::angle())?;
+        self.set_operating_mode(OperatingMode::ContinuousMeasure)?;
+        Ok(self.into_mode())
+    }
+
+    /// Hands back the untyped driver, which keeps the current configuration.
+    pub fn release(self) -> crate::TMag5273<I2C> {
+        self.sensor
+    }
+
+    /// Check if the device is connected.
+    pub fn is_connected(&mut self) -> bool {
+        self.sensor.is_connected()
+    }
+
+    /// Get the manufacturer ID.
+    pub fn get_manufacturer_id(&mut self) -> Result<u16, TMag5273Error> {
+        self.sensor.get_manufacturer_id()
+    }
+
+    /// Get the device ID.
+    pub fn get_device_id(&mut self) -> Result<DeviceId, TMag5273Error> {
+        self.sensor.get_device_id()
+    }
+
+    /// Sets the XY and Z axis ranges. Thresholds and offsets are scaled by the range, so set the range first.
+    pub fn set_ranges(&mut self, xy_range: Range, z_range: Range) -> Result<(), TMag5273Error> {
+        let sensor_config_2 = self
+            .sensor
+            .get_config_register::<SensorConfig2Register>()?
+            .with_xy_range(xy_range)
+            .with_z_range(z_range);
+        self.sensor.set_config_register(sensor_config_2)
+    }
+
+    /// Set the threshold in mT for an axis, see [TMag5273::set_mag_threshold](crate::TMag5273::set_mag_threshold).
+    #[cfg(feature = "float")]
+    pub fn set_mag_threshold(&mut self, threshold: f32, axis: Axis) -> Result<(), TMag5273Error> {
+        self.sensor.set_mag_threshold(threshold, axis)
+    }
+
+    /// Get the threshold in mT for an axis.
+    #[cfg(feature = "float")]
+    pub fn get_mag_threshold(&mut self, axis: Axis) -> Result<f32, TMag5273Error> {
+        self.sensor.get_mag_threshold(axis)
+    }
+
+    /// Set the temperature threshold in degC, `None` disables the threshold comparison.
+    #[cfg(feature = "float")]
+    pub fn set_temperature_threshold(
+        &mut self,
+        threshold: Option<f32>,
+    ) -> Result<(), TMag5273Error> {
+        self.sensor.set_temperature_threshold(threshold)
+    }
+
+    /// Get the temperature threshold in degC.
+    #[cfg(feature = "float")]
+    pub fn get_temperature_threshold(&mut self) -> Result<Option<f32>, TMag5273Error> {
+        self.sensor.get_temperature_threshold()
+    }
+
+    fn into_mode<NEW>(self) -> TMag5273<I2C, NEW> {
+        TMag5273 {
+            sensor: self.sensor,
+            _mode: PhantomData,
+        }
+    }
+
+    fn set_operating_mode(&mut self, operating_mode: OperatingMode) -> Result<(), TMag5273Error> {
+        let device_config_2 = self
+            .sensor
+            .get_config_register::<DeviceConfig2Register>()?
+            .with_operating_mode(operating_mode);
+        self.sensor.set_config_register(device_config_2)
+    }
+
+    fn set_channels(
+        &mut self,
+        mag_channel: MagneticChannel,
+        angle: Angle,
+    ) -> Result<(), TMag5273Error> {
+        let sensor_config_1 = self
+            .sensor
+            .get_config_register::<SensorConfig1Register>()?
+            .with_mag_channel(mag_channel);
+        self.sensor.set_config_register(sensor_config_1)?;
+        let sensor_config_2 = self
+            .sensor
+            .get_config_register::<SensorConfig2Register>()?
+            .with_angle(angle);
+        self.sensor.set_config_register(sensor_config_2)
+    }
+}
+
+impl<I2C, CH> TMag5273<I2C, Continuous<CH>>
+where
+    I2C: I2c<SevenBitAddress>,
+    CH: Channels,
+{
+    /// Get the temperature of the device in degrees Celsius.
+    #[cfg(feature = "float")]
+    pub fn get_temperature(&mut self) -> Result<f32, TMag5273Error> {
+        self.sensor.get_temperature()
+    }
+
+    /// Get the X axis data in mT.
+    #[cfg(feature = "float")]
+    pub fn get_x(&mut self) -> Result<f32, TMag5273Error>
+    where
+        CH: HasX,
+    {
+        self.sensor.get_mag_data(Axis::X)
+    }
+
+    /// Get the Y axis data in mT.
+    #[cfg(feature = "float")]
+    pub fn get_y(&mut self) -> Result<f32, TMag5273Error>
+    where
+        CH: HasY,
+    {
+        self.sensor.get_mag_data(Axis::Y)
+    }
+
+    /// Get the Z axis data in mT.
+    #[cfg(feature = "float")]
+    pub fn get_z(&mut self) -> Result<f32, TMag5273Error>
+    where
+        CH: HasZ,
+    {
+        self.sensor.get_mag_data(Axis::Z)
+    }
+
+    /// Get the temperature code of the device.
+    pub fn get_raw_temperature(&mut self) -> Result<RawTemperature, TMag5273Error> {
+        self.sensor.get_raw_temperature()
+    }
+
+    /// Get the X axis code alongside its range.
+    pub fn get_raw_x(&mut self) -> Result<RawMagneticSample, TMag5273Error>
+    where
+        CH: HasX,
+    {
+        self.sensor.get_raw_mag_data(Axis::X)
+    }
+
+    /// Get the Y axis code alongside its range.
+    pub fn get_raw_y(&mut self) -> Result<RawMagneticSample, TMag5273Error>
+    where
+        CH: HasY,
+    {
+        self.sensor.get_raw_mag_data(Axis::Y)
+    }
+
+    /// Get the Z axis code alongside its range.
+    pub fn get_raw_z(&mut self) -> Result<RawMagneticSample, TMag5273Error>
+    where
+        CH: HasZ,
+    {
+        self.sensor.get_raw_mag_data(Axis::Z)
+    }
+}
+
+impl<I2C> TMag5273<I2C, Continuous<XYZ>>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Get all the axis data from the device, alongside the temperature of the device.
+    #[cfg(feature = "float")]
+    pub fn get_all_data(&mut self) -> Result<TMag5273ChannelData, TMag5273Error> {
+        self.sensor.get_all_data()
+    }
+
+    /// Get all the axis data from the device, alongside the temperature of the device, without any floating
+    /// point conversion.
+    pub fn get_raw_all_data(&mut self) -> Result<TMag5273RawChannelData, TMag5273Error> {
+        self.sensor.get_raw_all_data()
+    }
+}
+
+impl<I2C, A> TMag5273<I2C, AngleMode<A>>
+where
+    I2C: I2c<SevenBitAddress>,
+    A: AngleAxes,
+{
+    /// Gets the calculated angle in degrees.
+    #[cfg(feature = "float")]
+    pub fn get_angle(&mut self) -> Result<f32, TMag5273Error> {
+        self.sensor.get_angle()
+    }
+
+    /// Gets the calculated angle in steps of 1/16 degree.
+    pub fn get_raw_angle(&mut self) -> Result<u16, TMag5273Error> {
+        self.sensor.get_raw_angle()
+    }
+
+    /// Returns the resultant vector magnitude of the angle measurement.
+    pub fn get_magnitude(&mut self) -> Result<u8, TMag5273Error> {
+        self.sensor.get_magnitude()
+    }
+
+    /// Set the magnetic gain applied to the angle calculation, mapped 0 - 255 = 0 - 100%.
+    pub fn set_magnetic_gain(&mut self, gain: u8) -> Result<(), TMag5273Error> {
+        self.sensor.set_magnetic_gain(gain)
+    }
+
+    /// Get the magnetic gain applied to the angle calculation.
+    pub fn get_magnetic_gain(&mut self) -> Result<u8, TMag5273Error> {
+        self.sensor.get_magnetic_gain()
+    }
+
+    /// Set the magnetic offset correction in mT for one of the angle axes, see
+    /// [TMag5273::set_magnetic_offset](crate::TMag5273::set_magnetic_offset).
+    #[cfg(feature = "float")]
+    pub fn set_magnetic_offset(
+        &mut self,
+        offset: f32,
+        mag_offset: MagneticChannelOffset,
+    ) -> Result<(), TMag5273Error> {
+        self.sensor.set_magnetic_offset(offset, mag_offset)
+    }
+
+    /// Get the magnetic offset correction in mT for one of the angle axes.
+    #[cfg(feature = "float")]
+    pub fn get_magnetic_offset(
+        &mut self,
+        mag_offset: MagneticChannelOffset,
+    ) -> Result<f32, TMag5273Error> {
+        self.sensor.get_magnetic_offset(mag_offset)
+    }
+}
@@ -0,0 +1,151 @@
+//! # Physical Units
+//!
+//! Typed variants of the floating point getters and setters, enabled with the `uom` feature. The values are
+//! [uom](https://docs.rs/uom) quantities rather than bare `f32`s, so a threshold given in gauss or uT is converted
+//! rather than silently taken as mT. The plain `f32` API is unchanged.
+//!
+//! ## Example
+//!
+//! ```Rust
+//! use uom::si::magnetic_flux_density::{gauss, millitesla};
+//!
+//! mag5273.set_mag_threshold_quantity(MagneticFluxDensity::new::<gauss>(50.0), Axis::Z)?;
+//! let z = mag5273.get_mag_data_quantity(Axis::Z)?;
+//! println!("{} mT", z.get::<millitesla>());
+//! ```
+pub use uom::si::f32::{Angle, MagneticFluxDensity, ThermodynamicTemperature};
+
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+use uom::si::{
+    angle::degree, magnetic_flux_density::millitesla, thermodynamic_temperature::degree_celsius,
+};
+
+use crate::{
+    types::{Axis, MagneticChannelOffset, TMag5273ChannelData, TMag5273Error},
+    TMag5273,
+};
+
+/// TMAG5273 All Sensor Channel Data as physical quantities
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TMag5273QuantityData {
+    /// X Axis
+    pub x: MagneticFluxDensity,
+    /// Y Axis
+    pub y: MagneticFluxDensity,
+    /// Z Axis
+    pub z: MagneticFluxDensity,
+    /// Temperature
+    pub temp: ThermodynamicTemperature,
+}
+
+impl From<TMag5273ChannelData> for TMag5273QuantityData {
+    fn from(data: TMag5273ChannelData) -> Self {
+        Self {
+            x: flux_density(data.x),
+            y: flux_density(data.y),
+            z: flux_density(data.z),
+            temp: temperature(data.temp),
+        }
+    }
+}
+
+pub(crate) fn flux_density(value: f32) -> MagneticFluxDensity {
+    MagneticFluxDensity::new::<millitesla>(value)
+}
+
+pub(crate) fn temperature(value: f32) -> ThermodynamicTemperature {
+    ThermodynamicTemperature::new::<degree_celsius>(value)
+}
+
+pub(crate) fn angle(value: f32) -> Angle {
+    Angle::new::<degree>(value)
+}
+
+pub(crate) fn to_millitesla(value: MagneticFluxDensity) -> f32 {
+    value.get::<millitesla>()
+}
+
+pub(crate) fn to_degree_celsius(value: ThermodynamicTemperature) -> f32 {
+    value.get::<degree_celsius>()
+}
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Set the threshold for an axis as a magnetic flux density, see [set_mag_threshold](TMag5273::set_mag_threshold).
+    pub fn set_mag_threshold_quantity(
+        &mut self,
+        threshold: MagneticFluxDensity,
+        axis: Axis,
+    ) -> Result<(), TMag5273Error> {
+        self.set_mag_threshold(to_millitesla(threshold), axis)
+    }
+
+    /// Get the threshold for an axis as a magnetic flux density.
+    pub fn get_mag_threshold_quantity(
+        &mut self,
+        axis: Axis,
+    ) -> Result<MagneticFluxDensity, TMag5273Error> {
+        self.get_mag_threshold(axis).map(flux_density)
+    }
+
+    /// Set the temperature threshold, see [set_temperature_threshold](TMag5273::set_temperature_threshold).
+    /// `None` disables the threshold comparison.
+    pub fn set_temperature_threshold_quantity(
+        &mut self,
+        threshold: Option<ThermodynamicTemperature>,
+    ) -> Result<(), TMag5273Error> {
+        self.set_temperature_threshold(threshold.map(to_degree_celsius))
+    }
+
+    /// Get the temperature threshold, or `None` if the threshold comparison is disabled.
+    pub fn get_temperature_threshold_quantity(
+        &mut self,
+    ) -> Result<Option<ThermodynamicTemperature>, TMag5273Error> {
+        Ok(self.get_temperature_threshold()?.map(temperature))
+    }
+
+    /// Set the magnetic offset correction as a magnetic flux density, see
+    /// [set_magnetic_offset](TMag5273::set_magnetic_offset).
+    pub fn set_magnetic_offset_quantity(
+        &mut self,
+        offset: MagneticFluxDensity,
+        mag_offset: MagneticChannelOffset,
+    ) -> Result<(), TMag5273Error> {
+        self.set_magnetic_offset(to_millitesla(offset), mag_offset)
+    }
+
+    /// Get the magnetic offset correction as a magnetic flux density.
+    pub fn get_magnetic_offset_quantity(
+        &mut self,
+        mag_offset: MagneticChannelOffset,
+    ) -> Result<MagneticFluxDensity, TMag5273Error> {
+        self.get_magnetic_offset(mag_offset).map(flux_density)
+    }
+
+    /// Get the temperature of the device. This will throw an error if the temperature channel is not enabled.
+    pub fn get_temperature_quantity(&mut self) -> Result<ThermodynamicTemperature, TMag5273Error> {
+        self.get_temperature().map(temperature)
+    }
+
+    /// Gets the specific axis channel data. This will throw an error if the channel is not enabled.
+    pub fn get_mag_data_quantity(
+        &mut self,
+        axis: Axis,
+    ) -> Result<MagneticFluxDensity, TMag5273Error> {
+        self.get_mag_data(axis).map(flux_density)
+    }
+
+    /// Get all the axis data from the device, alongside the temperature of the device.
+    /// If the correct Channels are not enabled, an error is thrown.
+    pub fn get_all_data_quantity(&mut self) -> Result<TMag5273QuantityData, TMag5273Error> {
+        self.get_all_data().map(TMag5273QuantityData::from)
+    }
+
+    /// Gets the measured/calculated angle of the enabled channel.
+    /// This will throw an error if the channels are not enabled.
+    pub fn get_angle_quantity(&mut self) -> Result<Angle, TMag5273Error> {
+        self.get_angle().map(angle)
+    }
+}
@@ -0,0 +1,215 @@
+//! # Wake-up and Sleep
+//!
+//! In wake-up and sleep (W&S) mode the device sleeps for the chosen interval, wakes up to make a conversion and
+//! goes back to sleep, until the interrupt condition is met. Once it is met the device asserts the interrupt and
+//! goes to stand-by mode, keeping the last result in the result registers. This lets the host sleep as well until
+//! the magnetic field crosses one of the thresholds, for example to detect a lid being opened on a battery budget.
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+
+use crate::{
+    conversions,
+    registers::*,
+    types::{Axis, TMag5273Error},
+    TMag5273,
+};
+
+/// Configuration for waking the host when the magnetic field crosses a threshold.
+#[derive(Default, Debug)]
+pub struct WakeOnThresholdConfig {
+    /// Threshold for the X axis in mT. `None` leaves the X channel disabled
+    pub x_threshold: Option<f32>,
+    /// Threshold for the Y axis in mT. `None` leaves the Y channel disabled
+    pub y_threshold: Option<f32>,
+    /// Threshold for the Z axis in mT. `None` leaves the Z channel disabled
+    pub z_threshold: Option<f32>,
+    /// Selects if the interrupt is asserted for a field above or below the thresholds
+    pub threshold_direction: MagThresholdDirection,
+    /// Number of threshold crossings before the interrupt is asserted
+    pub threshold_crossing_count: ThresholdCrossingCount,
+    /// Time spent asleep between conversions
+    pub sleep_time: SleepTime,
+    /// Selects how the interrupt is sent to the host
+    pub interrupt_mode: InterruptMode,
+    /// INT interrupt latched or pulsed
+    pub int_pin_mode: INTPinMode,
+    /// Also wake the host when a conversion completes, not just when a threshold is crossed
+    pub conversion_complete_interrupt_enabled: bool,
+}
+
+/// Reason the device left wake-up and sleep mode.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WakeReason {
+    /// The field crossed the threshold of at least one axis
+    Threshold,
+    /// A conversion completed without crossing any threshold
+    Conversion,
+}
+
+/// Handle to a device in wake-up and sleep mode, created by [TMag5273::wake_on_threshold].
+pub struct WakeOnThreshold<'a, I2C> {
+    sensor: &'a mut TMag5273<I2C>,
+    /// The X, Y and Z thresholds as set on the device, in mT
+    thresholds: [Option<f32>; 3],
+    /// Whether the interrupt is asserted for a field below the thresholds
+    below: bool,
+    /// Device Config 2 value which starts the wake-up and sleep mode
+    device_config_2: u8,
+}
+
+impl<I2C> TMag5273<I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Puts the device into wake-up and sleep mode, asserting the interrupt when the field crosses one of the thresholds.
+    /// Only the channels with a threshold are enabled, and the registers are set in the order given by the datasheet:
+    /// the device is put in stand-by mode, then the sensor, threshold and interrupt configuration is set, before
+    /// finally starting the wake-up and sleep mode. Any setting not covered by the config, such as the averaging or
+    /// the INT pin mask, is kept as it is on the device.
+    /// The thresholds are scaled by the range the device is currently configured for, so set the range first.
+    /// If no threshold is given a ChannelDisabled error is thrown.
+    ///
+    /// ## Example
+    ///
+    /// ```Rust
+    /// let mut wake = mag5273.wake_on_threshold(WakeOnThresholdConfig {
+    ///     z_threshold: Some(10.0),
+    ///     sleep_time: SleepTime::Ms100,
+    ///     interrupt_mode: InterruptMode::INTInterrupt,
+    ///     ..Default::default()
+    /// })?;
+    /// // Sleep until the INT pin is asserted
+    /// if wake.wake_reason()? == Some(WakeReason::Threshold) {
+    ///     // Handle the lid being opened
+    /// }
+    /// wake.rearm()?;
+    /// ```
+    pub fn wake_on_threshold(
+        &mut self,
+        config: WakeOnThresholdConfig,
+    ) -> Result<WakeOnThreshold<'_, I2C>, TMag5273Error> {
+        let mag_channel = conversions::mag_channel_from_axes(
+            config.x_threshold.is_some(),
+            config.y_threshold.is_some(),
+            config.z_threshold.is_some(),
+        )?;
+        let below = config.threshold_direction == MagThresholdDirection::Below;
+
+        // Stop any conversions while the device is being configured
+        let device_config_2 = self
+            .get_config_register::<DeviceConfig2Register>()?
+            .with_operating_mode(OperatingMode::StandBy);
+        self.set_config_register(device_config_2)?;
+
+        let sensor_config_1 = self
+            .get_config_register::<SensorConfig1Register>()?
+            .with_sleep_time(config.sleep_time)
+            .with_mag_channel(mag_channel);
+        self.set_config_register(sensor_config_1)?;
+        let sensor_config_2 = self
+            .get_config_register::<SensorConfig2Register>()?
+            .with_threshold_direction(config.threshold_direction)
+            .with_threshold_crossing_count(config.threshold_crossing_count);
+        self.set_config_register(sensor_config_2)?;
+
+        // Keep the thresholds as the device stores them, so the wake reason matches the device
+        let mut thresholds = [None; 3];
+        let axes = [
+            (Axis::X, config.x_threshold),
+            (Axis::Y, config.y_threshold),
+            (Axis::Z, config.z_threshold),
+        ];
+        for ((axis, threshold), applied) in axes.into_iter().zip(thresholds.iter_mut()) {
+            // A threshold of 0 disables the comparison on the axis
+            self.set_mag_threshold(threshold.unwrap_or(0.0), axis)?;
+            if threshold.is_some() {
+                *applied = Some(self.get_mag_threshold(axis)?);
+            }
+        }
+
+        let int_config = self
+            .get_config_register::<InterruptConfigRegister>()?
+            .with_interrupt_mode(config.interrupt_mode)
+            .with_int_pin_mode(config.int_pin_mode)
+            .with_threshold_interrupt_enabled(true)
+            .with_conversion_complete_interrupt_enabled(
+                config.conversion_complete_interrupt_enabled,
+            );
+        self.set_config_register(int_config)?;
+
+        let device_config_2 = device_config_2
+            .with_threshold(Threshold::TwosComplement)
+            .with_operating_mode(OperatingMode::WakeUpAndSleep);
+        self.set_config_register(device_config_2)?;
+
+        Ok(WakeOnThreshold {
+            sensor: self,
+            thresholds,
+            below,
+            device_config_2: device_config_2.raw_value(),
+        })
+    }
+}
+
+impl<I2C> WakeOnThreshold<'_, I2C>
+where
+    I2C: I2c<SevenBitAddress>,
+{
+    /// Returns why the device woke up, or `None` if it is still in wake-up and sleep mode.
+    /// This should be called once the host has been woken by the interrupt.
+    ///
+    /// NOTE: the device has no status flag for the source of the interrupt, so the reason is a heuristic based on the
+    /// last result kept by the device: if any axis is past its threshold the device is taken to have woken on the
+    /// threshold, otherwise on the conversion complete interrupt. When both interrupts are enabled, a conversion
+    /// which completes just past a threshold is reported as a threshold crossing, even if it was not counted
+    /// towards the threshold crossing count yet.
+    pub fn wake_reason(&mut self) -> Result<Option<WakeReason>, TMag5273Error> {
+        let device_config_2: DeviceConfig2Register = self.sensor.get_config_register()?;
+        if !matches!(device_config_2.operating_mode(), OperatingMode::StandBy) {
+            return Ok(None);
+        }
+
+        let mut data = [0x00; 6];
+        self.sensor
+            .read_registers(TMAG5273Register::XMsbResult, &mut data)?;
+        let config2 = self.sensor.sensor_config_registers()?.sensor_config2();
+        let xy_range = config2.xy_range().get_range(self.sensor.device_version);
+        let z_range = config2.z_range().get_range(self.sensor.device_version);
+
+        let crossed = self
+            .thresholds
+            .iter()
+            .zip(data.chunks(2))
+            .zip([xy_range, xy_range, z_range])
+            .any(|((threshold, sample), range)| match threshold {
+                Some(threshold) => conversions::threshold_crossed(
+                    [sample[0], sample[1]],
+                    range,
+                    *threshold,
+                    self.below,
+                ),
+                None => false,
+            });
+        match crossed {
+            true => Ok(Some(WakeReason::Threshold)),
+            false => Ok(Some(WakeReason::Conversion)),
+        }
+    }
+
+    /// Puts the device back into wake-up and sleep mode after it has woken up.
+    pub fn rearm(&mut self) -> Result<(), TMag5273Error> {
+        self.sensor
+            .write_register(TMAG5273Register::DeviceConfig2, self.device_config_2)
+    }
+
+    /// Gives access to the sensor, for example to read the data that woke the device.
+    pub fn sensor(&mut self) -> &mut TMag5273<I2C> {
+        self.sensor
+    }
+
+    /// Puts the device in stand-by mode, ending the wake-up and sleep mode.
+    pub fn stop(self) -> Result<(), TMag5273Error> {
+        let device_config_2 = DeviceConfig2Register::new_with_raw_value(self.device_config_2)
+            .with_operating_mode(OperatingMode::StandBy);
+        self.sensor.set_config_register(device_config_2)
+    }
+}
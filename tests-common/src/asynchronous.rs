@@ -0,0 +1,147 @@
+//! # Async Simulator
+//!
+//! Adapters which run the async driver against the simulated sensor, without needing an async runtime:
+//! - [AsyncRefCellDevice], an async I2C handle to a simulated device or bus shared through a RefCell.
+//! - The async INT pin, through the `Wait` implementation of [SimulatedIntPin].
+//! - [SimulatedDelay], an async delay in which each poll counts as one microsecond passing.
+//! - [block_on], which polls a future to completion on the current thread.
+//!
+//! Like the blocking simulator, each poll of the INT pin counts as a step of time in which the next conversion of
+//! the continuous modes completes, so a future waiting on the pin completes without any other task running.
+use core::cell::RefCell;
+use core::future::{poll_fn, Future};
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+use embedded_hal::digital::InputPin;
+use embedded_hal::i2c::{ErrorType, I2c as BlockingI2c, Operation, SevenBitAddress};
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::digital::Wait;
+use embedded_hal_async::i2c::I2c;
+
+use crate::simulator::SimulatedIntPin;
+
+/// Polls the future on the current thread until it completes.
+///
+/// ## Example
+///
+/// ```Rust
+/// let simulator = RefCell::new(SimulatedTMag5273::new(DeviceVersion::TMAG5273B1));
+/// let mag_sensor = block_on(TMag5273::new(AsyncRefCellDevice::new(&simulator), DeviceVersion::TMAG5273B1))?;
+/// ```
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// Async I2C handle to a simulated device or bus shared through a RefCell, like RefCellDevice from embedded-hal-bus
+/// does for the blocking traits. Every transaction completes on its first poll.
+pub struct AsyncRefCellDevice<'a, T> {
+    bus: &'a RefCell<T>,
+}
+
+impl<'a, T> AsyncRefCellDevice<'a, T> {
+    pub fn new(bus: &'a RefCell<T>) -> Self {
+        Self { bus }
+    }
+}
+
+impl<T: ErrorType> ErrorType for AsyncRefCellDevice<'_, T> {
+    type Error = T::Error;
+}
+
+impl<T: BlockingI2c> I2c<SevenBitAddress> for AsyncRefCellDevice<'_, T> {
+    async fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.bus.borrow_mut().transaction(address, operations)
+    }
+}
+
+impl SimulatedIntPin<'_> {
+    /// Reads the pin on every poll until `done` is true for the level read, true when the pin is low.
+    async fn wait_until(&mut self, mut done: impl FnMut(bool) -> bool) {
+        poll_fn(|cx| {
+            let Ok(low) = self.is_low();
+            match done(low) {
+                true => Poll::Ready(()),
+                false => {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+}
+
+impl Wait for SimulatedIntPin<'_> {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        self.wait_until(|low| !low).await;
+        Ok(())
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        self.wait_until(|low| low).await;
+        Ok(())
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        let mut was_low = false;
+        self.wait_until(|low| {
+            let edge = was_low && !low;
+            was_low = low;
+            edge
+        })
+        .await;
+        Ok(())
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        let mut was_high = false;
+        self.wait_until(|low| {
+            let edge = was_high && low;
+            was_high = !low;
+            edge
+        })
+        .await;
+        Ok(())
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        let mut previous = None;
+        self.wait_until(|low| {
+            let edge = previous.is_some_and(|previous| previous != low);
+            previous = Some(low);
+            edge
+        })
+        .await;
+        Ok(())
+    }
+}
+
+/// Async delay in which each poll counts as one microsecond passing, so a timeout is reached after a known number
+/// of polls of the futures it is raced against.
+#[derive(Default)]
+pub struct SimulatedDelay;
+
+impl DelayNs for SimulatedDelay {
+    async fn delay_ns(&mut self, ns: u32) {
+        let mut remaining_us = ns.div_ceil(1_000);
+        poll_fn(|cx| match remaining_us {
+            0 => Poll::Ready(()),
+            _ => {
+                remaining_us -= 1;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
@@ -3,6 +3,12 @@ use embedded_hal_bus::i2c::RefCellDevice;
 use registers::*;
 use tmag5273::*;
 use types::*;
+use units::{MagneticFluxDensity, ThermodynamicTemperature};
+use uom::si::{
+    angle::degree,
+    magnetic_flux_density::{gauss, microtesla, millitesla},
+    thermodynamic_temperature::degree_celsius,
+};
 
 const SENSOR_PART: DeviceVersion = DeviceVersion::TMAG5273B1;
 
@@ -147,6 +153,85 @@ where
     assert!(mag_sensor.is_connected());
 }
 
+pub fn generic_test_change_address<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let i2c_ref_cell = RefCell::new(i2c);
+    let default_address = SENSOR_PART.get_default_address();
+    let new_address = 0x55;
+
+    {
+        // Scope to drop the mutable borrow of mag_sensor
+        let mut mag_sensor = TMag5273::new(RefCellDevice::new(&i2c_ref_cell), SENSOR_PART).unwrap();
+
+        // Reserved addresses are rejected before anything is written
+        for address in [0x00, 0x07, 0x78, 0x80] {
+            assert_eq!(
+                mag_sensor.change_address(address),
+                Err(TMag5273Error::OutOfRange)
+            );
+        }
+        assert!(mag_sensor.is_connected());
+
+        // The same handle keeps working at the new address
+        mag_sensor
+            .change_address(new_address)
+            .expect("Failed to change I2C address");
+        assert!(mag_sensor.is_connected());
+        let register: I2cAddressRegister = mag_sensor
+            .get_config_register()
+            .expect("Failed to read I2C Address Register");
+        assert_eq!(register.i2c_address().value(), new_address);
+
+        // Nothing answers at the old address any more
+        assert!(TMag5273::new(RefCellDevice::new(&i2c_ref_cell), SENSOR_PART).is_err());
+
+        mag_sensor
+            .change_address(default_address)
+            .expect("Failed to change I2C address back");
+        assert!(mag_sensor.is_connected());
+
+        // Reset the I2C Address Register
+        let reset_i2c_register = I2cAddressRegister::builder()
+            .with_i2c_address_update_enabled(false)
+            .with_i2c_address(arbitrary_int::u7::new(default_address))
+            .build();
+        mag_sensor
+            .set_config_register(reset_i2c_register)
+            .expect("Failed to reset I2C Address Register");
+    }
+
+    let mut mag_sensor = TMag5273::new(RefCellDevice::new(&i2c_ref_cell), SENSOR_PART).unwrap();
+    assert!(mag_sensor.is_connected());
+}
+
+pub fn generic_test_scan_factory_addresses<I2C>(mut i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let device = scan_factory_addresses(&mut i2c)
+        .map(|device| device.expect("Failed to scan the bus"))
+        .find(|device| device.address == SENSOR_PART.get_default_address())
+        .expect("The sensor was not found at its factory address");
+    assert!(device.manufacturer_id_matches);
+    assert_eq!(device.device_id, Some(SENSOR_PART.get_device_id()));
+
+    // Only one variant starts at each factory address
+    let mut candidates = device.version_candidates();
+    assert_eq!(candidates.next(), Some(SENSOR_PART));
+    assert_eq!(candidates.next(), None);
+
+    // The full scan finds the sensor as well
+    assert!(scan_bus(&mut i2c)
+        .map(|device| device.expect("Failed to scan the bus"))
+        .any(|device| device.address == SENSOR_PART.get_default_address()));
+
+    // The driver can still be created once the scan has finished
+    let mut mag_sensor = TMag5273::new(i2c, SENSOR_PART).unwrap();
+    assert!(mag_sensor.is_connected());
+}
+
 pub fn generic_test_set_reset_int_config_1_register<I2C>(i2c: I2C)
 where
     I2C: embedded_hal::i2c::I2c,
@@ -268,3 +353,743 @@ where
 }
 
 //TODO: Add Tests for setting Thresholds!
+
+pub fn generic_test_crc_enabled_reads<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let mut mag_sensor =
+        TMag5273::new(i2c, SENSOR_PART).expect("Failed to create mag sensor instance");
+    reset_register!(DeviceConfig1Register, mag_sensor);
+    reset_register!(SensorConfig2Register, mag_sensor);
+
+    mag_sensor
+        .set_device_config(DeviceConfig {
+            i2c_crc_enabled: true,
+            ..Default::default()
+        })
+        .expect("Failed to enable I2C CRC");
+
+    // Every read below is now verified against the CRC byte sent by the device
+    let applied_device_config_1: DeviceConfig1Register = mag_sensor
+        .get_config_register()
+        .expect("Failed to get Device Config Register 1 with CRC enabled");
+    assert!(applied_device_config_1.i2c_crc_enabled());
+    assert_eq!(
+        mag_sensor
+            .get_manufacturer_id()
+            .expect("Failed to get manufacturer id with CRC enabled"),
+        MANUFACTURER_ID_VALUE
+    );
+    // The last register in the map is read from a frame that starts before it
+    mag_sensor
+        .get_config_register::<DeviceStatusRegister>()
+        .expect("Failed to get Device Status Register with CRC enabled");
+
+    let new_sensor_config_2 = SensorConfig2Register::builder()
+        .with_z_range(Range::High)
+        .with_xy_range(Range::Low)
+        .with_angle(Angle::XY)
+        .with_gain_channel(MagGainChannel::Second)
+        .with_threshold_direction(MagThresholdDirection::Below)
+        .with_threshold_crossing_count(ThresholdCrossingCount::Four)
+        .build();
+    mag_sensor
+        .set_config_register(new_sensor_config_2)
+        .expect("Failed to set Sensor Config Register 2");
+    let applied_sensor_config_2: SensorConfig2Register = mag_sensor
+        .get_config_register()
+        .expect("Failed to get Sensor Config Register 2 with CRC enabled");
+    assert_eq!(applied_sensor_config_2, new_sensor_config_2);
+
+    // Turning the CRC back off returns the driver to plain reads
+    reset_register!(SensorConfig2Register, mag_sensor);
+    reset_register!(DeviceConfig1Register, mag_sensor);
+    assert!(mag_sensor.is_connected());
+}
+
+pub fn generic_test_fast_read_modes<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    // Enables XYZ and the temperature channel in continuous mode
+    let mut mag_sensor = TMag5273::new(i2c, SENSOR_PART)
+        .expect("Failed to create mag sensor instance")
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+
+    // The 1-byte reads are rejected while in the standard 3-byte read mode
+    assert_eq!(
+        mag_sensor.get_fast_read_data(),
+        Err(TMag5273Error::WrongMode)
+    );
+
+    for read_mode in [I2cReadMode::OneByte16Bit, I2cReadMode::TwoByte8Bit] {
+        mag_sensor
+            .set_device_config(DeviceConfig {
+                i2c_read_mode: read_mode,
+                operating_mode: OperatingMode::ContinuousMeasure,
+                ..Default::default()
+            })
+            .expect("Failed to set the I2C read mode");
+        let data = mag_sensor
+            .get_fast_read_data()
+            .expect("Failed to get fast read data");
+        assert!(data.x.is_some());
+        assert!(data.y.is_some());
+        assert!(data.z.is_some());
+        let temp = data.temp.expect("Temperature channel should be sent");
+        assert!((-40.0..=170.0).contains(&temp));
+
+        let raw = mag_sensor
+            .get_fast_read_data_raw()
+            .expect("Failed to get raw fast read data");
+        let axes = [raw.x, raw.y, raw.z].map(|axis| axis.expect("Axis should be sent"));
+        assert!(raw.temp.is_some());
+        // Only the MSB is sent in the 8 bit mode
+        if matches!(read_mode, I2cReadMode::TwoByte8Bit) {
+            assert!(axes.iter().all(|axis| axis.code & 0xFF == 0));
+        }
+    }
+
+    // 16 bit data for all 4 channels can not be sent with the CRC enabled
+    mag_sensor
+        .set_device_config(DeviceConfig {
+            i2c_read_mode: I2cReadMode::OneByte16Bit,
+            i2c_crc_enabled: true,
+            operating_mode: OperatingMode::ContinuousMeasure,
+            ..Default::default()
+        })
+        .expect("Failed to set the I2C read mode");
+    assert_eq!(
+        mag_sensor.get_fast_read_data(),
+        Err(TMag5273Error::WrongMode)
+    );
+
+    // The 8 bit data can be sent with the CRC for any combination of channels
+    mag_sensor
+        .set_device_config(DeviceConfig {
+            i2c_read_mode: I2cReadMode::TwoByte8Bit,
+            i2c_crc_enabled: true,
+            operating_mode: OperatingMode::ContinuousMeasure,
+            ..Default::default()
+        })
+        .expect("Failed to set the I2C read mode");
+    mag_sensor
+        .get_fast_read_data()
+        .expect("Failed to get fast read data with CRC enabled");
+
+    reset_register!(DeviceConfig1Register, mag_sensor);
+    reset_register!(DeviceConfig2Register, mag_sensor);
+    assert!(mag_sensor.is_connected());
+}
+
+pub fn generic_test_set_reset_temperature_threshold<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let mut mag_sensor =
+        TMag5273::new(i2c, SENSOR_PART).expect("Failed to create mag sensor instance");
+    reset_register!(TConfigRegister, mag_sensor);
+    assert_eq!(
+        mag_sensor
+            .get_temperature_threshold()
+            .expect("Failed to get temperature threshold"),
+        None
+    );
+
+    // The threshold is rounded to the nearest 8 degC step above -41 degC
+    for (threshold, expected) in [(-41.0, -41.0), (25.0, 23.0), (85.0, 87.0), (167.0, 167.0)] {
+        mag_sensor
+            .set_temperature_threshold(Some(threshold))
+            .expect("Failed to set temperature threshold");
+        assert_eq!(
+            mag_sensor
+                .get_temperature_threshold()
+                .expect("Failed to get temperature threshold"),
+            Some(expected)
+        );
+    }
+
+    // Values beyond the codes at either end are rejected rather than clamped
+    for threshold in [-42.0, 168.0, 170.0] {
+        assert_eq!(
+            mag_sensor.set_temperature_threshold(Some(threshold)),
+            Err(TMag5273Error::OutOfRange)
+        );
+    }
+
+    // The temperature channel setting is kept when the threshold is set
+    let t_config = TConfigRegister::builder()
+        .with_temperature_channel_enabled(true)
+        .with_t_thr_config(arbitrary_int::u7::new(0))
+        .build();
+    mag_sensor
+        .set_config_register(t_config)
+        .expect("Failed to set TConfig Register");
+    mag_sensor
+        .set_temperature_threshold(Some(120.0))
+        .expect("Failed to set temperature threshold");
+    let applied_t_config: TConfigRegister = mag_sensor
+        .get_config_register()
+        .expect("Failed to get TConfig Register");
+    assert!(applied_t_config.temperature_channel_enabled());
+
+    mag_sensor
+        .set_temperature_threshold(None)
+        .expect("Failed to disable temperature threshold");
+    assert_eq!(
+        mag_sensor
+            .get_temperature_threshold()
+            .expect("Failed to get temperature threshold"),
+        None
+    );
+    reset_register!(TConfigRegister, mag_sensor);
+}
+
+pub fn generic_test_set_reset_magnetic_offset<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let mut mag_sensor =
+        TMag5273::new(i2c, SENSOR_PART).expect("Failed to create mag sensor instance");
+    reset_register!(SensorConfig2Register, mag_sensor);
+    assert_eq!(
+        mag_sensor.set_magnetic_offset(1.0, MagneticChannelOffset::FirstAxis),
+        Err(TMag5273Error::WrongMode)
+    );
+
+    // X 1st and Z 2nd, each using the resolution of its own range
+    let sensor_config_2 = SensorConfig2Register::builder()
+        .with_z_range(Range::High)
+        .with_xy_range(Range::Low)
+        .with_angle(Angle::XZ)
+        .with_gain_channel(MagGainChannel::First)
+        .with_threshold_direction(MagThresholdDirection::Above)
+        .with_threshold_crossing_count(ThresholdCrossingCount::One)
+        .build();
+    mag_sensor
+        .set_config_register(sensor_config_2)
+        .expect("Failed to set Sensor Config Register 2");
+
+    for (mag_offset, range) in [
+        (MagneticChannelOffset::FirstAxis, Range::Low),
+        (MagneticChannelOffset::SecondAxis, Range::High),
+    ] {
+        let resolution = range.get_range(SENSOR_PART) / 2048.0;
+        for offset in [-2.0, -0.3, 0.0, 0.3125, 1.7] {
+            mag_sensor
+                .set_magnetic_offset(offset, mag_offset)
+                .expect("Failed to set magnetic offset");
+            let applied_offset = mag_sensor
+                .get_magnetic_offset(mag_offset)
+                .expect("Failed to get magnetic offset");
+            assert!(
+                (applied_offset - offset).abs() <= resolution / 2.0,
+                "Offset {offset} mT read back as {applied_offset} mT"
+            );
+        }
+        // Largest correction is 127 times the resolution
+        assert_eq!(
+            mag_sensor.set_magnetic_offset(resolution * 128.0, mag_offset),
+            Err(TMag5273Error::OutOfRange)
+        );
+        mag_sensor
+            .set_magnetic_offset(0.0, mag_offset)
+            .expect("Failed to reset magnetic offset");
+    }
+    reset_register!(SensorConfig2Register, mag_sensor);
+}
+
+pub fn generic_test_measure_once<I2C, D>(i2c: I2C, mut delay: D)
+where
+    I2C: embedded_hal::i2c::I2c,
+    D: embedded_hal::delay::DelayNs,
+{
+    let mut mag_sensor =
+        TMag5273::new(i2c, SENSOR_PART).expect("Failed to create mag sensor instance");
+    // Stand-by mode with the I2C trigger
+    reset_register!(DeviceConfig2Register, mag_sensor);
+
+    // At least one channel is needed for a measurement
+    assert_eq!(
+        mag_sensor.measure_once(&mut delay, 100_000),
+        Err(TMag5273Error::ChannelDisabled)
+    );
+
+    // Only the enabled channels are measured
+    mag_sensor
+        .set_config_register(SensorConfig1Register::default().with_mag_channel(MagneticChannel::Z))
+        .expect("Failed to set Sensor Config Register 1");
+    let data = mag_sensor
+        .measure_once(&mut delay, 100_000)
+        .expect("Failed to measure once");
+    assert!(data.x.is_none() && data.y.is_none() && data.temp.is_none());
+    assert!(data.z.is_some());
+
+    mag_sensor
+        .set_config_register(
+            SensorConfig1Register::default().with_mag_channel(MagneticChannel::XYZ),
+        )
+        .expect("Failed to set Sensor Config Register 1");
+    mag_sensor
+        .set_config_register(TConfigRegister::default().with_temperature_channel_enabled(true))
+        .expect("Failed to set T Config Register");
+
+    for _ in 0..3 {
+        let data = mag_sensor
+            .measure_once(&mut delay, 100_000)
+            .expect("Failed to measure once");
+        let temp = data.temp.expect("The temperature channel is enabled");
+        assert!((-40.0..=170.0).contains(&temp));
+    }
+
+    // Conversions can only be triggered over I2C in stand-by mode
+    mag_sensor
+        .set_config_register(
+            DeviceConfig2Register::default().with_operating_mode(OperatingMode::ContinuousMeasure),
+        )
+        .expect("Failed to set Device Config Register 2");
+    assert_eq!(
+        mag_sensor.trigger_conversion(),
+        Err(TMag5273Error::WrongMode)
+    );
+
+    reset_register!(DeviceConfig2Register, mag_sensor);
+    reset_register!(SensorConfig1Register, mag_sensor);
+    reset_register!(TConfigRegister, mag_sensor);
+}
+
+pub fn generic_test_wake_on_threshold<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let mut mag_sensor =
+        TMag5273::new(i2c, SENSOR_PART).expect("Failed to create mag sensor instance");
+
+    // At least one threshold is needed to wake the device
+    assert!(matches!(
+        mag_sensor.wake_on_threshold(WakeOnThresholdConfig::default()),
+        Err(TMag5273Error::ChannelDisabled)
+    ));
+
+    // A threshold near the top of the range is not crossed by the field around the test rig
+    let mut wake = mag_sensor
+        .wake_on_threshold(WakeOnThresholdConfig {
+            z_threshold: Some(35.0),
+            sleep_time: SleepTime::Ms1,
+            interrupt_mode: InterruptMode::INTInterrupt,
+            ..Default::default()
+        })
+        .expect("Failed to start wake-up and sleep mode");
+    assert_eq!(wake.wake_reason().expect("Failed to get wake reason"), None);
+
+    let sensor = wake.sensor();
+    let sensor_config_1: SensorConfig1Register = sensor
+        .get_config_register()
+        .expect("Failed to get Sensor Config Register 1");
+    assert_eq!(sensor_config_1.mag_channel(), MagneticChannel::Z);
+    let int_config: InterruptConfigRegister = sensor
+        .get_config_register()
+        .expect("Failed to get Interrupt Config Register");
+    assert!(int_config.threshold_interrupt_enabled());
+    assert!(!int_config.conversion_complete_interrupt_enabled());
+    let device_config_2: DeviceConfig2Register = sensor
+        .get_config_register()
+        .expect("Failed to get Device Config Register 2");
+    assert!(matches!(
+        device_config_2.operating_mode(),
+        OperatingMode::WakeUpAndSleep
+    ));
+
+    wake.stop().expect("Failed to stop wake-up and sleep mode");
+    let device_config_2: DeviceConfig2Register = mag_sensor
+        .get_config_register()
+        .expect("Failed to get Device Config Register 2");
+    assert!(matches!(
+        device_config_2.operating_mode(),
+        OperatingMode::StandBy
+    ));
+
+    mag_sensor
+        .set_mag_threshold(0.0, Axis::Z)
+        .expect("Failed to reset Z threshold");
+    reset_register!(DeviceConfig2Register, mag_sensor);
+    reset_register!(SensorConfig1Register, mag_sensor);
+    reset_register!(SensorConfig2Register, mag_sensor);
+    reset_register!(InterruptConfigRegister, mag_sensor);
+}
+
+pub fn generic_test_cache_modes<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let mut mag_sensor = TMag5273::new(i2c, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    assert_eq!(mag_sensor.get_cache_mode(), CacheMode::ReadThrough);
+
+    for cache_mode in [
+        CacheMode::ReadThrough,
+        CacheMode::Cached,
+        CacheMode::Unchecked,
+    ] {
+        mag_sensor.set_cache_mode(cache_mode);
+        assert_eq!(mag_sensor.get_cache_mode(), cache_mode);
+        mag_sensor.get_all_data().expect("Failed to get all data");
+        mag_sensor
+            .get_mag_data(Axis::Z)
+            .expect("Failed to get Z data");
+
+        // Writes made through the driver are seen by every mode
+        let t_config = mag_sensor
+            .get_config_register::<TConfigRegister>()
+            .expect("Failed to get Temperature Config Register");
+        mag_sensor
+            .set_config_register(t_config.with_temperature_channel_enabled(false))
+            .expect("Failed to disable the temperature channel");
+        assert_eq!(
+            mag_sensor.get_temperature(),
+            Err(TMag5273Error::ChannelDisabled)
+        );
+        mag_sensor
+            .set_config_register(t_config)
+            .expect("Failed to enable the temperature channel");
+        mag_sensor
+            .get_temperature()
+            .expect("Failed to get temperature");
+    }
+
+    mag_sensor.refresh_cache().expect("Failed to refresh cache");
+    mag_sensor.set_cache_mode(CacheMode::ReadThrough);
+    reset_register!(DeviceConfig1Register, mag_sensor);
+    reset_register!(DeviceConfig2Register, mag_sensor);
+    reset_register!(SensorConfig1Register, mag_sensor);
+    reset_register!(SensorConfig2Register, mag_sensor);
+    reset_register!(TConfigRegister, mag_sensor);
+}
+
+pub fn generic_test_raw_data<I2C, D>(i2c: I2C, mut delay: D)
+where
+    I2C: embedded_hal::i2c::I2c,
+    D: embedded_hal::delay::DelayNs,
+{
+    let mut mag_sensor =
+        TMag5273::new(i2c, SENSOR_PART).expect("Failed to create mag sensor instance");
+    // Stand-by mode, so the results only change when a conversion is triggered
+    reset_register!(DeviceConfig2Register, mag_sensor);
+    mag_sensor
+        .set_config_register(
+            SensorConfig1Register::default().with_mag_channel(MagneticChannel::XYZ),
+        )
+        .expect("Failed to set Sensor Config Register 1");
+    mag_sensor
+        .set_config_register(SensorConfig2Register::default().with_angle(Angle::XY))
+        .expect("Failed to set Sensor Config Register 2");
+    mag_sensor
+        .set_config_register(TConfigRegister::default().with_temperature_channel_enabled(true))
+        .expect("Failed to set T Config Register");
+
+    let raw = mag_sensor
+        .measure_once_raw(&mut delay, 100_000)
+        .expect("Failed to measure once");
+    let raw_enabled = mag_sensor
+        .get_raw_enabled_data()
+        .expect("Failed to get raw enabled data");
+    assert_eq!(raw_enabled, raw);
+    let raw = mag_sensor
+        .get_raw_all_data()
+        .expect("Failed to get raw data");
+    assert_eq!(Some(raw.z), raw_enabled.z);
+    assert_eq!(
+        mag_sensor
+            .get_raw_mag_data(Axis::Z)
+            .expect("Failed to get raw Z data"),
+        raw.z
+    );
+    assert_eq!(
+        mag_sensor
+            .get_raw_temperature()
+            .expect("Failed to get raw temperature"),
+        raw.temp
+    );
+    let range = Range::Low.get_range_mt(SENSOR_PART);
+    assert_eq!(raw.x.range_mt, range);
+    assert_eq!(raw.z.range_mt, range);
+
+    // The fixed point values agree with the floating point API
+    let data = mag_sensor.get_all_data().expect("Failed to get all data");
+    for (sample, value) in [(raw.x, data.x), (raw.y, data.y), (raw.z, data.z)] {
+        assert!((sample.microtesla() as f32 / 1000.0 - value).abs() <= 0.002);
+        assert!((sample.millitesla() as f32 - value).abs() < 1.0);
+    }
+    assert!((raw.temp.centi_degrees() as f32 / 100.0 - data.temp).abs() <= 0.01);
+    let angle = mag_sensor.get_raw_angle().expect("Failed to get raw angle");
+    assert!(angle < 360 * 16);
+    assert_eq!(
+        angle as f32 / 16.0,
+        mag_sensor.get_angle().expect("Failed to get angle")
+    );
+
+    reset_register!(SensorConfig1Register, mag_sensor);
+    reset_register!(SensorConfig2Register, mag_sensor);
+    reset_register!(TConfigRegister, mag_sensor);
+}
+
+pub fn generic_test_units<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let mut mag_sensor = TMag5273::new(i2c, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    let sensor_config_2: SensorConfig2Register = mag_sensor
+        .get_config_register::<SensorConfig2Register>()
+        .expect("Failed to get Sensor Config Register 2")
+        .with_angle(Angle::XY);
+    mag_sensor
+        .set_config_register(sensor_config_2)
+        .expect("Failed to set Sensor Config Register 2");
+
+    // 100 G is 10 mT, with a resolution of the range / 128
+    let resolution = Range::High.get_range(SENSOR_PART) / 128.0;
+    mag_sensor
+        .set_mag_threshold_quantity(MagneticFluxDensity::new::<gauss>(100.0), Axis::Z)
+        .expect("Failed to set Z threshold");
+    let threshold = mag_sensor
+        .get_mag_threshold(Axis::Z)
+        .expect("Failed to get Z threshold");
+    assert!((threshold - 10.0).abs() <= resolution);
+    let threshold = mag_sensor
+        .get_mag_threshold_quantity(Axis::Z)
+        .expect("Failed to get Z threshold");
+    assert!((threshold.get::<millitesla>() - 10.0).abs() <= resolution);
+
+    mag_sensor
+        .set_temperature_threshold_quantity(Some(ThermodynamicTemperature::new::<degree_celsius>(
+            87.0,
+        )))
+        .expect("Failed to set temperature threshold");
+    assert_eq!(
+        mag_sensor
+            .get_temperature_threshold()
+            .expect("Failed to get temperature threshold"),
+        Some(87.0)
+    );
+    let threshold = mag_sensor
+        .get_temperature_threshold_quantity()
+        .expect("Failed to get temperature threshold")
+        .expect("The temperature threshold is disabled");
+    assert!((threshold.get::<degree_celsius>() - 87.0).abs() < 0.01);
+
+    // 500 uT is 0.5 mT, with a resolution of the range / 2048
+    let resolution = Range::High.get_range(SENSOR_PART) / 2048.0;
+    mag_sensor
+        .set_magnetic_offset_quantity(
+            MagneticFluxDensity::new::<microtesla>(500.0),
+            MagneticChannelOffset::FirstAxis,
+        )
+        .expect("Failed to set magnetic offset");
+    let offset = mag_sensor
+        .get_magnetic_offset_quantity(MagneticChannelOffset::FirstAxis)
+        .expect("Failed to get magnetic offset");
+    assert!((offset.get::<millitesla>() - 0.5).abs() <= resolution / 2.0);
+
+    let data = mag_sensor
+        .get_all_data_quantity()
+        .expect("Failed to get all data");
+    assert!((-40.0..=170.0).contains(&data.temp.get::<degree_celsius>()));
+    let z = mag_sensor
+        .get_mag_data_quantity(Axis::Z)
+        .expect("Failed to get Z data");
+    assert!(z.get::<millitesla>().abs() <= Range::High.get_range(SENSOR_PART));
+    let temperature = mag_sensor
+        .get_temperature_quantity()
+        .expect("Failed to get temperature");
+    assert!((-40.0..=170.0).contains(&temperature.get::<degree_celsius>()));
+    let angle = mag_sensor
+        .get_angle_quantity()
+        .expect("Failed to get angle");
+    assert!((0.0..360.0).contains(&angle.get::<degree>()));
+
+    mag_sensor
+        .set_magnetic_offset(0.0, MagneticChannelOffset::FirstAxis)
+        .expect("Failed to reset magnetic offset");
+    mag_sensor
+        .set_mag_threshold(0.0, Axis::Z)
+        .expect("Failed to reset Z threshold");
+    reset_register!(DeviceConfig1Register, mag_sensor);
+    reset_register!(DeviceConfig2Register, mag_sensor);
+    reset_register!(SensorConfig1Register, mag_sensor);
+    reset_register!(SensorConfig2Register, mag_sensor);
+    reset_register!(TConfigRegister, mag_sensor);
+}
+
+pub fn generic_test_health<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let mut mag_sensor = TMag5273::new(i2c, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    assert!(!mag_sensor.get_health_check());
+
+    // The first report may show faults latched while the supply ramped up, which it then clears
+    mag_sensor.health().expect("Failed to get health report");
+    let report = mag_sensor.health().expect("Failed to get health report");
+    assert!(report.is_healthy());
+    assert_eq!(report.check(), Ok(()));
+    let device_status: DeviceStatusRegister = mag_sensor
+        .get_config_register()
+        .expect("Failed to get Device Status Register");
+    assert_eq!(device_status.raw_value() & 0x0F, 0x00);
+
+    mag_sensor.set_health_check(true);
+    assert!(mag_sensor.get_health_check());
+    mag_sensor.get_all_data().expect("Failed to get all data");
+    mag_sensor
+        .get_temperature()
+        .expect("Failed to get temperature");
+}
+
+pub fn generic_test_recovery<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let mut mag_sensor = TMag5273::new(i2c, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    assert!(!mag_sensor.get_auto_recovery());
+
+    // The POR flag latched when the device powered up may still be set, restoring the same configuration
+    mag_sensor.recover().expect("Failed to recover");
+    assert!(!mag_sensor.recover().expect("Failed to recover"));
+    mag_sensor.get_all_data().expect("Failed to get all data");
+
+    mag_sensor.set_auto_recovery(true);
+    assert!(mag_sensor.get_auto_recovery());
+    mag_sensor.get_all_data().expect("Failed to get all data");
+    mag_sensor.set_cache_mode(CacheMode::Cached);
+    mag_sensor.get_all_data().expect("Failed to get all data");
+}
+
+pub fn generic_test_sampled_data<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let mut mag_sensor = TMag5273::new(i2c, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+
+    let first = mag_sensor
+        .get_raw_sampled_data()
+        .expect("Failed to get sample");
+    assert_eq!(first.freshness, SampleFreshness::Fresh);
+    assert!(first.set_count < 8);
+
+    // The freshness follows the step of the set count, whatever the conversion rate
+    let second = mag_sensor.get_sampled_data().expect("Failed to get sample");
+    let expected = match second.set_count.wrapping_sub(first.set_count) % 8 {
+        0 => SampleFreshness::Stale,
+        1 => SampleFreshness::Fresh,
+        step => SampleFreshness::Missed(step - 1),
+    };
+    assert_eq!(second.freshness, expected);
+    assert!((-40.0..=170.0).contains(&second.data.temp));
+}
+
+pub fn generic_test_frame_data<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let mut mag_sensor = TMag5273::new(i2c, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+
+    // All the channels are enabled by default, without the angle calculation
+    let frame = mag_sensor
+        .get_frame_data()
+        .expect("Failed to get frame data");
+    assert!((-40.0..=170.0).contains(&frame.temp.expect("Temperature not decoded")));
+    assert!(frame.x.is_some() && frame.y.is_some() && frame.z.is_some());
+    assert_eq!((frame.angle, frame.magnitude), (None, None));
+
+    let sensor_config_2: SensorConfig2Register = mag_sensor
+        .get_config_register::<SensorConfig2Register>()
+        .expect("Failed to get Sensor Config Register 2")
+        .with_angle(Angle::XY);
+    mag_sensor
+        .set_config_register(sensor_config_2)
+        .expect("Failed to set Sensor Config Register 2");
+    let frame = mag_sensor
+        .get_raw_frame_data()
+        .expect("Failed to get frame data");
+    assert!(frame.angle.expect("Angle not decoded") < 5760);
+    assert!(frame.magnitude.is_some());
+    reset_register!(SensorConfig2Register, mag_sensor);
+}
+
+pub fn generic_test_enabled_data<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let mut mag_sensor = TMag5273::new(i2c, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    let data = mag_sensor
+        .get_enabled_data()
+        .expect("Failed to get enabled data");
+    assert!(data.x.is_some() && data.y.is_some() && data.z.is_some());
+    assert!((-40.0..=170.0).contains(&data.temp.expect("Temperature not read")));
+
+    let sensor_config_1: SensorConfig1Register = mag_sensor
+        .get_config_register::<SensorConfig1Register>()
+        .expect("Failed to get Sensor Config Register 1")
+        .with_mag_channel(MagneticChannel::XY);
+    mag_sensor
+        .set_config_register(sensor_config_1)
+        .expect("Failed to set Sensor Config Register 1");
+    let data = mag_sensor
+        .get_raw_enabled_data()
+        .expect("Failed to get enabled data");
+    assert!(data.x.is_some() && data.y.is_some() && data.temp.is_some());
+    assert_eq!(data.z, None);
+    reset_register!(SensorConfig1Register, mag_sensor);
+}
+
+pub fn generic_test_temperature_compensation<I2C>(i2c: I2C)
+where
+    I2C: embedded_hal::i2c::I2c,
+{
+    let mut mag_sensor = TMag5273::new(i2c, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+
+    let compensation =
+        TemperatureCompensation::new(MagnetTemperatureModel::Linear(-0.035), 25.0).unwrap();
+    let data = mag_sensor
+        .get_compensated_data(&compensation)
+        .expect("Failed to get compensated data");
+    assert!((-40.0..=170.0).contains(&data.temp));
+
+    let device_config_1: DeviceConfig1Register = mag_sensor
+        .get_config_register::<DeviceConfig1Register>()
+        .expect("Failed to get Device Config Register 1")
+        .with_mag_tempo(MagnetTemperatureCoefficient::Zero2Compensation);
+    mag_sensor
+        .set_config_register(device_config_1)
+        .expect("Failed to set Device Config Register 1");
+    assert_eq!(
+        mag_sensor.get_compensated_data(&compensation),
+        Err(TMag5273Error::CompensationConflict)
+    );
+    reset_register!(DeviceConfig1Register, mag_sensor);
+}
@@ -1,3 +1,5 @@
 #![no_std]
+pub mod asynchronous;
 pub mod generic_cold_start_tests;
 pub mod generic_setting_registers_tests;
+pub mod simulator;
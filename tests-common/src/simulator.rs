@@ -0,0 +1,749 @@
+//! Register accurate software model of the TMAG5273, implementing the embedded-hal I2c trait.
+//!
+//! The simulator allows the generic tests to be run with `cargo test` on a machine without any sensor
+//! attached. It models:
+//!
+//! - The reset value of every register. The I2C Address Register holds the address above its update bit, so it
+//!   reads back the 8-bit form of the factory address, 0x44 for the 0x22 of the B1. The 0x6A listed in the
+//!   datasheet is the 8-bit form of the 0x35 of the A1.
+//! - Auto incrementing reads and writes, with read-only, write-1-to-clear and reserved bits.
+//! - The manufacturer ID and the device ID of the chosen DeviceVersion.
+//! - The standard 3-byte read with and without CRC, and the 1-byte read modes.
+//! - Conversions of an injected magnetic field and temperature, including the angle and magnitude results.
+//! - The INT pin, through [SimulatedIntPin], with latched and pulsed interrupts.
+//! - Injected faults, latched into the Device Status Register and summarised by the diagnostic flag.
+//! - Several devices sharing a bus, through [SimulatedBus], each powered through a [SimulatedEnablePin].
+//!
+//! Conversions complete instantly. In continuous measure and wake-up and sleep mode a conversion is made at the
+//! start of every transaction, in stand-by mode a conversion is made when the trigger bit is set in the register
+//! address, and in any mode a conversion can be forced with `convert`. In wake-up and sleep mode the device goes
+//! to stand-by mode once the interrupt condition is met, as the real device does.
+//! Each read of the INT pin also counts as a step of time, in which the next conversion of the continuous
+//! modes completes, so a host waiting on the pin sees the interrupts the device would send.
+use core::{cell::RefCell, convert::Infallible};
+use embedded_hal::digital::{self, InputPin, OutputPin};
+use embedded_hal::i2c::{
+    ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation, SevenBitAddress,
+};
+use tmag5273::{registers::*, types::DeviceVersion, MANUFACTURER_ID_VALUE};
+
+/// Number of registers in the register map, from DeviceConfig1 (0x00) to DeviceStatus (0x1C).
+const REGISTER_COUNT: usize = 0x1D;
+/// Bit 7 of the register address starts a conversion in stand-by mode.
+const TRIGGER_BIT: u8 = 0x80;
+/// The error flags of the Device Status Register.
+const DEVICE_STATUS_ERRORS: u8 = 0x0F;
+/// The diagnostic flag of the Conversion Status Register.
+const DIAGNOSTIC_ERROR: u8 = 0x02;
+/// Level of the bus when the host reads past the end of a 1-byte read packet.
+const IDLE_BYTE: u8 = 0xFF;
+
+/// Simulated TMAG5273 on an I2C bus.
+///
+/// ## Example
+///
+/// ```Rust
+/// let mut simulator = SimulatedTMag5273::new(DeviceVersion::TMAG5273B1);
+/// simulator.set_magnetic_field(10.0, -5.0, 2.5);
+/// simulator.set_temperature(30.0);
+/// let mut mag_sensor = TMag5273::new(simulator, DeviceVersion::TMAG5273B1)?.init_default()?;
+/// let data = mag_sensor.get_all_data()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct SimulatedTMag5273 {
+    version: DeviceVersion,
+    /// The address the device currently acknowledges.
+    address: SevenBitAddress,
+    registers: [u8; REGISTER_COUNT],
+    /// Register address the next read or write starts from.
+    pointer: u8,
+    /// Magnetic field in mT, as reported by the driver for the X, Y and Z axis.
+    field: [f32; 3],
+    /// Temperature in degrees C.
+    temperature: f32,
+    /// Number of consecutive conversions which crossed a threshold.
+    threshold_crossings: u8,
+    /// Whether the INT pin is asserted.
+    int_asserted: bool,
+    /// Error flags of the Device Status Register which are present, latched again on every conversion.
+    faults: u8,
+}
+
+impl SimulatedTMag5273 {
+    /// Creates a simulated device that has just powered up, with no field applied at 25 degrees C.
+    pub fn new(version: DeviceVersion) -> Self {
+        let mut simulator = Self {
+            version,
+            address: version.get_default_address(),
+            registers: [0x00; REGISTER_COUNT],
+            pointer: 0x00,
+            field: [0.0; 3],
+            temperature: 25.0,
+            threshold_crossings: 0,
+            int_asserted: false,
+            faults: 0x00,
+        };
+        simulator.power_on_reset();
+        simulator
+    }
+
+    /// Returns every register to its reset value, as if the device was power cycled.
+    /// The injected field and temperature are kept.
+    pub fn power_on_reset(&mut self) {
+        let default_address = self.version.get_default_address();
+        let device_id = match self.version.get_device_id() {
+            DeviceId::TMAG5273X1 => 0x01,
+            DeviceId::TMAG5273X2 => 0x02,
+        };
+        let [manufacturer_id_lsb, manufacturer_id_msb] = MANUFACTURER_ID_VALUE.to_le_bytes();
+
+        self.registers = [0x00; REGISTER_COUNT];
+        self.set(TMAG5273Register::I2CAddress, default_address << 1);
+        self.set(TMAG5273Register::DeviceID, device_id);
+        self.set(TMAG5273Register::ManufacturerIdLsb, manufacturer_id_lsb);
+        self.set(TMAG5273Register::ManufacturerIdMsb, manufacturer_id_msb);
+        self.set(
+            TMAG5273Register::ConvStatus,
+            ConversionStatusRegister::default().raw_value(),
+        );
+        self.set(
+            TMAG5273Register::DeviceStatus,
+            DeviceStatusRegister::default().raw_value(),
+        );
+        self.address = default_address;
+        self.pointer = 0x00;
+        self.threshold_crossings = 0;
+        self.int_asserted = false;
+    }
+
+    /// Returns the address the device currently acknowledges.
+    pub fn address(&self) -> SevenBitAddress {
+        self.address
+    }
+
+    /// Returns the current value of a register, without going over the bus.
+    pub fn register(&self, register: TMAG5273Register) -> u8 {
+        self.registers[usize::from(u8::from(register))]
+    }
+
+    /// Sets the magnetic field in mT seen by the X, Y and Z axis. The values are taken with the same sign
+    /// as the driver reports them, so reading them back through the driver returns the injected values.
+    pub fn set_magnetic_field(&mut self, x: f32, y: f32, z: f32) {
+        self.field = [x, y, z];
+    }
+
+    /// Sets the temperature of the device in degrees C.
+    pub fn set_temperature(&mut self, temperature: f32) {
+        self.temperature = temperature;
+    }
+
+    /// Sets the faults present on the device, as the error flags of the Device Status Register. The faults are
+    /// latched straight away and again on every conversion and every time the flags are cleared, until they are
+    /// removed by setting them to 0.
+    pub fn set_faults(&mut self, faults: u8) {
+        self.faults = faults & DEVICE_STATUS_ERRORS;
+        self.latch_faults();
+    }
+
+    /// Makes a conversion of the enabled channels, updating the result registers, the angle and magnitude
+    /// results and the Conversion Status Register. If the interrupt condition is met the INT pin is asserted
+    /// and, in wake-up and sleep mode, the device goes to stand-by mode.
+    pub fn convert(&mut self) {
+        let (x_enabled, y_enabled, z_enabled) = self.enabled_axes();
+        let config2 = SensorConfig2Register::new_with_raw_value(
+            self.register(TMAG5273Register::SensorConfig2),
+        );
+        let xy_range = config2.xy_range().get_range(self.version);
+        let z_range = config2.z_range().get_range(self.version);
+
+        if self.temperature_enabled() {
+            self.set_result(
+                TMAG5273Register::TMsbResult,
+                temperature_code(self.temperature),
+            );
+        }
+        // The driver reports the X axis with the opposite sign to the result register
+        let results = [
+            (
+                x_enabled,
+                TMAG5273Register::XMsbResult,
+                -self.field[0],
+                xy_range,
+            ),
+            (
+                y_enabled,
+                TMAG5273Register::YMSBResult,
+                self.field[1],
+                xy_range,
+            ),
+            (
+                z_enabled,
+                TMAG5273Register::ZMSBResult,
+                self.field[2],
+                z_range,
+            ),
+        ];
+        for (enabled, register, field, range) in results {
+            if enabled {
+                self.set_result(register, field_code(field, range) as u16);
+            }
+        }
+        self.calculate_angle(config2);
+
+        // Increment the rolling set count and flag the data as ready
+        let status = self.register(TMAG5273Register::ConvStatus);
+        let set_count = (status >> 5).wrapping_add(1) & 0b111;
+        self.set(
+            TMAG5273Register::ConvStatus,
+            (set_count << 5) | (status & 0b0001_1110) | 0b1,
+        );
+        self.latch_faults();
+
+        let threshold_met = self.threshold_met(config2, xy_range, z_range);
+        let int_config = InterruptConfigRegister::new_with_raw_value(
+            self.register(TMAG5273Register::IntConfig1),
+        );
+        let interrupt = (int_config.threshold_interrupt_enabled() && threshold_met)
+            || int_config.conversion_complete_interrupt_enabled();
+        if interrupt
+            && !int_config.int_pin_disabled()
+            && matches!(
+                int_config.interrupt_mode(),
+                Ok(InterruptMode::INTInterrupt | InterruptMode::INTInterruptNotI2cBusy)
+            )
+        {
+            self.int_asserted = true;
+        }
+        let device_config_2 = DeviceConfig2Register::new_with_raw_value(
+            self.register(TMAG5273Register::DeviceConfig2),
+        );
+        if interrupt
+            && matches!(
+                device_config_2.operating_mode(),
+                OperatingMode::WakeUpAndSleep
+            )
+        {
+            self.set(
+                TMAG5273Register::DeviceConfig2,
+                device_config_2
+                    .with_operating_mode(OperatingMode::StandBy)
+                    .raw_value(),
+            );
+        }
+    }
+
+    /// Checks the latest results against the thresholds of the enabled axes, returning true once the threshold
+    /// has been crossed for the number of consecutive conversions set by THR_CNT.
+    fn threshold_met(
+        &mut self,
+        config2: SensorConfig2Register,
+        xy_range: f32,
+        z_range: f32,
+    ) -> bool {
+        let (x_enabled, y_enabled, z_enabled) = self.enabled_axes();
+        let device_config_2 = DeviceConfig2Register::new_with_raw_value(
+            self.register(TMAG5273Register::DeviceConfig2),
+        );
+        let seven_lsb = matches!(device_config_2.threshold(), Ok(Threshold::SevenLsb));
+        let below = config2.threshold_direction() == MagThresholdDirection::Below;
+        let axes = [
+            (
+                x_enabled,
+                TMAG5273Register::XThrConfig,
+                TMAG5273Register::XMsbResult,
+                xy_range,
+            ),
+            (
+                y_enabled,
+                TMAG5273Register::YThrConfig,
+                TMAG5273Register::YMSBResult,
+                xy_range,
+            ),
+            (
+                z_enabled,
+                TMAG5273Register::ZThrConfig,
+                TMAG5273Register::ZMSBResult,
+                z_range,
+            ),
+        ];
+        let crossed =
+            axes.into_iter()
+                .any(|(enabled, threshold_register, result_register, range)| {
+                    let threshold = self.register(threshold_register);
+                    // A threshold of 0 means no threshold comparison
+                    if !enabled || threshold == 0 {
+                        return false;
+                    }
+                    let value = self.result(result_register) as f32 * range / 32768.0;
+                    let (value, threshold) = match seven_lsb {
+                        // Two opposite thresholds of equal magnitude
+                        true => (value.abs(), (threshold & 0x7F) as f32 * range / 128.0),
+                        false => (value, i8::from_le_bytes([threshold]) as f32 * range / 128.0),
+                    };
+                    match below {
+                        true => value < threshold,
+                        false => value > threshold,
+                    }
+                });
+
+        self.threshold_crossings = match crossed {
+            true => self.threshold_crossings.saturating_add(1),
+            false => 0,
+        };
+        let required_crossings = match config2.threshold_crossing_count() {
+            ThresholdCrossingCount::One => 1,
+            ThresholdCrossingCount::Four => 4,
+        };
+        self.threshold_crossings >= required_crossings
+    }
+
+    /// Angle and magnitude calculation between the two channels selected by ANGLE_EN. The offset and gain
+    /// corrections are applied to the channels before the calculation.
+    fn calculate_angle(&mut self, config2: SensorConfig2Register) {
+        let (first, second) = match config2.angle() {
+            Angle::Disabled => return,
+            Angle::XY => (TMAG5273Register::XMsbResult, TMAG5273Register::YMSBResult),
+            Angle::YZ => (TMAG5273Register::YMSBResult, TMAG5273Register::ZMSBResult),
+            Angle::XZ => (TMAG5273Register::XMsbResult, TMAG5273Register::ZMSBResult),
+        };
+        // Each offset LSB is the range / 2048, while each result LSB is the range / 32768
+        let offset = |register| i8::from_le_bytes([self.register(register)]) as f32 * 16.0;
+        let mut first = self.result(first) as f32 + offset(TMAG5273Register::MagOffsetConfig1);
+        let mut second = self.result(second) as f32 + offset(TMAG5273Register::MagOffsetConfig2);
+
+        // A gain of 0 is interpreted by the device as 1
+        let gain = match self.register(TMAG5273Register::MagGainConfig) {
+            0 => 1.0,
+            gain => gain as f32 / 256.0,
+        };
+        match config2.gain_channel() {
+            MagGainChannel::First => first *= gain,
+            MagGainChannel::Second => second *= gain,
+        }
+
+        let mut angle = libm::atan2f(second, first).to_degrees();
+        if angle < 0.0 {
+            angle += 360.0;
+        }
+        let angle_code = (libm::roundf(angle * 16.0) as u16) % (360 * 16);
+        self.set_result(TMAG5273Register::AngleResultMSB, angle_code);
+        let magnitude = libm::roundf(libm::hypotf(first, second) / 256.0).min(255.0);
+        self.set(TMAG5273Register::MagnitudeResult, magnitude as u8);
+    }
+
+    /// Latches the present faults into the Device Status Register, and raises the diagnostic flag of the
+    /// Conversion Status Register while any error flag is set.
+    fn latch_faults(&mut self) {
+        let device_status = self.register(TMAG5273Register::DeviceStatus) | self.faults;
+        self.set(TMAG5273Register::DeviceStatus, device_status);
+        let conversion_status = match device_status & DEVICE_STATUS_ERRORS != 0 {
+            true => self.register(TMAG5273Register::ConvStatus) | DIAGNOSTIC_ERROR,
+            false => self.register(TMAG5273Register::ConvStatus) & !DIAGNOSTIC_ERROR,
+        };
+        self.set(TMAG5273Register::ConvStatus, conversion_status);
+    }
+
+    fn set(&mut self, register: TMAG5273Register, value: u8) {
+        self.registers[usize::from(u8::from(register))] = value;
+    }
+
+    /// Sets a 16 bit result starting at its MSB register.
+    fn set_result(&mut self, msb_register: TMAG5273Register, value: u16) {
+        let [msb, lsb] = value.to_be_bytes();
+        let address = usize::from(u8::from(msb_register));
+        self.registers[address] = msb;
+        self.registers[address + 1] = lsb;
+    }
+
+    /// Gets a 16 bit result starting at its MSB register.
+    fn result(&self, msb_register: TMAG5273Register) -> i16 {
+        let address = usize::from(u8::from(msb_register));
+        i16::from_be_bytes([self.registers[address], self.registers[address + 1]])
+    }
+
+    fn device_config_1(&self) -> DeviceConfig1Register {
+        DeviceConfig1Register::new_with_raw_value(self.register(TMAG5273Register::DeviceConfig1))
+    }
+
+    fn temperature_enabled(&self) -> bool {
+        TConfigRegister::new_with_raw_value(self.register(TMAG5273Register::TConfig))
+            .temperature_channel_enabled()
+    }
+
+    /// Returns which of the X, Y and Z axis are converted, based off MAG_CH_EN.
+    fn enabled_axes(&self) -> (bool, bool, bool) {
+        let mag_channel = self.register(TMAG5273Register::SensorConfig1) >> 4;
+        match mag_channel {
+            // X, Y and Z map to bits 0, 1 and 2 up to XYZ
+            0x0..=0x7 => (
+                mag_channel & 0b001 != 0,
+                mag_channel & 0b010 != 0,
+                mag_channel & 0b100 != 0,
+            ),
+            // XYX and YXY
+            0x8 | 0x9 => (true, true, false),
+            // YZY
+            0xA => (false, true, true),
+            // XZX
+            0xB => (true, false, true),
+            // Reserved
+            _ => (false, false, false),
+        }
+    }
+
+    /// Handles a write from the host. The first byte is the register address, with the trigger bit in the MSB,
+    /// and any following bytes are written to successive registers.
+    fn write(&mut self, data: &[u8]) {
+        let Some((command, values)) = data.split_first() else {
+            return;
+        };
+        self.pointer = command & !TRIGGER_BIT;
+
+        // The conversion is started once the register address has been decoded
+        let config2 = DeviceConfig2Register::new_with_raw_value(
+            self.register(TMAG5273Register::DeviceConfig2),
+        );
+        if command & TRIGGER_BIT != 0
+            && matches!(config2.operating_mode(), OperatingMode::StandBy)
+            && matches!(config2.trigger_mode(), TriggerMode::Default)
+        {
+            self.convert();
+        }
+
+        for value in values {
+            self.write_register(self.pointer, *value);
+            self.pointer = self.pointer.wrapping_add(1);
+        }
+    }
+
+    /// Writes a single register, respecting read-only, write-1-to-clear and reserved bits.
+    fn write_register(&mut self, address: u8, value: u8) {
+        let Ok(register) = TMAG5273Register::try_from(address) else {
+            return;
+        };
+        match register {
+            TMAG5273Register::DeviceConfig1
+            | TMAG5273Register::DeviceConfig2
+            | TMAG5273Register::SensorConfig1
+            | TMAG5273Register::XThrConfig
+            | TMAG5273Register::YThrConfig
+            | TMAG5273Register::ZThrConfig
+            | TMAG5273Register::TConfig
+            | TMAG5273Register::MagGainConfig
+            | TMAG5273Register::MagOffsetConfig1
+            | TMAG5273Register::MagOffsetConfig2 => self.set(register, value),
+            // Bit 7 is reserved
+            TMAG5273Register::SensorConfig2 => self.set(register, value & 0x7F),
+            // Bit 1 is reserved
+            TMAG5273Register::IntConfig1 => self.set(register, value & !0x02),
+            TMAG5273Register::I2CAddress => {
+                self.set(register, value);
+                // The device goes back to its factory address when the update is disabled
+                self.address = match value & 0b1 != 0 {
+                    true => value >> 1,
+                    false => self.version.get_default_address(),
+                };
+            }
+            // POR is cleared by writing '1'
+            TMAG5273Register::ConvStatus => {
+                self.set(register, self.register(register) & !(value & 0x10))
+            }
+            // The error flags are cleared by writing '1'
+            TMAG5273Register::DeviceStatus => {
+                self.set(
+                    register,
+                    self.register(register) & !(value & DEVICE_STATUS_ERRORS),
+                );
+                self.latch_faults();
+            }
+            // Everything else is read-only
+            _ => (),
+        }
+    }
+
+    /// Returns the level of the INT pin, true when it is low, then moves time on by one step.
+    /// A pulse ends before the next read of the pin, while a latched interrupt is held until the device is
+    /// addressed. When the pin is not asserted the next conversion of the continuous modes completes.
+    pub fn poll_int_pin(&mut self) -> bool {
+        let int_config = InterruptConfigRegister::new_with_raw_value(
+            self.register(TMAG5273Register::IntConfig1),
+        );
+        let low = self.int_asserted;
+        if matches!(int_config.int_pin_mode(), INTPinMode::Pulsed) {
+            self.int_asserted = false;
+        }
+        if !low && self.converting() {
+            self.convert();
+        }
+        low
+    }
+
+    /// Whether the operating mode makes conversions without a trigger.
+    fn converting(&self) -> bool {
+        let config2 = DeviceConfig2Register::new_with_raw_value(
+            self.register(TMAG5273Register::DeviceConfig2),
+        );
+        matches!(
+            config2.operating_mode(),
+            OperatingMode::ContinuousMeasure | OperatingMode::WakeUpAndSleep
+        )
+    }
+
+    /// Standard 3-byte read, starting from the register address last written by the host.
+    /// When the CRC is enabled a CRC byte is sent after every 4 register bytes.
+    fn standard_read(&mut self, buffer: &mut [u8]) {
+        let crc_enabled = self.device_config_1().i2c_crc_enabled();
+        let mut frame = [0x00; 4];
+        let mut frame_len = 0;
+        for byte in buffer.iter_mut() {
+            if crc_enabled && frame_len == frame.len() {
+                *byte = crc8(0xFF, &frame);
+                frame_len = 0;
+                continue;
+            }
+            let value = self
+                .registers
+                .get(usize::from(self.pointer))
+                .copied()
+                .unwrap_or(0x00);
+            self.pointer = self.pointer.wrapping_add(1);
+            if crc_enabled {
+                frame[frame_len] = value;
+                frame_len += 1;
+            }
+            *byte = value;
+        }
+    }
+
+    /// 1-byte read, sending the enabled channels in the T, X, Y, Z order followed by the Conversion Status Register
+    /// and, when enabled, a CRC calculated over the command byte and the data.
+    fn fast_read(&mut self, buffer: &mut [u8], sixteen_bit: bool) {
+        let (x_enabled, y_enabled, z_enabled) = self.enabled_axes();
+        let channels = [
+            (self.temperature_enabled(), TMAG5273Register::TMsbResult),
+            (x_enabled, TMAG5273Register::XMsbResult),
+            (y_enabled, TMAG5273Register::YMSBResult),
+            (z_enabled, TMAG5273Register::ZMSBResult),
+        ];
+
+        let mut packet = [0x00; 10];
+        let mut len = 0;
+        for (enabled, register) in channels {
+            if !enabled {
+                continue;
+            }
+            let [msb, lsb] = self.result(register).to_be_bytes();
+            packet[len] = msb;
+            len += 1;
+            // Only the MSB is sent in the 8 bit mode
+            if sixteen_bit {
+                packet[len] = lsb;
+                len += 1;
+            }
+        }
+        packet[len] = self.register(TMAG5273Register::ConvStatus);
+        len += 1;
+        if self.device_config_1().i2c_crc_enabled() {
+            let command = crc8(0xFF, &[(self.address << 1) | 1]);
+            packet[len] = crc8(command, &packet[..len]);
+            len += 1;
+        }
+
+        for (byte, value) in buffer
+            .iter_mut()
+            .zip(packet[..len].iter().chain(core::iter::repeat(&IDLE_BYTE)))
+        {
+            *byte = *value;
+        }
+    }
+}
+
+impl ErrorType for SimulatedTMag5273 {
+    type Error = ErrorKind;
+}
+
+impl I2c<SevenBitAddress> for SimulatedTMag5273 {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        if address != self.address {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        if self.converting() {
+            self.convert();
+        }
+        // Addressing the device clears a latched interrupt, and a pulse has ended by the time a transaction is made
+        self.int_asserted = false;
+
+        // A read is only a 1-byte read if no register address was written first
+        let mut addressed = false;
+        for operation in operations {
+            match operation {
+                Operation::Write(data) => {
+                    addressed |= !data.is_empty();
+                    self.write(data);
+                }
+                Operation::Read(buffer) => {
+                    match (addressed, self.device_config_1().i2c_read_mode()) {
+                        (false, I2cReadMode::OneByte16Bit) => self.fast_read(buffer, true),
+                        (false, I2cReadMode::TwoByte8Bit) => self.fast_read(buffer, false),
+                        _ => self.standard_read(buffer),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// CRC sent by the device, generated by the polynomial x^8 + x^2 + x + 1 starting from `crc`.
+fn crc8(crc: u8, data: &[u8]) -> u8 {
+    data.iter().fold(crc, |crc, byte| {
+        (0..8).fold(crc ^ byte, |crc, _| match crc & 0x80 != 0 {
+            true => (crc << 1) ^ 0x07,
+            false => crc << 1,
+        })
+    })
+}
+
+/// Converts a temperature in degrees C to the 16 bit temperature result.
+fn temperature_code(temperature: f32) -> u16 {
+    const TSENSE_T0: f32 = 25.0; // Reference temperature for TADC_T0
+    const TADC_T0: f32 = 17508.0; // Temp result in decimal value (from 16-bit format)
+    const TADC_RES: f32 = 60.1; // Temperature sensing resolution (in 16-bit format)
+    libm::roundf(TADC_T0 + (temperature - TSENSE_T0) * TADC_RES).clamp(0.0, u16::MAX as f32) as u16
+}
+
+/// Converts a field in mT to the 16 bit 2's complement result for the range, saturating at the range.
+fn field_code(field: f32, range: f32) -> i16 {
+    libm::roundf(field / range * 32768.0).clamp(i16::MIN as f32, i16::MAX as f32) as i16
+}
+
+/// INT pin of a simulated device which is shared with the driver through a RefCell.
+///
+/// ## Example
+///
+/// ```Rust
+/// let simulator = RefCell::new(SimulatedTMag5273::new(DeviceVersion::TMAG5273B1));
+/// let mut mag_sensor = TMag5273::new(RefCellDevice::new(&simulator), DeviceVersion::TMAG5273B1)?;
+/// let pin = SimulatedIntPin::new(&simulator);
+/// ```
+pub struct SimulatedIntPin<'a> {
+    device: &'a RefCell<SimulatedTMag5273>,
+}
+
+impl<'a> SimulatedIntPin<'a> {
+    pub fn new(device: &'a RefCell<SimulatedTMag5273>) -> Self {
+        Self { device }
+    }
+}
+
+impl digital::ErrorType for SimulatedIntPin<'_> {
+    type Error = Infallible;
+}
+
+impl InputPin for SimulatedIntPin<'_> {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.is_low()?)
+    }
+
+    /// INT is active low
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.device.borrow_mut().poll_int_pin())
+    }
+}
+
+/// Bus of `N` simulated devices of the same variant, each of which can be powered on and off.
+/// A powered off device does not answer, and goes through a power on reset when it is powered on again.
+/// When several powered devices share an address they all take part in the transaction, and a read returns the
+/// data of the last one.
+#[derive(Debug, Clone)]
+pub struct SimulatedBus<const N: usize> {
+    devices: [SimulatedTMag5273; N],
+    powered: [bool; N],
+}
+
+impl<const N: usize> SimulatedBus<N> {
+    /// Creates a bus of devices which are all powered off.
+    pub fn new(version: DeviceVersion) -> Self {
+        Self {
+            devices: core::array::from_fn(|_| SimulatedTMag5273::new(version)),
+            powered: [false; N],
+        }
+    }
+
+    /// Powers a device on or off.
+    pub fn set_powered(&mut self, index: usize, powered: bool) {
+        if powered && !self.powered[index] {
+            self.devices[index].power_on_reset();
+        }
+        self.powered[index] = powered;
+    }
+
+    /// Returns a device on the bus.
+    pub fn device(&self, index: usize) -> &SimulatedTMag5273 {
+        &self.devices[index]
+    }
+
+    /// Returns a device on the bus, for example to inject a field or reset it.
+    pub fn device_mut(&mut self, index: usize) -> &mut SimulatedTMag5273 {
+        &mut self.devices[index]
+    }
+}
+
+impl<const N: usize> ErrorType for SimulatedBus<N> {
+    type Error = ErrorKind;
+}
+
+impl<const N: usize> I2c<SevenBitAddress> for SimulatedBus<N> {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let mut acknowledged = false;
+        for (device, _) in self
+            .devices
+            .iter_mut()
+            .zip(self.powered)
+            .filter(|(device, powered)| *powered && device.address() == address)
+        {
+            device.transaction(address, operations)?;
+            acknowledged = true;
+        }
+        match acknowledged {
+            true => Ok(()),
+            false => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+        }
+    }
+}
+
+/// Enable pin which powers one device of a [SimulatedBus], active high.
+pub struct SimulatedEnablePin<'a, const N: usize> {
+    bus: &'a RefCell<SimulatedBus<N>>,
+    index: usize,
+}
+
+impl<'a, const N: usize> SimulatedEnablePin<'a, N> {
+    pub fn new(bus: &'a RefCell<SimulatedBus<N>>, index: usize) -> Self {
+        Self { bus, index }
+    }
+}
+
+impl<const N: usize> digital::ErrorType for SimulatedEnablePin<'_, N> {
+    type Error = Infallible;
+}
+
+impl<const N: usize> OutputPin for SimulatedEnablePin<'_, N> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.bus.borrow_mut().set_powered(self.index, false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.bus.borrow_mut().set_powered(self.index, true);
+        Ok(())
+    }
+}
@@ -48,4 +48,72 @@ mod cold_start_tests {
     fn test_set_reset_t_config_register(i2c: EspI2c) {
         generic_test_set_reset_t_config_register(i2c);
     }
+    #[test]
+    fn test_crc_enabled_reads(i2c: EspI2c) {
+        generic_test_crc_enabled_reads(i2c);
+    }
+    #[test]
+    fn test_fast_read_modes(i2c: EspI2c) {
+        generic_test_fast_read_modes(i2c);
+    }
+    #[test]
+    fn test_set_reset_temperature_threshold(i2c: EspI2c) {
+        generic_test_set_reset_temperature_threshold(i2c);
+    }
+    #[test]
+    fn test_set_reset_magnetic_offset(i2c: EspI2c) {
+        generic_test_set_reset_magnetic_offset(i2c);
+    }
+    #[test]
+    fn test_measure_once(i2c: EspI2c) {
+        generic_test_measure_once(i2c, esp_hal::delay::Delay::new());
+    }
+    #[test]
+    fn test_wake_on_threshold(i2c: EspI2c) {
+        generic_test_wake_on_threshold(i2c);
+    }
+    #[test]
+    fn test_change_address(i2c: EspI2c) {
+        generic_test_change_address(i2c);
+    }
+    #[test]
+    fn test_scan_factory_addresses(i2c: EspI2c) {
+        generic_test_scan_factory_addresses(i2c);
+    }
+    #[test]
+    fn test_cache_modes(i2c: EspI2c) {
+        generic_test_cache_modes(i2c);
+    }
+    #[test]
+    fn test_raw_data(i2c: EspI2c) {
+        generic_test_raw_data(i2c, esp_hal::delay::Delay::new());
+    }
+    #[test]
+    fn test_units(i2c: EspI2c) {
+        generic_test_units(i2c);
+    }
+    #[test]
+    fn test_health(i2c: EspI2c) {
+        generic_test_health(i2c);
+    }
+    #[test]
+    fn test_recovery(i2c: EspI2c) {
+        generic_test_recovery(i2c);
+    }
+    #[test]
+    fn test_sampled_data(i2c: EspI2c) {
+        generic_test_sampled_data(i2c);
+    }
+    #[test]
+    fn test_frame_data(i2c: EspI2c) {
+        generic_test_frame_data(i2c);
+    }
+    #[test]
+    fn test_enabled_data(i2c: EspI2c) {
+        generic_test_enabled_data(i2c);
+    }
+    #[test]
+    fn test_temperature_compensation(i2c: EspI2c) {
+        generic_test_temperature_compensation(i2c);
+    }
 }
@@ -49,4 +49,64 @@ mod setting_register_tests {
     fn test_set_reset_t_config_register(i2c: PicoI2c) {
         generic_test_set_reset_t_config_register(i2c); // Pass the i2c variable to the inner test function
     }
+    #[test]
+    fn test_crc_enabled_reads(i2c: PicoI2c) {
+        generic_test_crc_enabled_reads(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_fast_read_modes(i2c: PicoI2c) {
+        generic_test_fast_read_modes(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_set_reset_temperature_threshold(i2c: PicoI2c) {
+        generic_test_set_reset_temperature_threshold(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_set_reset_magnetic_offset(i2c: PicoI2c) {
+        generic_test_set_reset_magnetic_offset(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_wake_on_threshold(i2c: PicoI2c) {
+        generic_test_wake_on_threshold(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_change_address(i2c: PicoI2c) {
+        generic_test_change_address(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_scan_factory_addresses(i2c: PicoI2c) {
+        generic_test_scan_factory_addresses(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_cache_modes(i2c: PicoI2c) {
+        generic_test_cache_modes(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_units(i2c: PicoI2c) {
+        generic_test_units(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_health(i2c: PicoI2c) {
+        generic_test_health(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_recovery(i2c: PicoI2c) {
+        generic_test_recovery(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_sampled_data(i2c: PicoI2c) {
+        generic_test_sampled_data(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_frame_data(i2c: PicoI2c) {
+        generic_test_frame_data(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_enabled_data(i2c: PicoI2c) {
+        generic_test_enabled_data(i2c); // Pass the i2c variable to the inner test function
+    }
+    #[test]
+    fn test_temperature_compensation(i2c: PicoI2c) {
+        generic_test_temperature_compensation(i2c); // Pass the i2c variable to the inner test function
+    }
 }
@@ -0,0 +1,17 @@
+use tests_common as common;
+
+#[cfg(test)]
+pub mod simulator {
+    use crate::common::simulator::SimulatedTMag5273;
+    use tmag5273::types::DeviceVersion;
+
+    pub mod async_tests;
+    pub mod cold_start_tests;
+    pub mod setting_registers_tests;
+    pub mod simulator_tests;
+
+    /// Each test gets its own freshly powered up sensor, so the tests can be run concurrently
+    fn setup_i2c() -> SimulatedTMag5273 {
+        SimulatedTMag5273::new(DeviceVersion::TMAG5273B1)
+    }
+}
@@ -0,0 +1,188 @@
+//! Tests for the async driver, run against the simulated sensor through the async adapters of tests-common.
+use core::cell::RefCell;
+
+use super::setup_i2c;
+use crate::common::asynchronous::{block_on, AsyncRefCellDevice, SimulatedDelay};
+use crate::common::simulator::{SimulatedIntPin, SimulatedTMag5273};
+use tmag5273::{asynchronous::TMag5273, registers::*, types::*, InterruptConfig};
+
+type AsyncSensor<'a> = TMag5273<AsyncRefCellDevice<'a, SimulatedTMag5273>>;
+
+const SENSOR_PART: DeviceVersion = DeviceVersion::TMAG5273B1;
+
+/// Creates an async sensor on the simulator, measuring continuously with all the channels enabled
+async fn init_sensor(simulator: &RefCell<SimulatedTMag5273>) -> AsyncSensor<'_> {
+    TMag5273::new(AsyncRefCellDevice::new(simulator), SENSOR_PART)
+        .await
+        .expect("Failed to create mag sensor instance")
+        .init_default()
+        .await
+        .expect("Failed to initialise mag sensor")
+}
+
+#[test]
+fn test_async_crc_enabled_reads() {
+    let simulator = RefCell::new(setup_i2c());
+    simulator.borrow_mut().set_magnetic_field(-10.0, 10.0, 2.5);
+    simulator.borrow_mut().set_temperature(30.0);
+    block_on(async {
+        let mut mag_sensor = init_sensor(&simulator).await;
+        let device_config_1: DeviceConfig1Register = mag_sensor
+            .get_config_register()
+            .await
+            .expect("Failed to get Device Config Register 1");
+        mag_sensor
+            .set_config_register(device_config_1.with_i2c_crc_enabled(true))
+            .await
+            .expect("Failed to enable I2C CRC");
+
+        // Reads within a single CRC frame
+        assert_eq!(
+            mag_sensor.get_manufacturer_id().await,
+            Ok(tmag5273::MANUFACTURER_ID_VALUE)
+        );
+
+        // Reads spread over two CRC frames
+        let data = mag_sensor
+            .get_all_data()
+            .await
+            .expect("Failed to get all data with CRC enabled");
+        assert!((data.temp - 30.0).abs() < 0.1);
+        assert!((data.x + 10.0).abs() < 0.01);
+        assert!((data.y - 10.0).abs() < 0.01);
+        assert!((data.z - 2.5).abs() < 0.01);
+
+        // The last frame is moved back so it does not run past the Device Status Register
+        let frame = mag_sensor
+            .get_frame_data()
+            .await
+            .expect("Failed to get frame data with CRC enabled");
+        assert!((frame.z.unwrap() - 2.5).abs() < 0.01);
+        assert!(frame.conversion_status.conversion_ready());
+    });
+}
+
+#[test]
+fn test_async_cache_modes_after_power_on_reset() {
+    let simulator = RefCell::new(setup_i2c());
+    simulator.borrow_mut().set_magnetic_field(0.0, 0.0, 2.5);
+    block_on(async {
+        let mut mag_sensor = init_sensor(&simulator).await;
+
+        // The POR flag read alongside the data refreshes the cache, which shows the channels are disabled
+        mag_sensor.set_cache_mode(CacheMode::Cached);
+        simulator.borrow_mut().power_on_reset();
+        assert_eq!(
+            mag_sensor.get_all_data().await,
+            Err(TMag5273Error::ChannelDisabled)
+        );
+        let conversion_status = ConversionStatusRegister::new_with_raw_value(
+            simulator.borrow().register(TMAG5273Register::ConvStatus),
+        );
+        assert!(!conversion_status.power_on_reset());
+    });
+}
+
+#[test]
+fn test_async_power_on_reset_recovery() {
+    let simulator = RefCell::new(setup_i2c());
+    simulator.borrow_mut().set_magnetic_field(0.0, 0.0, 2.5);
+    let configuration = || -> [u8; 12] {
+        core::array::from_fn(|address| {
+            simulator
+                .borrow()
+                .register(TMAG5273Register::try_from(address as u8).unwrap())
+        })
+    };
+    block_on(async {
+        let mut mag_sensor = init_sensor(&simulator).await;
+        mag_sensor
+            .set_magnetic_gain(200)
+            .await
+            .expect("Failed to set gain");
+        mag_sensor
+            .change_address(0x40)
+            .await
+            .expect("Failed to change address");
+        // Clears the POR flag latched when the device powered up
+        mag_sensor.recover().await.expect("Failed to recover");
+        assert!(!mag_sensor.recover().await.expect("Failed to recover"));
+        let applied = configuration();
+
+        // The device is moved back to its address and the configuration restored
+        simulator.borrow_mut().power_on_reset();
+        assert!(mag_sensor.recover().await.expect("Failed to recover"));
+        assert_eq!(simulator.borrow().address(), 0x40);
+        assert_eq!(configuration(), applied);
+
+        // In auto recovery the device no longer answering at its address is noticed by the next read
+        mag_sensor.set_auto_recovery(true);
+        simulator.borrow_mut().power_on_reset();
+        assert_eq!(
+            mag_sensor.get_all_data().await,
+            Err(TMag5273Error::DeviceReset)
+        );
+        assert_eq!(configuration(), applied);
+        let data = mag_sensor
+            .get_all_data()
+            .await
+            .expect("Failed to get all data");
+        assert!((data.z - 2.5).abs() < 0.01);
+    });
+}
+
+#[test]
+fn test_async_interrupt_acquisition() {
+    for int_pin_mode in [INTPinMode::Latched, INTPinMode::Pulsed] {
+        let simulator = RefCell::new(setup_i2c());
+        block_on(async {
+            let mut mag_sensor = init_sensor(&simulator).await;
+
+            // The conversion complete interrupt has to be sent through the INT pin
+            assert!(matches!(
+                mag_sensor
+                    .interrupt_acquisition(SimulatedIntPin::new(&simulator))
+                    .await,
+                Err(TMag5273Error::WrongMode)
+            ));
+            mag_sensor
+                .set_interrupts(InterruptConfig {
+                    interrupt_mode: InterruptMode::INTInterrupt,
+                    int_pin_mode,
+                    conversion_complete_interrupt_enabled: true,
+                    ..Default::default()
+                })
+                .await
+                .expect("Failed to set interrupts");
+
+            let mut acquisition = mag_sensor
+                .interrupt_acquisition(SimulatedIntPin::new(&simulator))
+                .await
+                .expect("Failed to take the INT pin");
+            for z in [2.5, 5.0, 7.5] {
+                simulator.borrow_mut().set_magnetic_field(0.0, 0.0, z);
+                let data = acquisition
+                    .wait_for_data(&mut SimulatedDelay, 10_000)
+                    .await
+                    .expect("Failed to wait for data");
+                assert!((data.z - z).abs() < 0.01);
+            }
+
+            // No interrupt is sent in stand-by mode, so the wait gives up
+            let device_config_2: DeviceConfig2Register = acquisition
+                .sensor()
+                .get_config_register()
+                .await
+                .expect("Failed to get Device Config Register 2");
+            acquisition
+                .sensor()
+                .set_config_register(device_config_2.with_operating_mode(OperatingMode::StandBy))
+                .await
+                .expect("Failed to set Device Config Register 2");
+            assert_eq!(
+                acquisition.wait_for_data(&mut SimulatedDelay, 1_000).await,
+                Err(TMag5273Error::Timeout)
+            );
+        });
+    }
+}
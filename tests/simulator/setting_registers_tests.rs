@@ -0,0 +1,159 @@
+use super::setup_i2c;
+use crate::common::generic_setting_registers_tests::*;
+use utils::Delay;
+
+#[test]
+fn test_is_connected() {
+    let i2c = setup_i2c();
+    generic_test_is_connected(i2c);
+}
+
+#[test]
+fn test_create_tmag5273() {
+    let i2c = setup_i2c();
+    generic_test_create_tmag5273(i2c);
+}
+
+#[test]
+fn test_set_reset_device_config_1_register() {
+    let i2c = setup_i2c();
+    generic_test_set_reset_device_config_1_register(i2c);
+}
+
+#[test]
+fn test_set_reset_device_config_2_register() {
+    let i2c = setup_i2c();
+    generic_test_reset_device_config_2_register(i2c);
+}
+
+#[test]
+fn test_set_reset_i2c_address_register() {
+    let i2c = setup_i2c();
+    generic_test_set_reset_i2c_address_register(i2c);
+}
+
+#[test]
+fn test_set_reset_int_config_1_register() {
+    let i2c = setup_i2c();
+    generic_test_set_reset_int_config_1_register(i2c);
+}
+
+#[test]
+fn test_set_reset_sensor_config_1_register() {
+    let i2c = setup_i2c();
+    generic_test_set_reset_sensor_config_1_register(i2c);
+}
+
+#[test]
+fn test_set_reset_sensor_config_2_register() {
+    let i2c = setup_i2c();
+    generic_test_set_reset_sensor_config_2_register(i2c);
+}
+
+#[test]
+fn test_set_reset_t_config_register() {
+    let i2c = setup_i2c();
+    generic_test_set_reset_t_config_register(i2c);
+}
+
+#[test]
+fn test_crc_enabled_reads() {
+    let i2c = setup_i2c();
+    generic_test_crc_enabled_reads(i2c);
+}
+
+#[test]
+fn test_fast_read_modes() {
+    let i2c = setup_i2c();
+    generic_test_fast_read_modes(i2c);
+}
+
+#[test]
+fn test_set_reset_temperature_threshold() {
+    let i2c = setup_i2c();
+    generic_test_set_reset_temperature_threshold(i2c);
+}
+
+#[test]
+fn test_set_reset_magnetic_offset() {
+    let i2c = setup_i2c();
+    generic_test_set_reset_magnetic_offset(i2c);
+}
+
+#[test]
+fn test_measure_once() {
+    let i2c = setup_i2c();
+    generic_test_measure_once(i2c, Delay::new());
+}
+
+#[test]
+fn test_wake_on_threshold() {
+    let i2c = setup_i2c();
+    generic_test_wake_on_threshold(i2c);
+}
+
+#[test]
+fn test_change_address() {
+    let i2c = setup_i2c();
+    generic_test_change_address(i2c);
+}
+
+#[test]
+fn test_scan_factory_addresses() {
+    let i2c = setup_i2c();
+    generic_test_scan_factory_addresses(i2c);
+}
+
+#[test]
+fn test_cache_modes() {
+    let i2c = setup_i2c();
+    generic_test_cache_modes(i2c);
+}
+
+#[test]
+fn test_raw_data() {
+    let i2c = setup_i2c();
+    generic_test_raw_data(i2c, Delay::new());
+}
+
+#[test]
+fn test_units() {
+    let i2c = setup_i2c();
+    generic_test_units(i2c);
+}
+
+#[test]
+fn test_health() {
+    let i2c = setup_i2c();
+    generic_test_health(i2c);
+}
+
+#[test]
+fn test_recovery() {
+    let i2c = setup_i2c();
+    generic_test_recovery(i2c);
+}
+
+#[test]
+fn test_sampled_data() {
+    let i2c = setup_i2c();
+    generic_test_sampled_data(i2c);
+}
+
+#[test]
+fn test_frame_data() {
+    let i2c = setup_i2c();
+    generic_test_frame_data(i2c);
+}
+
+#[test]
+fn test_enabled_data() {
+    let i2c = setup_i2c();
+    generic_test_enabled_data(i2c);
+}
+
+#[test]
+fn test_temperature_compensation() {
+    let i2c = setup_i2c();
+    generic_test_temperature_compensation(i2c);
+}
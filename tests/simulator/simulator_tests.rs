@@ -0,0 +1,1279 @@
+//! Tests for the behaviour of the simulated sensor which the generic tests can not check on hardware,
+//! such as reading back injected field and temperature values.
+use core::cell::{Cell, RefCell};
+
+use super::setup_i2c;
+use crate::common::simulator::{
+    SimulatedBus, SimulatedEnablePin, SimulatedIntPin, SimulatedTMag5273,
+};
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, Operation, SevenBitAddress};
+use embedded_hal_bus::i2c::RefCellDevice;
+use tmag5273::{
+    bring_up_sensors, registers::*, scan_bus, types::*, AngleCalibrator, Calibration, Calibrator,
+    DeviceConfig, EncoderConfig, EncoderDirection, InterruptConfig, MagnetTemperatureModel,
+    RotaryEncoder, TMag5273, TemperatureCompensation, WakeOnThresholdConfig, WakeReason,
+};
+use utils::Delay;
+
+const SENSOR_PART: DeviceVersion = DeviceVersion::TMAG5273B1;
+
+#[test]
+fn test_injected_field_and_temperature() {
+    let mut simulator = setup_i2c();
+    simulator.set_magnetic_field(10.0, -5.0, 2.5);
+    simulator.set_temperature(30.0);
+
+    let mut mag_sensor = TMag5273::new(simulator, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    let data = mag_sensor.get_all_data().expect("Failed to get all data");
+
+    // One LSB of the 80 mT range is 0.0024 mT
+    assert!((data.x - 10.0).abs() < 0.01);
+    assert!((data.y + 5.0).abs() < 0.01);
+    assert!((data.z - 2.5).abs() < 0.01);
+    assert!((data.temp - 30.0).abs() < 0.02);
+}
+
+#[test]
+fn test_angle_calculation() {
+    let mut simulator = setup_i2c();
+    // The driver reports X inverted, so this is 45 degrees between the X and Y result registers
+    simulator.set_magnetic_field(-10.0, 10.0, 0.0);
+
+    let mut mag_sensor = TMag5273::new(simulator, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    let sensor_config_2: SensorConfig2Register = mag_sensor
+        .get_config_register::<SensorConfig2Register>()
+        .expect("Failed to get Sensor Config Register 2")
+        .with_angle(Angle::XY);
+    mag_sensor
+        .set_config_register(sensor_config_2)
+        .expect("Failed to set Sensor Config Register 2");
+
+    let angle = mag_sensor.get_angle().expect("Failed to get angle");
+    assert!((angle - 45.0).abs() <= 1.0 / 16.0);
+    assert!(mag_sensor.get_magnitude().expect("Failed to get magnitude") > 0);
+}
+
+#[test]
+fn test_power_on_reset() {
+    let mut simulator = setup_i2c();
+    // The 8-bit form of the 0x22 factory address, as the address sits above the update bit
+    assert_eq!(simulator.register(TMAG5273Register::I2CAddress), 0x44);
+
+    TMag5273::new(&mut simulator, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    assert_ne!(simulator.register(TMAG5273Register::DeviceConfig2), 0x00);
+
+    simulator.power_on_reset();
+    assert_eq!(simulator.register(TMAG5273Register::DeviceConfig2), 0x00);
+    let conversion_status = ConversionStatusRegister::new_with_raw_value(
+        simulator.register(TMAG5273Register::ConvStatus),
+    );
+    assert!(conversion_status.power_on_reset());
+    assert_eq!(simulator.address(), SENSOR_PART.get_default_address());
+}
+
+#[test]
+fn test_wake_on_threshold() {
+    let simulator = RefCell::new(setup_i2c());
+    let mut mag_sensor = TMag5273::new(RefCellDevice::new(&simulator), SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    let int_config = InterruptConfigRegister::default().with_int_pin_disabled(true);
+    mag_sensor
+        .set_config_register(int_config)
+        .expect("Failed to set Interrupt Config Register");
+    let mut wake = mag_sensor
+        .wake_on_threshold(WakeOnThresholdConfig {
+            z_threshold: Some(20.0),
+            interrupt_mode: InterruptMode::INTInterrupt,
+            ..Default::default()
+        })
+        .expect("Failed to start wake-up and sleep mode");
+
+    // Settings outside the wake-up config are kept
+    let int_config: InterruptConfigRegister = wake
+        .sensor()
+        .get_config_register()
+        .expect("Failed to get Interrupt Config Register");
+    assert!(int_config.int_pin_disabled());
+    assert!(int_config.threshold_interrupt_enabled());
+
+    // Below the threshold the device keeps sleeping
+    simulator.borrow_mut().set_magnetic_field(0.0, 0.0, 10.0);
+    assert_eq!(wake.wake_reason().expect("Failed to get wake reason"), None);
+
+    // Each transaction wakes the device for a conversion, which crosses the threshold
+    simulator.borrow_mut().set_magnetic_field(0.0, 0.0, 30.0);
+    wake.sensor().is_connected();
+    assert_eq!(
+        wake.wake_reason().expect("Failed to get wake reason"),
+        Some(WakeReason::Threshold)
+    );
+
+    // The device can be rearmed once the field is back below the threshold
+    simulator.borrow_mut().set_magnetic_field(0.0, 0.0, 10.0);
+    wake.rearm().expect("Failed to rearm");
+    assert_eq!(wake.wake_reason().expect("Failed to get wake reason"), None);
+    wake.stop().expect("Failed to stop wake-up and sleep mode");
+    let device_config_2: DeviceConfig2Register = mag_sensor
+        .get_config_register()
+        .expect("Failed to get Device Config Register 2");
+    assert!(matches!(
+        device_config_2.operating_mode(),
+        OperatingMode::StandBy
+    ));
+}
+
+#[test]
+fn test_wake_on_conversion() {
+    let simulator = RefCell::new(setup_i2c());
+    simulator.borrow_mut().set_magnetic_field(0.0, 0.0, 10.0);
+    let mut mag_sensor = TMag5273::new(RefCellDevice::new(&simulator), SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    let mut wake = mag_sensor
+        .wake_on_threshold(WakeOnThresholdConfig {
+            z_threshold: Some(20.0),
+            conversion_complete_interrupt_enabled: true,
+            ..Default::default()
+        })
+        .expect("Failed to start wake-up and sleep mode");
+
+    assert_eq!(
+        wake.wake_reason().expect("Failed to get wake reason"),
+        Some(WakeReason::Conversion)
+    );
+}
+
+#[test]
+fn test_interrupt_acquisition() {
+    for int_pin_mode in [INTPinMode::Latched, INTPinMode::Pulsed] {
+        let simulator = RefCell::new(setup_i2c());
+        simulator.borrow_mut().set_magnetic_field(0.0, 0.0, 2.5);
+        let mut mag_sensor = TMag5273::new(RefCellDevice::new(&simulator), SENSOR_PART)
+            .unwrap()
+            .init_default()
+            .expect("Failed to initialise mag sensor");
+
+        // The conversion complete interrupt has to be sent through the INT pin
+        assert!(matches!(
+            mag_sensor.interrupt_acquisition(SimulatedIntPin::new(&simulator)),
+            Err(TMag5273Error::WrongMode)
+        ));
+        mag_sensor
+            .set_interrupts(InterruptConfig {
+                interrupt_mode: InterruptMode::INTInterrupt,
+                int_pin_mode,
+                conversion_complete_interrupt_enabled: true,
+                ..Default::default()
+            })
+            .expect("Failed to set interrupts");
+
+        let mut acquisition = mag_sensor
+            .interrupt_acquisition(SimulatedIntPin::new(&simulator))
+            .expect("Failed to take the INT pin");
+        // A pulse can be missed while the pin is polled, so the interrupt is latched
+        let interrupt_config = acquisition
+            .sensor()
+            .get_interrupt_config()
+            .expect("Failed to get interrupt config");
+        assert!(matches!(interrupt_config.int_pin_mode, INTPinMode::Latched));
+        for z in [2.5, 5.0, 7.5] {
+            simulator.borrow_mut().set_magnetic_field(0.0, 0.0, z);
+            let data = acquisition
+                .wait_for_data(&mut Delay::new(), 10_000)
+                .expect("Failed to wait for data");
+            assert!((data.z - z).abs() < 0.01);
+        }
+
+        // No interrupt is sent in stand-by mode, so the wait gives up
+        let device_config_2: DeviceConfig2Register = acquisition
+            .sensor()
+            .get_config_register()
+            .expect("Failed to get Device Config Register 2");
+        acquisition
+            .sensor()
+            .set_config_register(device_config_2.with_operating_mode(OperatingMode::StandBy))
+            .expect("Failed to set Device Config Register 2");
+        assert_eq!(
+            acquisition.wait_for_data(&mut Delay::new(), 1_000),
+            Err(TMag5273Error::Timeout)
+        );
+    }
+}
+
+#[test]
+fn test_typestate_transitions() {
+    use tmag5273::typestate::{self, XY, XYZ, Z};
+
+    let simulator = RefCell::new(setup_i2c());
+    simulator.borrow_mut().set_magnetic_field(-10.0, 10.0, 2.5);
+    let mag_sensor = typestate::TMag5273::new(RefCellDevice::new(&simulator), SENSOR_PART)
+        .expect("Failed to create mag sensor instance");
+    let device_config_2 = DeviceConfig2Register::new_with_raw_value(
+        simulator.borrow().register(TMAG5273Register::DeviceConfig2),
+    );
+    assert!(matches!(
+        device_config_2.operating_mode(),
+        OperatingMode::StandBy
+    ));
+
+    let mut mag_sensor = mag_sensor
+        .into_continuous::<Z>()
+        .expect("Failed to enter continuous mode");
+    assert!((mag_sensor.get_z().expect("Failed to get Z") - 2.5).abs() < 0.01);
+
+    let mut mag_sensor = mag_sensor
+        .into_continuous::<XYZ>()
+        .expect("Failed to enter continuous mode");
+    let data = mag_sensor.get_all_data().expect("Failed to get all data");
+    assert!((data.x + 10.0).abs() < 0.01);
+    assert!((data.temp - 25.0).abs() < 0.02);
+
+    let mut mag_sensor = mag_sensor
+        .into_angle_mode::<XY>()
+        .expect("Failed to enter angle mode");
+    let angle = mag_sensor.get_angle().expect("Failed to get angle");
+    assert!((angle - 45.0).abs() <= 1.0 / 16.0);
+
+    let mut mag_sensor = mag_sensor
+        .into_standby()
+        .expect("Failed to enter stand-by mode")
+        .release();
+    let sensor_config_2: SensorConfig2Register = mag_sensor
+        .get_config_register()
+        .expect("Failed to get Sensor Config Register 2");
+    assert_eq!(sensor_config_2.angle(), Angle::XY);
+}
+
+#[test]
+fn test_bring_up_sensors() {
+    let bus = RefCell::new(SimulatedBus::<4>::new(SENSOR_PART));
+    let mut enable_pins = core::array::from_fn(|index| SimulatedEnablePin::new(&bus, index));
+    // The last sensor can keep the factory address
+    let addresses = [0x30, 0x31, 0x32, SENSOR_PART.get_default_address()];
+
+    // Every sensor needs its own address
+    assert!(matches!(
+        bring_up_sensors(
+            || RefCellDevice::new(&bus),
+            &mut enable_pins,
+            [0x30, 0x31, 0x30, 0x32],
+            SENSOR_PART,
+            &mut Delay::new(),
+        ),
+        Err(TMag5273Error::AddressInUse)
+    ));
+
+    // Re-running the bring-up gives the same result
+    for _ in 0..2 {
+        let sensors = bring_up_sensors(
+            || RefCellDevice::new(&bus),
+            &mut enable_pins,
+            addresses,
+            SENSOR_PART,
+            &mut Delay::new(),
+        )
+        .expect("Failed to bring up sensors");
+        for (index, (mut sensor, address)) in sensors.into_iter().zip(addresses).enumerate() {
+            assert!(sensor.is_connected());
+            assert_eq!(bus.borrow().device(index).address(), address);
+        }
+    }
+
+    // A brown-out sends every sensor back to the factory address, so they all answer at once
+    for index in 0..4 {
+        bus.borrow_mut().device_mut(index).power_on_reset();
+    }
+    let [_, sensor_1, _, _] = bring_up_sensors(
+        || RefCellDevice::new(&bus),
+        &mut enable_pins,
+        addresses,
+        SENSOR_PART,
+        &mut Delay::new(),
+    )
+    .expect("Failed to bring up sensors after a brown-out");
+
+    // Each handle talks to its own sensor
+    bus.borrow_mut()
+        .device_mut(1)
+        .set_magnetic_field(0.0, 0.0, 5.0);
+    let mut sensor_1 = sensor_1
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    let z = sensor_1.get_mag_data(Axis::Z).expect("Failed to get Z");
+    assert!((z - 5.0).abs() < 0.01);
+    assert!(matches!(
+        DeviceConfig2Register::new_with_raw_value(
+            bus.borrow()
+                .device(0)
+                .register(TMAG5273Register::DeviceConfig2)
+        )
+        .operating_mode(),
+        OperatingMode::StandBy
+    ));
+}
+
+#[test]
+fn test_scan_bus_after_bring_up() {
+    let bus = RefCell::new(SimulatedBus::<2>::new(SENSOR_PART));
+    let mut enable_pins = core::array::from_fn(|index| SimulatedEnablePin::new(&bus, index));
+    let addresses = [0x30, SENSOR_PART.get_default_address()];
+    bring_up_sensors(
+        || RefCellDevice::new(&bus),
+        &mut enable_pins,
+        addresses,
+        SENSOR_PART,
+        &mut Delay::new(),
+    )
+    .expect("Failed to bring up sensors");
+
+    let mut i2c = RefCellDevice::new(&bus);
+    let mut devices = scan_bus(&mut i2c).map(|device| device.expect("Failed to scan the bus"));
+
+    // The scan goes up through the addresses, so the sensor at its factory address is found first
+    let factory = devices
+        .next()
+        .expect("Failed to find the sensor at its factory address");
+    assert_eq!(factory.address, SENSOR_PART.get_default_address());
+    assert_eq!(factory.version_candidates().next(), Some(SENSOR_PART));
+
+    // The moved sensor could be any variant with its device ID
+    let moved = devices.next().expect("Failed to find the moved sensor");
+    assert_eq!(moved.address, 0x30);
+    assert!(moved.manufacturer_id_matches);
+    assert_eq!(moved.device_id, Some(DeviceId::TMAG5273X1));
+    let mut candidates = moved.version_candidates();
+    for version in [
+        DeviceVersion::TMAG5273A1,
+        DeviceVersion::TMAG5273B1,
+        DeviceVersion::TMAG5273C1,
+        DeviceVersion::TMAG5273D1,
+    ] {
+        assert_eq!(candidates.next(), Some(version));
+    }
+    assert_eq!(candidates.next(), None);
+    assert!(devices.next().is_none());
+}
+
+#[test]
+fn test_change_address_onto_another_device() {
+    let bus = RefCell::new(SimulatedBus::<2>::new(SENSOR_PART));
+    let mut enable_pins = core::array::from_fn(|index| SimulatedEnablePin::new(&bus, index));
+    let [_, mut sensor] = bring_up_sensors(
+        || RefCellDevice::new(&bus),
+        &mut enable_pins,
+        [0x30, SENSOR_PART.get_default_address()],
+        SENSOR_PART,
+        &mut Delay::new(),
+    )
+    .expect("Failed to bring up sensors");
+
+    // Nothing is written when the address is taken, so both devices still answer on their own
+    assert_eq!(
+        sensor.change_address(0x30),
+        Err(TMag5273Error::AddressInUse)
+    );
+    assert_eq!(
+        bus.borrow().device(1).address(),
+        SENSOR_PART.get_default_address()
+    );
+    assert!(sensor.is_connected());
+
+    // Keeping the current address is not a conflict
+    sensor
+        .change_address(SENSOR_PART.get_default_address())
+        .expect("Failed to keep the address");
+    sensor
+        .change_address(0x31)
+        .expect("Failed to change to a free address");
+    assert_eq!(bus.borrow().device(1).address(), 0x31);
+    assert!(sensor.is_connected());
+}
+
+#[test]
+fn test_cache_modes_after_power_on_reset() {
+    let simulator = RefCell::new(setup_i2c());
+    simulator.borrow_mut().set_magnetic_field(0.0, 0.0, 2.5);
+    let mut mag_sensor = TMag5273::new(RefCellDevice::new(&simulator), SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+
+    // The cached configuration is trusted without a check, so the reset goes unnoticed
+    mag_sensor.set_cache_mode(CacheMode::Unchecked);
+    simulator.borrow_mut().power_on_reset();
+    mag_sensor.get_all_data().expect("Failed to get all data");
+
+    // The POR flag read alongside the data refreshes the cache, which shows the channels are disabled
+    mag_sensor.set_cache_mode(CacheMode::Cached);
+    assert_eq!(
+        mag_sensor.get_all_data(),
+        Err(TMag5273Error::ChannelDisabled)
+    );
+    let conversion_status = ConversionStatusRegister::new_with_raw_value(
+        simulator.borrow().register(TMAG5273Register::ConvStatus),
+    );
+    assert!(!conversion_status.power_on_reset());
+
+    // Once the device is configured again the data is read from the cache
+    let mut mag_sensor = mag_sensor
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    let data = mag_sensor.get_all_data().expect("Failed to get all data");
+    assert!((data.z - 2.5).abs() < 0.01);
+}
+
+#[test]
+fn test_hard_and_soft_iron_calibration() {
+    // A 30 mT field swept over a sphere, scaled and sheared (soft iron) and then offset (hard iron)
+    let distorted_field = |theta: f32, phi: f32| {
+        let (x, y, z) = (
+            theta.sin() * phi.cos(),
+            theta.sin() * phi.sin(),
+            theta.cos(),
+        );
+        (
+            30.0 * (1.5 * x + 0.2 * y) + 12.0,
+            30.0 * (0.2 * x + 0.8 * y) - 5.0,
+            30.0 * (0.1 * x + 1.1 * z) + 3.0,
+        )
+    };
+    let simulator = RefCell::new(setup_i2c());
+    let mut mag_sensor = TMag5273::new(RefCellDevice::new(&simulator), SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+
+    let mut calibrator = Calibrator::<64>::new();
+    assert_eq!(calibrator.fit(), Err(TMag5273Error::CalibrationFailed));
+    for sample in 0..64 {
+        let theta = ((sample / 8) as f32 + 0.5) * core::f32::consts::PI / 8.0;
+        let phi = (sample % 8) as f32 * core::f32::consts::PI / 4.0;
+        let (x, y, z) = distorted_field(theta, phi);
+        simulator.borrow_mut().set_magnetic_field(x, y, z);
+        calibrator
+            .add_sample(mag_sensor.get_all_data().expect("Failed to get all data"))
+            .expect("Failed to add sample");
+    }
+    assert!(calibrator.is_full());
+    assert_eq!(
+        calibrator.add_sample(mag_sensor.get_all_data().expect("Failed to get all data")),
+        Err(TMag5273Error::OutOfRange)
+    );
+
+    let calibration = calibrator.fit().expect("Failed to fit calibration");
+    for (offset, expected) in calibration.offset.iter().zip([12.0, -5.0, 3.0]) {
+        assert!((offset - expected).abs() < 0.1);
+    }
+
+    // Directions which were not sampled all end up on the same sphere
+    let magnitudes = (0..10).map(|step| {
+        let (x, y, z) = distorted_field(step as f32 * 0.7, step as f32 * 1.4);
+        simulator.borrow_mut().set_magnetic_field(x, y, z);
+        let data = mag_sensor
+            .get_calibrated_data(&calibration)
+            .expect("Failed to get calibrated data");
+        (data.x * data.x + data.y * data.y + data.z * data.z).sqrt()
+    });
+    let (min, max) = magnitudes.fold((f32::MAX, f32::MIN), |(min, max), magnitude| {
+        (min.min(magnitude), max.max(magnitude))
+    });
+    assert!(max - min < 0.5);
+
+    let mut bytes = calibration.to_bytes();
+    assert_eq!(Calibration::from_bytes(&bytes), Ok(calibration));
+    bytes[0] ^= 0x01;
+    assert_eq!(
+        Calibration::from_bytes(&bytes),
+        Err(TMag5273Error::CrcMismatch)
+    );
+}
+
+#[test]
+fn test_angle_calibration() {
+    // A magnet turning over the sensor, with unequal amplitudes and offset channels
+    let field = |turn: f32| (20.0 * turn.cos() + 3.0, 12.0 * turn.sin() - 2.0);
+    // The angle between the X and Y result registers, where X is inverted
+    let expected_angle = |turn: f32| {
+        let angle = turn.sin().atan2(-turn.cos()).to_degrees();
+        (angle + 360.0) % 360.0
+    };
+    let angle_error = |angle: f32, expected: f32| {
+        let error = (angle - expected).abs() % 360.0;
+        error.min(360.0 - error)
+    };
+
+    let simulator = RefCell::new(setup_i2c());
+    let mut mag_sensor = TMag5273::new(RefCellDevice::new(&simulator), SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    let sensor_config_2: SensorConfig2Register = mag_sensor
+        .get_config_register::<SensorConfig2Register>()
+        .expect("Failed to get Sensor Config Register 2")
+        .with_angle(Angle::XY);
+    mag_sensor
+        .set_config_register(sensor_config_2)
+        .expect("Failed to set Sensor Config Register 2");
+
+    let mut calibrator = AngleCalibrator::<90>::new(Angle::XY);
+    for step in 0..45 {
+        let (x, y) = field((step as f32 * 4.0).to_radians());
+        simulator.borrow_mut().set_magnetic_field(x, y, 0.0);
+        calibrator
+            .add_sample(
+                mag_sensor
+                    .get_raw_all_data()
+                    .expect("Failed to get raw data"),
+            )
+            .expect("Failed to add sample");
+    }
+    // Half a rotation is not enough
+    assert_eq!(calibrator.fit(), Err(TMag5273Error::CalibrationFailed));
+    for step in 45..90 {
+        let (x, y) = field((step as f32 * 4.0).to_radians());
+        simulator.borrow_mut().set_magnetic_field(x, y, 0.0);
+        calibrator
+            .add_sample(
+                mag_sensor
+                    .get_raw_all_data()
+                    .expect("Failed to get raw data"),
+            )
+            .expect("Failed to add sample");
+    }
+
+    let calibration = calibrator.fit().expect("Failed to fit angle calibration");
+    assert_eq!(calibration.gain_channel, MagGainChannel::First);
+    assert!(calibration.error_before > 10.0);
+    assert!(calibration.error_after < 0.5);
+
+    let turn = 1.0f32;
+    let (x, y) = field(turn);
+    simulator.borrow_mut().set_magnetic_field(x, y, 0.0);
+    let angle = mag_sensor.get_angle().expect("Failed to get angle");
+    assert!(angle_error(angle, expected_angle(turn)) > 5.0);
+
+    mag_sensor
+        .set_angle_calibration(&calibration)
+        .expect("Failed to set angle calibration");
+    assert_eq!(mag_sensor.get_magnetic_gain(), Ok(calibration.gain));
+    let angle = mag_sensor.get_angle().expect("Failed to get angle");
+    assert!(angle_error(angle, expected_angle(turn)) < 0.5);
+
+    // The calibration only fits the channels it was recorded from
+    mag_sensor
+        .set_config_register(sensor_config_2.with_angle(Angle::YZ))
+        .expect("Failed to set Sensor Config Register 2");
+    assert_eq!(
+        mag_sensor.set_angle_calibration(&calibration),
+        Err(TMag5273Error::WrongMode)
+    );
+}
+
+#[test]
+fn test_rotary_encoder() {
+    // The field of a magnet on a shaft at the angle in degrees, where X is inverted in the result registers
+    let shaft = |degrees: f32| {
+        let radians = degrees.to_radians();
+        (-20.0 * radians.cos(), 20.0 * radians.sin(), 0.0)
+    };
+    let simulator = RefCell::new(setup_i2c());
+    let mut mag_sensor = TMag5273::new(RefCellDevice::new(&simulator), SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    let sensor_config_2: SensorConfig2Register = mag_sensor
+        .get_config_register::<SensorConfig2Register>()
+        .expect("Failed to get Sensor Config Register 2")
+        .with_angle(Angle::XY);
+    mag_sensor
+        .set_config_register(sensor_config_2)
+        .expect("Failed to set Sensor Config Register 2");
+
+    let config = EncoderConfig {
+        zero: 90 * 16,
+        ..Default::default()
+    };
+    let mut encoder = RotaryEncoder::new(mag_sensor, config);
+
+    // Three turns forward in 30 degree steps, 10 ms apart
+    let mut timestamp_us = 0;
+    let mut state = encoder.state();
+    for step in 0..=36 {
+        let (x, y, z) = shaft(90.0 + step as f32 * 30.0);
+        simulator.borrow_mut().set_magnetic_field(x, y, z);
+        state = encoder
+            .update(timestamp_us)
+            .expect("Failed to update encoder");
+        timestamp_us += 10_000;
+    }
+    assert_eq!(state.turns, 3);
+    assert!((state.position - 1080 * 16).abs() <= 1);
+    assert!((state.velocity - 3000 * 16).abs() <= 100);
+
+    // Jitter around the zero position does not take a turn off
+    for degrees in [89.8, 90.2, 89.8, 90.2] {
+        let (x, y, z) = shaft(degrees);
+        simulator.borrow_mut().set_magnetic_field(x, y, z);
+        state = encoder
+            .update(timestamp_us)
+            .expect("Failed to update encoder");
+        timestamp_us += 10_000;
+    }
+    assert_eq!(state.turns, 3);
+
+    // Turning back past the zero position does
+    let (x, y, z) = shaft(60.0);
+    simulator.borrow_mut().set_magnetic_field(x, y, z);
+    state = encoder
+        .update(timestamp_us)
+        .expect("Failed to update encoder");
+    assert_eq!(state.turns, 2);
+    assert!(state.velocity < 0);
+
+    // The angle is read from the device, so the angle calculation must be enabled
+    encoder
+        .sensor()
+        .set_config_register(sensor_config_2.with_angle(Angle::Disabled))
+        .expect("Failed to set Sensor Config Register 2");
+    assert_eq!(
+        encoder.update(timestamp_us),
+        Err(TMag5273Error::ChannelDisabled)
+    );
+
+    let mut reversed = RotaryEncoder::new(
+        encoder.release(),
+        EncoderConfig {
+            direction: EncoderDirection::Reversed,
+            ..config
+        },
+    );
+    assert_eq!(reversed.update_with_angle(80 * 16, 0).angle, 10 * 16);
+    reversed.zero_here();
+    assert_eq!(reversed.update_with_angle(70 * 16, 1_000).position, 10 * 16);
+}
+
+#[test]
+fn test_health_faults() {
+    let simulator = RefCell::new(setup_i2c());
+    let mut mag_sensor = TMag5273::new(RefCellDevice::new(&simulator), SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    // The power-on-reset flag is reported, but is not a fault
+    let report = mag_sensor.health().expect("Failed to get health report");
+    assert!(report.power_on_reset);
+    assert!(report.is_healthy());
+
+    // VCC undervoltage and oscillator error, with the undervoltage reported first
+    simulator.borrow_mut().set_faults(0b1001);
+    let report = mag_sensor.health().expect("Failed to get health report");
+    assert!(report.vcc_under_voltage && report.oscillator_error && report.diagnostic_error);
+    assert!(!report.otp_crc_error && !report.int_pin_error);
+    assert_eq!(report.check(), Err(TMag5273Error::VccUnderVoltage));
+
+    // The flags are cleared, but latched again while the faults are present
+    mag_sensor.get_all_data().expect("Failed to get all data");
+    mag_sensor.set_health_check(true);
+    assert_eq!(
+        mag_sensor.get_all_data(),
+        Err(TMag5273Error::VccUnderVoltage)
+    );
+
+    // Once the faults are gone the latched flags are reported and cleared one last time
+    simulator.borrow_mut().set_faults(0b0000);
+    mag_sensor.set_health_check(false);
+    assert!(!mag_sensor
+        .health()
+        .expect("Failed to get health report")
+        .is_healthy());
+    mag_sensor.set_health_check(true);
+    mag_sensor.get_all_data().expect("Failed to get all data");
+    assert_eq!(
+        simulator.borrow().register(TMAG5273Register::DeviceStatus) & 0x0F,
+        0x00
+    );
+}
+
+#[test]
+fn test_power_on_reset_recovery() {
+    let simulator = RefCell::new(setup_i2c());
+    simulator.borrow_mut().set_magnetic_field(0.0, 0.0, 2.5);
+    let mut mag_sensor = TMag5273::new(RefCellDevice::new(&simulator), SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    mag_sensor
+        .set_mag_threshold(10.0, Axis::X)
+        .expect("Failed to set threshold");
+    mag_sensor
+        .set_magnetic_gain(200)
+        .expect("Failed to set gain");
+    mag_sensor
+        .change_address(0x40)
+        .expect("Failed to change address");
+    // Clears the POR flag latched when the device powered up
+    mag_sensor.recover().expect("Failed to recover");
+    assert!(!mag_sensor.recover().expect("Failed to recover"));
+
+    let configuration = || -> [u8; 12] {
+        core::array::from_fn(|address| {
+            simulator
+                .borrow()
+                .register(TMAG5273Register::try_from(address as u8).unwrap())
+        })
+    };
+    let applied = configuration();
+
+    // The device is moved back to its address and the configuration restored
+    simulator.borrow_mut().power_on_reset();
+    assert_eq!(
+        simulator.borrow().address(),
+        SENSOR_PART.get_default_address()
+    );
+    assert!(mag_sensor.recover().expect("Failed to recover"));
+    assert_eq!(simulator.borrow().address(), 0x40);
+    assert_eq!(configuration(), applied);
+    assert!(!mag_sensor.recover().expect("Failed to recover"));
+
+    // In auto recovery the device no longer answering at its address is noticed by the next read
+    mag_sensor.set_auto_recovery(true);
+    assert!(mag_sensor.get_auto_recovery());
+    simulator.borrow_mut().power_on_reset();
+    assert_eq!(mag_sensor.get_all_data(), Err(TMag5273Error::DeviceReset));
+    assert_eq!(configuration(), applied);
+    let data = mag_sensor.get_all_data().expect("Failed to get all data");
+    assert!((data.z - 2.5).abs() < 0.01);
+
+    // In the Cached mode it is noticed through the POR flag read alongside the data
+    mag_sensor
+        .change_address(SENSOR_PART.get_default_address())
+        .expect("Failed to change address");
+    mag_sensor.set_cache_mode(CacheMode::Cached);
+    simulator.borrow_mut().power_on_reset();
+    assert_eq!(mag_sensor.get_all_data(), Err(TMag5273Error::DeviceReset));
+    let data = mag_sensor.get_all_data().expect("Failed to get all data");
+    assert!((data.z - 2.5).abs() < 0.01);
+}
+
+#[test]
+fn test_measure_once_after_power_on_reset() {
+    let simulator = RefCell::new(setup_i2c());
+    simulator.borrow_mut().set_magnetic_field(0.0, 0.0, 2.5);
+    let mut mag_sensor = TMag5273::new(RefCellDevice::new(&simulator), SENSOR_PART).unwrap();
+    mag_sensor
+        .set_config_register(SensorConfig1Register::default().with_mag_channel(MagneticChannel::Z))
+        .expect("Failed to set Sensor Config Register 1");
+    mag_sensor.recover().expect("Failed to recover");
+    mag_sensor.set_cache_mode(CacheMode::Cached);
+    mag_sensor.set_auto_recovery(true);
+
+    // The data of a conversion made by the reset device is not returned
+    simulator.borrow_mut().power_on_reset();
+    assert_eq!(
+        mag_sensor.measure_once(&mut Delay::new(), 100_000),
+        Err(TMag5273Error::DeviceReset)
+    );
+    let data = mag_sensor
+        .measure_once(&mut Delay::new(), 100_000)
+        .expect("Failed to measure once");
+    assert!((data.z.expect("The Z channel is enabled") - 2.5).abs() < 0.01);
+    assert!(data.x.is_none());
+}
+
+#[test]
+fn test_sample_freshness() {
+    let simulator = RefCell::new(setup_i2c());
+    let mut mag_sensor = TMag5273::new(RefCellDevice::new(&simulator), SENSOR_PART).unwrap();
+    mag_sensor
+        .set_config_register(
+            SensorConfig1Register::default().with_mag_channel(MagneticChannel::XYZ),
+        )
+        .expect("Failed to set Sensor Config Register 1");
+    mag_sensor
+        .set_config_register(TConfigRegister::default().with_temperature_channel_enabled(true))
+        .expect("Failed to set T Config Register");
+
+    // In stand-by mode a conversion is only made when triggered
+    mag_sensor
+        .trigger_conversion()
+        .expect("Failed to trigger conversion");
+    let sample = mag_sensor
+        .get_raw_sampled_data()
+        .expect("Failed to get sample");
+    assert_eq!(sample.freshness, SampleFreshness::Fresh);
+    let set_count = sample.set_count;
+    let sample = mag_sensor
+        .get_raw_sampled_data()
+        .expect("Failed to get sample");
+    assert_eq!(sample.freshness, SampleFreshness::Stale);
+    assert_eq!(sample.set_count, set_count);
+
+    mag_sensor
+        .trigger_conversion()
+        .expect("Failed to trigger conversion");
+    let sample = mag_sensor.get_sampled_data().expect("Failed to get sample");
+    assert_eq!(sample.freshness, SampleFreshness::Fresh);
+
+    // The set count rolls over after 8 conversions
+    for _ in 0..6 {
+        mag_sensor
+            .trigger_conversion()
+            .expect("Failed to trigger conversion");
+    }
+    let sample = mag_sensor.get_sampled_data().expect("Failed to get sample");
+    assert_eq!(sample.freshness, SampleFreshness::Missed(5));
+    assert_eq!(sample.set_count, (set_count + 7) % 8);
+}
+
+#[test]
+fn test_frame_data() {
+    let mut simulator = setup_i2c();
+    // The driver reports X inverted, so this is 45 degrees between the X and Y result registers
+    simulator.set_magnetic_field(-10.0, 10.0, 2.5);
+    simulator.set_temperature(30.0);
+
+    let mut mag_sensor = TMag5273::new(simulator, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    let frame = mag_sensor
+        .get_frame_data()
+        .expect("Failed to get frame data");
+    assert!((frame.temp.unwrap() - 30.0).abs() < 0.1);
+    assert!((frame.x.unwrap() + 10.0).abs() < 0.01);
+    assert!((frame.y.unwrap() - 10.0).abs() < 0.01);
+    assert!((frame.z.unwrap() - 2.5).abs() < 0.01);
+    assert!(frame.conversion_status.conversion_ready());
+    assert_eq!((frame.angle, frame.magnitude), (None, None));
+
+    // Only the channels of the angle calculation are decoded
+    let sensor_config_1: SensorConfig1Register = mag_sensor
+        .get_config_register::<SensorConfig1Register>()
+        .expect("Failed to get Sensor Config Register 1")
+        .with_mag_channel(MagneticChannel::XY);
+    mag_sensor
+        .set_config_register(sensor_config_1)
+        .expect("Failed to set Sensor Config Register 1");
+    let sensor_config_2: SensorConfig2Register = mag_sensor
+        .get_config_register::<SensorConfig2Register>()
+        .expect("Failed to get Sensor Config Register 2")
+        .with_angle(Angle::XY);
+    mag_sensor
+        .set_config_register(sensor_config_2)
+        .expect("Failed to set Sensor Config Register 2");
+    mag_sensor
+        .set_config_register(TConfigRegister::default())
+        .expect("Failed to set T Config Register");
+
+    let frame = mag_sensor
+        .get_raw_frame_data()
+        .expect("Failed to get frame data");
+    assert_eq!((frame.temp, frame.z), (None, None));
+    assert!(frame.x.is_some() && frame.y.is_some());
+    assert!(frame.angle.unwrap().abs_diff(45 * 16) <= 1);
+    assert_eq!(
+        frame.magnitude,
+        Some(mag_sensor.get_magnitude().expect("Failed to get magnitude"))
+    );
+    assert_eq!(frame.device_status.raw_value() & 0x0F, 0x00);
+}
+
+#[test]
+fn test_enabled_data() {
+    let mut simulator = setup_i2c();
+    simulator.set_magnetic_field(10.0, -5.0, 2.5);
+    simulator.set_temperature(30.0);
+
+    let mut mag_sensor = TMag5273::new(simulator, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    let data = mag_sensor
+        .get_enabled_data()
+        .expect("Failed to get enabled data");
+    assert!((data.temp.unwrap() - 30.0).abs() < 0.1);
+    assert!((data.z.unwrap() - 2.5).abs() < 0.01);
+
+    // XY only, without the temperature
+    let sensor_config_1: SensorConfig1Register = mag_sensor
+        .get_config_register::<SensorConfig1Register>()
+        .expect("Failed to get Sensor Config Register 1")
+        .with_mag_channel(MagneticChannel::XY);
+    mag_sensor
+        .set_config_register(sensor_config_1)
+        .expect("Failed to set Sensor Config Register 1");
+    mag_sensor
+        .set_config_register(TConfigRegister::default())
+        .expect("Failed to set T Config Register");
+    assert_eq!(
+        mag_sensor.get_all_data(),
+        Err(TMag5273Error::ChannelDisabled)
+    );
+    let data = mag_sensor
+        .get_enabled_data()
+        .expect("Failed to get enabled data");
+    assert_eq!((data.temp, data.z), (None, None));
+    assert!((data.x.unwrap() - 10.0).abs() < 0.01);
+    assert!((data.y.unwrap() + 5.0).abs() < 0.01);
+
+    // The pseudo-simultaneous modes report each axis once
+    mag_sensor
+        .set_config_register(sensor_config_1.with_mag_channel(MagneticChannel::YZY))
+        .expect("Failed to set Sensor Config Register 1");
+    let data = mag_sensor
+        .get_raw_enabled_data()
+        .expect("Failed to get enabled data");
+    assert_eq!((data.temp, data.x), (None, None));
+    assert!((data.z.unwrap().microtesla() - 2500).abs() < 20);
+    assert!((data.y.unwrap().microtesla() + 5000).abs() < 20);
+
+    // Nothing to read
+    mag_sensor
+        .set_config_register(sensor_config_1.with_mag_channel(MagneticChannel::Default))
+        .expect("Failed to set Sensor Config Register 1");
+    assert_eq!(
+        mag_sensor.get_raw_enabled_data(),
+        Err(TMag5273Error::ChannelDisabled)
+    );
+}
+
+#[test]
+fn test_temperature_compensation() {
+    let mut simulator = setup_i2c();
+    simulator.set_magnetic_field(10.0, -5.0, 2.5);
+    simulator.set_temperature(125.0);
+    let mut mag_sensor = TMag5273::new(simulator, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+
+    // SmCo loses 3.5% of its field from 25 to 125 degC
+    let compensation =
+        TemperatureCompensation::new(MagnetTemperatureModel::Linear(-0.035), 25.0).unwrap();
+    let data = mag_sensor
+        .get_compensated_data(&compensation)
+        .expect("Failed to get compensated data");
+    assert!((data.x - 10.0 / 0.965).abs() < 0.01);
+    assert!((data.y + 5.0 / 0.965).abs() < 0.01);
+    assert!((data.z - 2.5 / 0.965).abs() < 0.01);
+    assert!((data.temp - 125.0).abs() < 0.1);
+
+    // Interpolated between the entries either side of the temperature
+    let table = [(-40.0, 1.02), (25.0, 1.0), (125.0, 0.95)];
+    let compensation = TemperatureCompensation::new(MagnetTemperatureModel::Table(&table), 25.0)
+        .expect("Failed to create compensation");
+    assert!((compensation.field_ratio(75.0) - 0.975).abs() < 1e-6);
+    assert!((compensation.field_ratio(170.0) - 0.95).abs() < 1e-6);
+    assert!((compensation.compensate_field(0.975, 75.0) - 1.0).abs() < 1e-6);
+    assert_eq!(
+        TemperatureCompensation::new(MagnetTemperatureModel::Table(&table[..1]), 25.0),
+        Err(TMag5273Error::OutOfRange)
+    );
+    assert_eq!(
+        TemperatureCompensation::new(MagnetTemperatureModel::Linear(-1.0), 25.0),
+        Err(TMag5273Error::OutOfRange)
+    );
+
+    // The on-chip compensation is never applied twice
+    let device_config_1: DeviceConfig1Register = mag_sensor
+        .get_config_register::<DeviceConfig1Register>()
+        .expect("Failed to get Device Config Register 1")
+        .with_mag_tempo(MagnetTemperatureCoefficient::Zero12Compensation);
+    mag_sensor
+        .set_config_register(device_config_1)
+        .expect("Failed to set Device Config Register 1");
+    assert_eq!(
+        mag_sensor.get_compensated_data(&compensation),
+        Err(TMag5273Error::CompensationConflict)
+    );
+}
+
+/// Bus which answers every read with fixed bytes while they are set, and otherwise passes the transfers on to the
+/// simulator. It checks the driver against byte sequences which do not come from the simulator.
+struct FixedReadBus<'a> {
+    simulator: &'a RefCell<SimulatedTMag5273>,
+    response: &'a Cell<Option<&'a [u8]>>,
+}
+
+impl ErrorType for FixedReadBus<'_> {
+    type Error = ErrorKind;
+}
+
+impl I2c<SevenBitAddress> for FixedReadBus<'_> {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let Some(response) = self.response.get() else {
+            return self.simulator.borrow_mut().transaction(address, operations);
+        };
+        for operation in operations {
+            if let Operation::Read(buffer) = operation {
+                buffer.copy_from_slice(response);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bus which records the addresses a register address is written to, and passes every transfer on to the simulator.
+struct WriteRecordingBus<'a> {
+    simulator: &'a RefCell<SimulatedTMag5273>,
+    written: Vec<SevenBitAddress>,
+}
+
+impl ErrorType for WriteRecordingBus<'_> {
+    type Error = ErrorKind;
+}
+
+impl I2c<SevenBitAddress> for WriteRecordingBus<'_> {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        if operations
+            .iter()
+            .any(|operation| matches!(operation, Operation::Write(data) if !data.is_empty()))
+        {
+            self.written.push(address);
+        }
+        self.simulator.borrow_mut().transaction(address, operations)
+    }
+}
+
+#[test]
+fn test_scan_bus_only_writes_to_acknowledged_addresses() {
+    let simulator = RefCell::new(setup_i2c());
+    let mut i2c = WriteRecordingBus {
+        simulator: &simulator,
+        written: Vec::new(),
+    };
+    assert_eq!(scan_bus(&mut i2c).count(), 1);
+
+    // Every other address is only probed with a plain read
+    assert!(!i2c.written.is_empty());
+    assert!(i2c
+        .written
+        .iter()
+        .all(|address| *address == SENSOR_PART.get_default_address()));
+}
+
+/// Bus which fails the next register read at an address, and passes every other transfer on to the simulator.
+struct DroppedReadBus<'a> {
+    simulator: &'a RefCell<SimulatedTMag5273>,
+    drop_at: &'a Cell<Option<SevenBitAddress>>,
+}
+
+impl ErrorType for DroppedReadBus<'_> {
+    type Error = ErrorKind;
+}
+
+impl I2c<SevenBitAddress> for DroppedReadBus<'_> {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let register_read = matches!(operations, [Operation::Write(_), Operation::Read(_)]);
+        if register_read && self.drop_at.get() == Some(address) {
+            self.drop_at.set(None);
+            return Err(ErrorKind::Bus);
+        }
+        self.simulator.borrow_mut().transaction(address, operations)
+    }
+}
+
+#[test]
+fn test_change_address_moves_back_when_unconfirmed() {
+    let simulator = RefCell::new(setup_i2c());
+    let drop_at = Cell::new(None);
+    let bus = DroppedReadBus {
+        simulator: &simulator,
+        drop_at: &drop_at,
+    };
+    let mut mag_sensor = TMag5273::new(bus, SENSOR_PART).unwrap();
+
+    // The device moves, but the read confirming it is lost, so the device is moved back
+    drop_at.set(Some(0x40));
+    assert_eq!(
+        mag_sensor.change_address(0x40),
+        Err(TMag5273Error::I2c(ErrorKind::Bus))
+    );
+    assert_eq!(
+        simulator.borrow().address(),
+        SENSOR_PART.get_default_address()
+    );
+    assert!(mag_sensor.is_connected());
+}
+
+#[test]
+fn test_raw_x_at_full_scale_matches_float() {
+    let mut simulator = setup_i2c();
+    // Beyond the range, so every axis saturates at the most negative code of its result registers
+    simulator.set_magnetic_field(1000.0, -1000.0, -1000.0);
+
+    let mut mag_sensor = TMag5273::new(simulator, SENSOR_PART)
+        .unwrap()
+        .init_default()
+        .expect("Failed to initialise mag sensor");
+    let raw = mag_sensor
+        .get_raw_all_data()
+        .expect("Failed to get raw data");
+    assert_eq!(raw.x.code, 32768);
+    assert_eq!(raw.y.code, -32768);
+
+    // Once inverted, the X code is the full positive range whichever conversion is used
+    let range = f32::from(raw.x.range_mt);
+    let x = mag_sensor.get_mag_data(Axis::X).expect("Failed to get X");
+    let data = mag_sensor
+        .get_enabled_data()
+        .expect("Failed to get enabled data");
+    assert_eq!(x, range);
+    assert_eq!(data.x, Some(x));
+    assert_eq!(data.y, Some(-range));
+    assert_eq!(raw.x.millitesla() as f32, x);
+}
+
+#[test]
+fn test_crc_datasheet_examples() {
+    let simulator = RefCell::new(setup_i2c());
+    let response = Cell::new(None);
+    let bus = FixedReadBus {
+        simulator: &simulator,
+        response: &response,
+    };
+    let mut mag_sensor = TMag5273::new(bus, SENSOR_PART).unwrap();
+    mag_sensor
+        .set_device_config(DeviceConfig {
+            i2c_crc_enabled: true,
+            ..Default::default()
+        })
+        .expect("Failed to enable I2C CRC");
+
+    // The standard 3-byte read sends the CRC of the 4 register bytes, examples from the I2C Read CRC section
+    response.set(Some(&[0x00, 0x00, 0x00, 0x00, 0xD1]));
+    assert_eq!(mag_sensor.get_magnitude(), Ok(0x00));
+    response.set(Some(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]));
+    assert_eq!(mag_sensor.get_magnitude(), Ok(0xFF));
+    response.set(Some(&[0x00, 0x00, 0x00, 0x00, 0x0F]));
+    assert_eq!(mag_sensor.get_magnitude(), Err(TMag5273Error::CrcMismatch));
+
+    // The 1-byte reads send the CRC of the command byte, 0x45 for the address 0x22, and the data. The CRC bytes
+    // are worked out with the equations of the datasheet
+    response.set(None);
+    mag_sensor
+        .set_config_register(SensorConfig1Register::default().with_mag_channel(MagneticChannel::Z))
+        .expect("Failed to set Sensor Config Register 1");
+    mag_sensor
+        .set_device_config(DeviceConfig {
+            i2c_read_mode: I2cReadMode::TwoByte8Bit,
+            i2c_crc_enabled: true,
+            ..Default::default()
+        })
+        .expect("Failed to set the I2C read mode");
+    response.set(Some(&[0x12, 0x01, 0x17]));
+    let data = mag_sensor
+        .get_fast_read_data_raw()
+        .expect("Failed to get fast read data");
+    assert_eq!(data.z.map(|z| z.code), Some(0x1200));
+    assert!(data.x.is_none() && data.temp.is_none());
+    assert!(data.conversion_status.conversion_ready());
+
+    response.set(None);
+    mag_sensor
+        .set_config_register(TConfigRegister::default().with_temperature_channel_enabled(true))
+        .expect("Failed to set T Config Register");
+    mag_sensor
+        .set_device_config(DeviceConfig {
+            i2c_read_mode: I2cReadMode::OneByte16Bit,
+            i2c_crc_enabled: true,
+            ..Default::default()
+        })
+        .expect("Failed to set the I2C read mode");
+    response.set(Some(&[0x44, 0x64, 0xF0, 0x00, 0x01, 0x75]));
+    let data = mag_sensor
+        .get_fast_read_data_raw()
+        .expect("Failed to get fast read data");
+    assert_eq!(data.temp.map(|temp| temp.code), Some(17508));
+    assert_eq!(data.z.map(|z| z.code), Some(-4096));
+    response.set(Some(&[0x44, 0x64, 0xF0, 0x00, 0x01, 0x74]));
+    assert_eq!(
+        mag_sensor.get_fast_read_data_raw(),
+        Err(TMag5273Error::CrcMismatch)
+    );
+}
+
+#[test]
+fn test_simulated_crc_byte_sequences() {
+    let mut simulator = setup_i2c();
+    let address = SENSOR_PART.get_default_address();
+    simulator
+        .write(address, &[TMAG5273Register::DeviceConfig1.into(), 0x80])
+        .expect("Failed to enable I2C CRC");
+
+    // The standard 3-byte read sends the CRC of the 4 register bytes, examples from the I2C Read CRC section
+    let mut frame = [0x00; 5];
+    simulator
+        .write_read(address, &[TMAG5273Register::IntConfig1.into()], &mut frame)
+        .expect("Failed to read registers");
+    assert_eq!(frame, [0x00, 0x00, 0x00, 0x00, 0xD1]);
+    simulator
+        .write(
+            address,
+            &[TMAG5273Register::XThrConfig.into(), 0xFF, 0xFF, 0xFF, 0xFF],
+        )
+        .expect("Failed to write registers");
+    simulator
+        .write_read(address, &[TMAG5273Register::XThrConfig.into()], &mut frame)
+        .expect("Failed to read registers");
+    assert_eq!(frame, [0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+
+    // The 1-byte reads send the CRC of the command byte, 0x45 for the address 0x22, and the data. The CRC bytes
+    // are worked out with the equations of the datasheet
+    simulator
+        .write(address, &[TMAG5273Register::TConfig.into(), 0x00])
+        .expect("Failed to disable the temperature channel");
+    simulator
+        .write(address, &[TMAG5273Register::SensorConfig1.into(), 0x40])
+        .expect("Failed to enable the Z channel");
+    simulator
+        .write(address, &[TMAG5273Register::DeviceConfig1.into(), 0x82])
+        .expect("Failed to set the 8 bit read mode");
+    // A code of 0x1200 in the 40 mT range, from the first conversion since the POR
+    simulator.set_magnetic_field(0.0, 0.0, 5.625);
+    simulator.convert();
+    let mut packet = [0x00; 3];
+    simulator
+        .read(address, &mut packet)
+        .expect("Failed to read");
+    assert_eq!(packet, [0x12, 0x31, 0x87]);
+
+    simulator
+        .write(address, &[TMAG5273Register::TConfig.into(), 0x01])
+        .expect("Failed to enable the temperature channel");
+    simulator
+        .write(address, &[TMAG5273Register::DeviceConfig1.into(), 0x81])
+        .expect("Failed to set the 16 bit read mode");
+    // 17508 at 25 degC and a code of -4096, from the second conversion
+    simulator.set_magnetic_field(0.0, 0.0, -5.0);
+    simulator.convert();
+    let mut packet = [0x00; 6];
+    simulator
+        .read(address, &mut packet)
+        .expect("Failed to read");
+    assert_eq!(packet, [0x44, 0x64, 0xF0, 0x00, 0x51, 0xC2]);
+}
@@ -14,6 +14,14 @@ mod util_imports {
 
 use util_imports::*;
 
+#[cfg(feature = "rpi")]
+/// Blocking delay for the Raspberry Pi
+pub use rppal::hal::Delay;
+
+#[cfg(not(feature = "rpi"))]
+/// Blocking delay for use alongside the FTDI Interface
+pub use ftdi_embedded_hal::Delay;
+
 #[cfg(feature = "rpi")]
 /// Set up the I2C bus for the Raspberry Pi
 pub fn setup_i2c() -> Result<PiI2c::I2c, Box<dyn Error>> {